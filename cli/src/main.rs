@@ -6,10 +6,11 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process;
 
-use clap::{ArgAction, Parser, Subcommand};
+use chrono::{DateTime, Datelike, Local};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term::{self, termcolor};
-use comemo::Prehashed;
+use comemo::{Prehashed, Track};
 use elsa::FrozenVec;
 use memmap2::Mmap;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
@@ -17,10 +18,14 @@ use once_cell::unsync::OnceCell;
 use same_file::{is_same_file, Handle};
 use siphasher::sip128::{Hasher128, SipHasher};
 use termcolor::{ColorChoice, StandardStream, WriteColor};
-use typst::diag::{FileError, FileResult, SourceError, StrResult};
-use typst::eval::Library;
+use typst::diag::{FileError, FileResult, Severity, SourceError, StrResult};
+use typst::doc::{Document, Frame};
+use typst::eval::{Datetime, Library, Tracer, Value};
+use typst::export::PdfStandard;
 use typst::font::{Font, FontBook, FontInfo, FontVariant};
-use typst::syntax::{Source, SourceId};
+use typst::geom::Color;
+use typst::model::{Content, Introspector, Locatable, Selector};
+use typst::syntax::{Source, SourceId, Span};
 use typst::util::{Buffer, PathExt};
 use typst::World;
 use walkdir::WalkDir;
@@ -30,7 +35,7 @@ type CodespanError = codespan_reporting::files::Error;
 
 const TYPST_VERSION: &str = env!("TYPST_VERSION");
 
-/// typst creates PDF files from .typ files
+/// typst creates PDF, PNG and SVG files from .typ files
 #[derive(Debug, Clone, Parser)]
 #[clap(name = "typst", version = TYPST_VERSION, author)]
 pub struct CliArguments {
@@ -42,6 +47,20 @@ pub struct CliArguments {
     #[clap(long = "root", value_name = "DIR")]
     root: Option<PathBuf>,
 
+    /// Configure the directory in which packages are stored (defaults to
+    /// `typst/packages` in the user's data directory)
+    #[clap(long = "package-path", value_name = "DIR")]
+    package_path: Option<PathBuf>,
+
+    /// Add a string key-value pair visible to documents through `sys.inputs`
+    #[clap(
+        long = "input",
+        value_name = "KEY=VALUE",
+        action = ArgAction::Append,
+        value_parser = parse_input,
+    )]
+    inputs: Vec<(String, String)>,
+
     /// The typst command to run
     #[command(subcommand)]
     command: Command,
@@ -51,7 +70,7 @@ pub struct CliArguments {
 #[derive(Debug, Clone, Subcommand)]
 #[command()]
 enum Command {
-    /// Compiles the input file into a PDF file
+    /// Compiles the input file into a PDF, PNG, SVG or HTML file
     #[command(visible_alias = "c")]
     Compile(CompileCommand),
 
@@ -59,18 +78,34 @@ enum Command {
     #[command(visible_alias = "w")]
     Watch(WatchCommand),
 
+    /// Processes an input file to extract provided metadata
+    Query(QueryCommand),
+
     /// List all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 }
 
-/// Compiles the input file into a PDF file
+/// Compiles the input file into a PDF, PNG, SVG or HTML file
 #[derive(Debug, Clone, Parser)]
 pub struct CompileCommand {
     /// Path to input Typst file
     input: PathBuf,
 
-    /// Path to output PDF file
+    /// Path to output file (PDF, HTML, or PNG/SVG with `{n}` as a placeholder
+    /// for the page number)
     output: Option<PathBuf>,
+
+    /// The resolution of PNG output in pixels per inch
+    #[arg(long = "ppi", default_value_t = 144.0, value_parser = parse_ppi)]
+    ppi: f32,
+
+    /// The pages to export as PNG or SVG, e.g. `2-5` (defaults to all pages)
+    #[arg(long = "pages", value_name = "RANGE", value_parser = parse_page_range)]
+    pages: Option<PageRange>,
+
+    /// Produce a PDF/A-2b conforming file for archiving
+    #[arg(long = "pdf-a")]
+    pdf_a: bool,
 }
 
 /// Watches the input file and recompiles on changes
@@ -79,8 +114,51 @@ pub struct WatchCommand {
     /// Path to input Typst file
     input: PathBuf,
 
-    /// Path to output PDF file
+    /// Path to output file (PDF, HTML, or PNG/SVG with `{n}` as a placeholder
+    /// for the page number)
     output: Option<PathBuf>,
+
+    /// The resolution of PNG output in pixels per inch
+    #[arg(long = "ppi", default_value_t = 144.0, value_parser = parse_ppi)]
+    ppi: f32,
+
+    /// The pages to export as PNG or SVG, e.g. `2-5` (defaults to all pages)
+    #[arg(long = "pages", value_name = "RANGE", value_parser = parse_page_range)]
+    pages: Option<PageRange>,
+
+    /// Produce a PDF/A-2b conforming file for archiving
+    #[arg(long = "pdf-a")]
+    pdf_a: bool,
+}
+
+/// Processes an input file to extract provided metadata
+#[derive(Debug, Clone, Parser)]
+pub struct QueryCommand {
+    /// Path to input Typst file
+    input: PathBuf,
+
+    /// Defines which elements to retrieve, e.g. `heading`, `<label>` or
+    /// `figure.where(kind: image)`
+    selector: String,
+
+    /// Extracts just one field from all retrieved elements
+    #[arg(long = "field")]
+    field: Option<String>,
+
+    /// Expects and retrieves exactly one element
+    #[arg(long = "one")]
+    one: bool,
+
+    /// The format to serialize in
+    #[arg(long = "format", value_enum, default_value_t = SerializationFormat::Json)]
+    format: SerializationFormat,
+}
+
+/// A format to serialize query results in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum SerializationFormat {
+    Json,
+    Yaml,
 }
 
 /// List all discovered fonts in system and custom font paths
@@ -91,6 +169,76 @@ pub struct FontsCommand {
     variants: bool,
 }
 
+/// A range of pages, starting at 1. Both ends are inclusive and optional.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PageRange {
+    start: Option<usize>,
+    end: Option<usize>,
+}
+
+impl PageRange {
+    /// Whether the range contains the page with the given number.
+    fn contains(&self, page: usize) -> bool {
+        self.start.map_or(true, |start| start <= page)
+            && self.end.map_or(true, |end| page <= end)
+    }
+}
+
+/// Parse a page range like `3`, `2-5`, `4-` or `-3`.
+fn parse_page_range(value: &str) -> Result<PageRange, String> {
+    let number = |part: &str| -> Result<Option<usize>, String> {
+        let part = part.trim();
+        if part.is_empty() {
+            return Ok(None);
+        }
+        match part.parse::<usize>() {
+            Ok(0) => Err("page numbers start at 1".into()),
+            Ok(n) => Ok(Some(n)),
+            Err(_) => Err(format!("invalid page number: {part}")),
+        }
+    };
+
+    let range = match value.split_once('-') {
+        Some((start, end)) => PageRange { start: number(start)?, end: number(end)? },
+        None => {
+            let page = number(value)?.ok_or("page range must not be empty")?;
+            PageRange { start: Some(page), end: Some(page) }
+        }
+    };
+
+    if let (Some(start), Some(end)) = (range.start, range.end) {
+        if start > end {
+            return Err("page range must not be descending".into());
+        }
+    }
+
+    Ok(range)
+}
+
+/// Parse a resolution in pixels per inch.
+fn parse_ppi(value: &str) -> Result<f32, String> {
+    let ppi = value
+        .trim()
+        .parse::<f32>()
+        .map_err(|_| format!("invalid resolution: {value}"))?;
+    if !ppi.is_finite() || ppi <= 0.0 {
+        return Err("resolution must be positive".into());
+    }
+    Ok(ppi)
+}
+
+/// Parse an input like `key=value`.
+fn parse_input(value: &str) -> Result<(String, String), String> {
+    let (key, value) = value
+        .split_once('=')
+        .ok_or("input must be a key and a value separated by an equals sign")?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("input key must not be empty".into());
+    }
+    Ok((key.into(), value.into()))
+}
+
 /// A summary of the input arguments relevant to compilation.
 struct CompileSettings {
     /// The path to the input file.
@@ -105,25 +253,57 @@ struct CompileSettings {
     /// The root directory for absolute paths.
     root: Option<PathBuf>,
 
+    /// The directory in which packages are stored.
+    package_path: Option<PathBuf>,
+
+    /// The key-value pairs available through `sys.inputs`.
+    inputs: Vec<(String, String)>,
+
     /// The paths to search for fonts.
     font_paths: Vec<PathBuf>,
+
+    /// The resolution of PNG output in pixels per inch.
+    ppi: f32,
+
+    /// The pages to export as images.
+    pages: Option<PageRange>,
+
+    /// The standard exported PDFs conform to.
+    standard: PdfStandard,
 }
 
 impl CompileSettings {
     /// Create a new compile settings from the field values.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         input: PathBuf,
         output: Option<PathBuf>,
         watch: bool,
         root: Option<PathBuf>,
+        package_path: Option<PathBuf>,
+        inputs: Vec<(String, String)>,
         font_paths: Vec<PathBuf>,
+        ppi: f32,
+        pages: Option<PageRange>,
+        standard: PdfStandard,
     ) -> Self {
         let output = match output {
             Some(path) => path,
             None => input.with_extension("pdf"),
         };
 
-        Self { input, output, watch, root, font_paths }
+        Self {
+            input,
+            output,
+            watch,
+            root,
+            package_path,
+            inputs,
+            font_paths,
+            ppi,
+            pages,
+            standard,
+        }
     }
 
     /// Create a new compile settings from the CLI arguments and a compile command.
@@ -131,12 +311,91 @@ impl CompileSettings {
     /// # Panics
     /// Panics if the command is not a compile or watch command.
     pub fn with_arguments(args: CliArguments) -> Self {
-        let (input, output, watch) = match args.command {
-            Command::Compile(command) => (command.input, command.output, false),
-            Command::Watch(command) => (command.input, command.output, true),
+        let (input, output, watch, ppi, pages, pdf_a) = match args.command {
+            Command::Compile(command) => (
+                command.input,
+                command.output,
+                false,
+                command.ppi,
+                command.pages,
+                command.pdf_a,
+            ),
+            Command::Watch(command) => (
+                command.input,
+                command.output,
+                true,
+                command.ppi,
+                command.pages,
+                command.pdf_a,
+            ),
             _ => unreachable!(),
         };
-        Self::new(input, output, watch, args.root, args.font_paths)
+        let standard = if pdf_a { PdfStandard::A2b } else { PdfStandard::V1_7 };
+        Self::new(
+            input,
+            output,
+            watch,
+            args.root,
+            args.package_path,
+            args.inputs,
+            args.font_paths,
+            ppi,
+            pages,
+            standard,
+        )
+    }
+}
+
+/// A summary of the input arguments relevant to querying.
+struct QuerySettings {
+    /// The path to the input file.
+    input: PathBuf,
+
+    /// The selector to match elements with.
+    selector: String,
+
+    /// The field to extract from the matched elements.
+    field: Option<String>,
+
+    /// Whether exactly one element is expected.
+    one: bool,
+
+    /// The format to serialize the results in.
+    format: SerializationFormat,
+
+    /// The root directory for absolute paths.
+    root: Option<PathBuf>,
+
+    /// The directory in which packages are stored.
+    package_path: Option<PathBuf>,
+
+    /// The key-value pairs available through `sys.inputs`.
+    inputs: Vec<(String, String)>,
+
+    /// The paths to search for fonts.
+    font_paths: Vec<PathBuf>,
+}
+
+impl QuerySettings {
+    /// Create a new query settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a query command.
+    pub fn with_arguments(args: CliArguments) -> Self {
+        match args.command {
+            Command::Query(command) => Self {
+                input: command.input,
+                selector: command.selector,
+                field: command.field,
+                one: command.one,
+                format: command.format,
+                root: args.root,
+                package_path: args.package_path,
+                inputs: args.inputs,
+                font_paths: args.font_paths,
+            },
+            _ => unreachable!(),
+        }
     }
 }
 
@@ -174,6 +433,7 @@ fn main() {
         Command::Compile(_) | Command::Watch(_) => {
             compile(CompileSettings::with_arguments(arguments))
         }
+        Command::Query(_) => query(QuerySettings::with_arguments(arguments)),
         Command::Fonts(_) => fonts(FontsSettings::with_arguments(arguments)),
     };
 
@@ -196,22 +456,11 @@ fn print_error(msg: &str) -> io::Result<()> {
 
 /// Execute a compilation command.
 fn compile(command: CompileSettings) -> StrResult<()> {
-    let root = if let Some(root) = &command.root {
-        root.clone()
-    } else if let Some(dir) = command
-        .input
-        .canonicalize()
-        .ok()
-        .as_ref()
-        .and_then(|path| path.parent())
-    {
-        dir.into()
-    } else {
-        PathBuf::new()
-    };
-
     // Create the world that serves sources, fonts and files.
-    let mut world = SystemWorld::new(root, &command.font_paths);
+    let root = root_dir(&command.input, command.root.as_ref());
+    let packages = package_dir(command.package_path.as_ref());
+    let mut world =
+        SystemWorld::new(root, packages, &command.inputs, &command.font_paths);
 
     // Perform initial compilation.
     let failed = compile_once(&mut world, &command)?;
@@ -262,6 +511,28 @@ fn compile(command: CompileSettings) -> StrResult<()> {
     }
 }
 
+/// Determine the root for absolute paths: The configured one or else the
+/// directory of the input file.
+fn root_dir(input: &Path, root: Option<&PathBuf>) -> PathBuf {
+    if let Some(root) = root {
+        root.clone()
+    } else if let Some(dir) =
+        input.canonicalize().ok().as_ref().and_then(|path| path.parent())
+    {
+        dir.into()
+    } else {
+        PathBuf::new()
+    }
+}
+
+/// Determine the package directory: The configured one or else
+/// `typst/packages` in the user's data directory.
+fn package_dir(package_path: Option<&PathBuf>) -> Option<PathBuf> {
+    package_path
+        .cloned()
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("typst").join("packages")))
+}
+
 /// Compile a single time.
 fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult<bool> {
     status(command, Status::Compiling).unwrap();
@@ -269,25 +540,136 @@ fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult
     world.reset();
     world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
 
-    match typst::compile(world) {
-        // Export the PDF.
-        Ok(document) => {
-            let buffer = typst::export::pdf(&document);
-            fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
-            status(command, Status::Success).unwrap();
+    // HTML is exported from the content tree rather than from the pages.
+    let mut tracer = Tracer::default();
+    let result = if has_extension(&command.output, "html") {
+        typst::compile_html(world, &mut tracer).map(|html| export_html(&html, command))
+    } else {
+        typst::compile(world, &mut tracer).map(|document| export(&document, command))
+    };
+
+    let warnings = tracer.warnings().to_vec();
+    match result {
+        // Export the PDF, images or HTML and print warnings.
+        Ok(exported) => {
+            exported?;
+            if warnings.is_empty() {
+                status(command, Status::Success).unwrap();
+            } else {
+                status(command, Status::PartialSuccess).unwrap();
+            }
+            print_diagnostics(world, warnings)
+                .map_err(|_| "failed to print diagnostics")?;
             Ok(false)
         }
 
         // Print diagnostics.
         Err(errors) => {
             status(command, Status::Error).unwrap();
-            print_diagnostics(world, *errors)
+            let mut diagnostics = *errors;
+            diagnostics.extend(warnings);
+            print_diagnostics(world, diagnostics)
                 .map_err(|_| "failed to print diagnostics")?;
             Ok(true)
         }
     }
 }
 
+/// Export into the target format, which is determined by the output path's
+/// file extension.
+fn export(document: &Document, command: &CompileSettings) -> StrResult<()> {
+    if has_extension(&command.output, "png") {
+        export_images(document, command, ImageExportFormat::Png)
+    } else if has_extension(&command.output, "svg") {
+        export_images(document, command, ImageExportFormat::Svg)
+    } else {
+        let buffer = typst::export::pdf(document, command.standard);
+        fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
+        Ok(())
+    }
+}
+
+/// Write an exported HTML document to the output path.
+fn export_html(html: &str, command: &CompileSettings) -> StrResult<()> {
+    fs::write(&command.output, html).map_err(|_| "failed to write HTML file")?;
+    Ok(())
+}
+
+/// Whether the path has the given file extension, ignoring case.
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case(extension))
+}
+
+/// An image format to export pages into.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ImageExportFormat {
+    Png,
+    Svg,
+}
+
+/// Export the selected pages into one image file each.
+fn export_images(
+    document: &Document,
+    command: &CompileSettings,
+    format: ImageExportFormat,
+) -> StrResult<()> {
+    let pages: Vec<_> = document
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| (i + 1, frame))
+        .filter(|&(page, _)| command.pages.map_or(true, |range| range.contains(page)))
+        .collect();
+
+    if pages.is_empty() {
+        Err("the selected page range does not contain any pages")?;
+    }
+
+    let string = command.output.to_str().unwrap_or_default();
+    let numbered = string.contains("{n}");
+    if !numbered && pages.len() > 1 {
+        Err("cannot export multiple images without `{n}` in output path")?;
+    }
+
+    // Pad the page numbers so that the files sort correctly.
+    let width = 1 + document.pages.len().checked_ilog10().unwrap_or(0) as usize;
+    for (page, frame) in pages {
+        let path = if numbered {
+            PathBuf::from(string.replace("{n}", &format!("{page:0width$}")))
+        } else {
+            command.output.clone()
+        };
+
+        match format {
+            ImageExportFormat::Png => {
+                let pixel_per_pt = command.ppi / 72.0;
+                if !fits_pixmap(frame, pixel_per_pt) {
+                    Err("page is too large to render at this resolution")?;
+                }
+                let pixmap = typst::export::render(frame, pixel_per_pt, Color::WHITE);
+                pixmap.save_png(path).map_err(|_| "failed to write PNG file")?;
+            }
+            ImageExportFormat::Svg => {
+                let svg = typst::export::svg(frame);
+                fs::write(path, svg).map_err(|_| "failed to write SVG file")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a frame rendered at the given resolution stays within the size
+/// limits of a pixel buffer.
+fn fits_pixmap(frame: &Frame, pixel_per_pt: f32) -> bool {
+    let size = frame.size();
+    let pxw = (f64::from(pixel_per_pt) * size.x.to_pt()).round().max(1.0);
+    let pxh = (f64::from(pixel_per_pt) * size.y.to_pt()).round().max(1.0);
+    // Each pixel takes four bytes and the whole buffer must be addressable
+    // with an `i32`.
+    pxw * pxh * 4.0 <= i32::MAX as f64
+}
+
 /// Clear the terminal and render the status message.
 fn status(command: &CompileSettings, status: Status) -> io::Result<()> {
     if !command.watch {
@@ -326,6 +708,7 @@ fn status(command: &CompileSettings, status: Status) -> io::Result<()> {
 enum Status {
     Compiling,
     Success,
+    PartialSuccess,
     Error,
 }
 
@@ -334,6 +717,7 @@ impl Status {
         match self {
             Self::Compiling => "compiling ...",
             Self::Success => "compiled successfully",
+            Self::PartialSuccess => "compiled with warnings",
             Self::Error => "compiled with errors",
         }
     }
@@ -342,6 +726,7 @@ impl Status {
         let styles = term::Styles::default();
         match self {
             Self::Error => styles.header_error,
+            Self::PartialSuccess => styles.header_warning,
             _ => styles.header_note,
         }
     }
@@ -358,7 +743,13 @@ fn print_diagnostics(
     for error in errors {
         // The main diagnostic.
         let range = error.range(world);
-        let diag = Diagnostic::error()
+        let diag = match error.severity {
+            Severity::Error => Diagnostic::error(),
+            Severity::Warning => Diagnostic::warning(),
+            Severity::Hint => Diagnostic::help(),
+        };
+
+        let diag = diag
             .with_message(error.message)
             .with_labels(vec![Label::primary(error.span.source(), range)]);
 
@@ -381,6 +772,99 @@ fn print_diagnostics(
     Ok(())
 }
 
+/// Execute a query command.
+fn query(command: QuerySettings) -> StrResult<()> {
+    let root = root_dir(&command.input, command.root.as_ref());
+    let packages = package_dir(command.package_path.as_ref());
+    let mut world =
+        SystemWorld::new(root, packages, &command.inputs, &command.font_paths);
+    world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
+
+    let mut tracer = Tracer::default();
+    let result = typst::compile(&world, &mut tracer);
+    let warnings = tracer.warnings().to_vec();
+    match result {
+        // Retrieve and serialize the matching elements.
+        Ok(document) => {
+            let data = retrieve(&world, &command, &document)?;
+            let serialized = serialize(data, &command)?;
+            println!("{serialized}");
+            print_diagnostics(&world, warnings)
+                .map_err(|_| "failed to print diagnostics")?;
+        }
+
+        // Print diagnostics.
+        Err(errors) => {
+            let mut diagnostics = *errors;
+            diagnostics.extend(warnings);
+            print_diagnostics(&world, diagnostics)
+                .map_err(|_| "failed to print diagnostics")?;
+            process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Retrieve the elements matching the query's selector from the document,
+/// or just the requested field of each of them.
+fn retrieve(
+    world: &SystemWorld,
+    command: &QuerySettings,
+    document: &Document,
+) -> StrResult<Vec<Value>> {
+    let selector = typst::eval::eval_string(
+        (world as &dyn World).track(),
+        &command.selector,
+        Span::detached(),
+    )
+    .map_err(|errors| {
+        let mut message = String::from("failed to evaluate selector");
+        for (i, error) in errors.into_iter().enumerate() {
+            message.push_str(if i == 0 { ": " } else { ", " });
+            message.push_str(&error.message);
+        }
+        message
+    })?
+    .cast::<Selector>()?;
+
+    // Only locatable elements are known to the introspector.
+    if let Selector::Elem(func, _) = &selector {
+        if !Content::new(*func).can::<dyn Locatable>() {
+            return Err(format!("cannot query for {}s", func.name()).into());
+        }
+    } else if let Selector::Regex(_) = &selector {
+        return Err("cannot query for text".into());
+    }
+
+    let introspector = Introspector::new(&document.pages);
+    let elements = introspector.query(selector);
+    Ok(match &command.field {
+        Some(field) => elements.iter().filter_map(|elem| elem.field(field)).collect(),
+        None => elements.into_iter().map(Value::Content).collect(),
+    })
+}
+
+/// Serialize the retrieved data in the requested format.
+fn serialize(data: Vec<Value>, command: &QuerySettings) -> StrResult<String> {
+    let value = if command.one {
+        let len = data.len();
+        let Ok([item]) = <[Value; 1]>::try_from(data) else {
+            return Err(format!("expected exactly one element, found {len}").into());
+        };
+        item
+    } else {
+        Value::Array(data.into_iter().collect())
+    };
+
+    match command.format {
+        SerializationFormat::Json => serde_json::to_string_pretty(&value)
+            .map_err(|err| format!("failed to serialize to json ({err})").into()),
+        SerializationFormat::Yaml => serde_yaml::to_string(&value)
+            .map_err(|err| format!("failed to serialize to yaml ({err})").into()),
+    }
+}
+
 /// Execute a font listing command.
 fn fonts(command: FontsSettings) -> StrResult<()> {
     let mut searcher = FontSearcher::new();
@@ -404,12 +888,14 @@ fn fonts(command: FontsSettings) -> StrResult<()> {
 /// A world that provides access to the operating system.
 struct SystemWorld {
     root: PathBuf,
+    packages: Option<PathBuf>,
     library: Prehashed<Library>,
     book: Prehashed<FontBook>,
     fonts: Vec<FontSlot>,
     hashes: RefCell<HashMap<PathBuf, FileResult<PathHash>>>,
     paths: RefCell<HashMap<PathHash, PathSlot>>,
     sources: FrozenVec<Box<Source>>,
+    today: OnceCell<DateTime<Local>>,
     main: SourceId,
 }
 
@@ -428,7 +914,12 @@ struct PathSlot {
 }
 
 impl SystemWorld {
-    fn new(root: PathBuf, font_paths: &[PathBuf]) -> Self {
+    fn new(
+        root: PathBuf,
+        packages: Option<PathBuf>,
+        inputs: &[(String, String)],
+        font_paths: &[PathBuf],
+    ) -> Self {
         let mut searcher = FontSearcher::new();
         searcher.search_system();
 
@@ -439,14 +930,21 @@ impl SystemWorld {
             searcher.search_dir(path)
         }
 
+        let inputs = inputs
+            .iter()
+            .map(|(key, value)| (key.as_str().into(), Value::Str(value.as_str().into())))
+            .collect();
+
         Self {
             root,
-            library: Prehashed::new(typst_library::build()),
+            packages,
+            library: Prehashed::new(typst_library::build_with_inputs(inputs)),
             book: Prehashed::new(searcher.book),
             fonts: searcher.fonts,
             hashes: RefCell::default(),
             paths: RefCell::default(),
             sources: FrozenVec::new(),
+            today: OnceCell::new(),
             main: SourceId::detached(),
         }
     }
@@ -500,6 +998,21 @@ impl World for SystemWorld {
             .get_or_init(|| read(path).map(Buffer::from))
             .clone()
     }
+
+    fn packages(&self) -> Option<&Path> {
+        self.packages.as_deref()
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        // The time is fixed on first access so that it stays the same
+        // throughout a compilation.
+        let now = self.today.get_or_init(Local::now);
+        let naive = match offset {
+            None => now.naive_local(),
+            Some(offset) => now.naive_utc() + chrono::Duration::hours(offset),
+        };
+        Datetime::from_ymd(naive.year(), naive.month() as u8, naive.day() as u8)
+    }
 }
 
 impl SystemWorld {
@@ -558,6 +1071,7 @@ impl SystemWorld {
         self.sources.as_mut().clear();
         self.hashes.borrow_mut().clear();
         self.paths.borrow_mut().clear();
+        self.today.take();
     }
 }
 
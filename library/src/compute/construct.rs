@@ -1,7 +1,7 @@
 use std::num::NonZeroI64;
 use std::str::FromStr;
 
-use typst::eval::Regex;
+use typst::eval::{Datetime, Duration, Regex};
 
 use crate::prelude::*;
 
@@ -223,6 +223,222 @@ cast_from_value! {
     },
 }
 
+/// Create a new datetime.
+///
+/// A datetime is a calendar date, optionally with a time of day. It can be
+/// [displayed]($type/datetime.display) with a custom pattern, compared with
+/// other datetimes, and moved forward or backward by adding or subtracting a
+/// [duration]($func/duration).
+///
+/// ## Example
+/// ```example
+/// #let date = datetime(
+///   year: 2012,
+///   month: 8,
+///   day: 3,
+/// )
+///
+/// #date.display() \
+/// #date.display(
+///   "[day].[month].[year]"
+/// ) \
+/// #date.display(
+///   "[weekday], [month repr:long] [day padding:none]"
+/// )
+/// ```
+///
+/// Display: Datetime
+/// Category: construct
+/// Returns: datetime
+#[func]
+pub fn datetime(
+    /// The year of the datetime.
+    #[named]
+    year: Option<YearComponent>,
+    /// The month of the datetime.
+    #[named]
+    month: Option<MonthComponent>,
+    /// The day of the datetime.
+    #[named]
+    day: Option<DayComponent>,
+    /// The hour of the datetime.
+    ///
+    /// If any of the time components is given, the datetime has a time of day
+    /// and the missing ones default to zero.
+    #[named]
+    #[default]
+    hour: Option<HourComponent>,
+    /// The minute of the datetime.
+    #[named]
+    #[default]
+    minute: Option<MinuteComponent>,
+    /// The second of the datetime.
+    #[named]
+    #[default]
+    second: Option<SecondComponent>,
+) -> Value {
+    let Some(YearComponent(year)) = year else {
+        bail!(args.span, "missing argument: year");
+    };
+    let Some(MonthComponent(month)) = month else {
+        bail!(args.span, "missing argument: month");
+    };
+    let Some(DayComponent(day)) = day else {
+        bail!(args.span, "missing argument: day");
+    };
+
+    let datetime = if hour.is_none() && minute.is_none() && second.is_none() {
+        Datetime::from_ymd(year, month, day)
+    } else {
+        Datetime::from_ymd_hms(
+            year,
+            month,
+            day,
+            hour.map_or(0, |v| v.0),
+            minute.map_or(0, |v| v.0),
+            second.map_or(0, |v| v.0),
+        )
+    };
+
+    match datetime {
+        Some(datetime) => Value::Datetime(datetime),
+        None => bail!(args.span, "date does not exist"),
+    }
+}
+
+/// A year, which must fit into 32 bits.
+struct YearComponent(i32);
+
+cast_from_value! {
+    YearComponent,
+    v: i64 => Self(i32::try_from(v).map_err(|_| "year is out of range")?),
+}
+
+/// Define a date or time component that must lie in a certain range.
+macro_rules! component {
+    ($name:ident, $range:expr, $message:literal) => {
+        /// A date or time component.
+        struct $name(u8);
+
+        cast_from_value! {
+            $name,
+            v: i64 => if ($range).contains(&v) {
+                Self(v as u8)
+            } else {
+                Err($message)?
+            },
+        }
+    };
+}
+
+component!(MonthComponent, 1..=12, "month must be between 1 and 12");
+component!(DayComponent, 1..=31, "day must be between 1 and 31");
+component!(HourComponent, 0..=23, "hour must be between 0 and 23");
+component!(MinuteComponent, 0..=59, "minute must be between 0 and 59");
+component!(SecondComponent, 0..=59, "second must be between 0 and 59");
+
+/// Create a new duration.
+///
+/// All given components are summed up. A duration can be added to or
+/// subtracted from a [datetime]($func/datetime) and is also the result of
+/// subtracting two datetimes.
+///
+/// ## Example
+/// ```example
+/// #let span = duration(
+///   days: 3,
+///   hours: 12,
+/// )
+///
+/// #span.hours() hours \
+/// #(datetime(year: 2023, month: 1, day: 30) + span).display()
+/// ```
+///
+/// Display: Duration
+/// Category: construct
+/// Returns: duration
+#[func]
+pub fn duration(
+    /// The number of seconds.
+    #[named]
+    #[default(0)]
+    seconds: i64,
+    /// The number of minutes.
+    #[named]
+    #[default(0)]
+    minutes: i64,
+    /// The number of hours.
+    #[named]
+    #[default(0)]
+    hours: i64,
+    /// The number of days.
+    #[named]
+    #[default(0)]
+    days: i64,
+    /// The number of weeks.
+    #[named]
+    #[default(0)]
+    weeks: i64,
+) -> Value {
+    let total =
+        [(seconds, 1), (minutes, 60), (hours, 3600), (days, 86400), (weeks, 604800)]
+            .into_iter()
+            .try_fold(0i64, |sum, (value, factor)| {
+                sum.checked_add(value.checked_mul(factor)?)
+            });
+
+    match total {
+        Some(total) => Value::Duration(Duration::from_seconds(total)),
+        None => bail!(args.span, "duration is out of range"),
+    }
+}
+
+/// Get the current date.
+///
+/// The date is provided by the environment in which Typst runs. The command
+/// line interface uses the system clock and keeps the date fixed for the
+/// duration of a compilation.
+///
+/// ## Example
+/// ```example
+/// Today's date is
+/// #today().display().
+/// ```
+///
+/// Display: Today
+/// Category: construct
+/// Returns: datetime
+#[func]
+pub fn today(
+    /// An offset in hours to apply to the current UTC date. If set to
+    /// `{auto}`, the local date is used instead.
+    #[named]
+    #[default]
+    offset: Smart<UtcOffset>,
+) -> Value {
+    let offset = match offset {
+        Smart::Auto => None,
+        Smart::Custom(UtcOffset(offset)) => Some(offset),
+    };
+
+    match vm.world().today(offset) {
+        Some(datetime) => Value::Datetime(datetime),
+        None => bail!(args.span, "unable to get the current date"),
+    }
+}
+
+/// An offset from UTC in hours.
+struct UtcOffset(i64);
+
+cast_from_value! {
+    UtcOffset,
+    v: i64 => if (-24..=24).contains(&v) {
+        Self(v)
+    } else {
+        Err("offset must be between -24 and 24 hours")?
+    },
+}
+
 /// Create a custom symbol with modifiers.
 ///
 /// ## Example
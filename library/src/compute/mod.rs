@@ -1,6 +1,7 @@
 //! Computational functions.
 
 pub mod calc;
+pub mod sys;
 mod construct;
 mod data;
 mod foundations;
@@ -0,0 +1,15 @@
+//! System-related things.
+
+use typst::eval::{Module, Scope};
+
+use crate::prelude::*;
+
+/// A module with system-related things.
+///
+/// The `inputs` dictionary holds the string values passed to the compiler via
+/// `--input key=value`.
+pub fn module(inputs: Dict) -> Module {
+    let mut scope = Scope::new();
+    scope.define("inputs", inputs);
+    Module::new("sys").with_scope(scope)
+}
@@ -0,0 +1,610 @@
+//! Semantic HTML export.
+
+use std::fmt::Write;
+
+use typst::model::{realize, Guard};
+use typst::util::Escaped;
+
+use crate::layout::{
+    place_cells, AlignElem, BlockElem, BoxElem, ColbreakElem, ColumnsElem,
+    DEFAULT_MARGIN, EnumElem, EnumItem, HElem, HideElem, ListElem, ListItem, MoveElem,
+    PadElem, PageElem, PagebreakElem, Paper, ParbreakElem, PlaceElem, RotateElem,
+    ScaleElem, TableCellElem, TableElem, TableFooterElem, TableHeaderElem, TermItem,
+    TermsElem, VElem,
+};
+use crate::math::EquationElem;
+use crate::meta::{
+    Counter, DocumentElem, FigureElem, HeadingElem, LinkElem, LinkTarget, LocalName,
+};
+use crate::prelude::*;
+use crate::text::{
+    EmphElem, LinebreakElem, Quoter, Quotes, RawElem, SmartQuoteElem, SpaceElem,
+    StrongElem, TextElem,
+};
+
+/// Convert content into a standalone HTML document.
+pub fn html(vt: &mut Vt, content: &Content, styles: StyleChain) -> SourceResult<String> {
+    let mut writer = HtmlWriter::new(vt);
+    writer.accept(content, styles)?;
+    let title = writer.title.take();
+    let lang = writer.lang.take().unwrap_or_else(|| TextElem::lang_in(styles));
+    let body = writer.finish();
+
+    let mut html = String::from("<!DOCTYPE html>\n");
+    writeln!(html, "<html lang=\"{}\">", lang.as_str()).unwrap();
+    html.push_str("<head>\n<meta charset=\"utf-8\">\n");
+    if let Some(title) = title {
+        writeln!(html, "<title>{}</title>", Escaped(&title)).unwrap();
+    }
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&body);
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+/// Builds HTML from content.
+struct HtmlWriter<'a, 'v> {
+    /// The virtual typesetter.
+    vt: &'v mut Vt<'a>,
+    /// The finished block-level HTML.
+    body: String,
+    /// The inline HTML of the paragraph that is currently being built.
+    par: String,
+    /// The list that is currently being built from loose items.
+    list: Option<ListBuilder>,
+    /// Substitutes smart quotes.
+    quoter: Quoter,
+    /// A smart quote waiting for the next character, with the position in the
+    /// paragraph at which it is inserted.
+    quote: Option<(usize, Quotes<'static>, bool)>,
+    /// Whether we are inside of inline-level content.
+    inline: bool,
+    /// The document's title.
+    title: Option<EcoString>,
+    /// The document's language, i.e. the language of its first text.
+    lang: Option<Lang>,
+}
+
+impl<'a, 'v> HtmlWriter<'a, 'v> {
+    /// Create a new writer.
+    fn new(vt: &'v mut Vt<'a>) -> Self {
+        Self {
+            vt,
+            body: String::new(),
+            par: String::new(),
+            list: None,
+            quoter: Quoter::new(),
+            quote: None,
+            inline: false,
+            title: None,
+            lang: None,
+        }
+    }
+
+    /// Convert a piece of content.
+    fn accept(&mut self, content: &Content, styles: StyleChain) -> SourceResult<()> {
+        if let Some(children) = content.to_sequence() {
+            for child in children {
+                self.accept(child, styles)?;
+            }
+            return Ok(());
+        }
+
+        if let Some((elem, local)) = content.to_styled() {
+            let styles = styles.chain(local);
+            if local.interruption::<DocumentElem>().is_some() {
+                if let Some(title) = DocumentElem::title_in(styles) {
+                    self.title = Some(title);
+                }
+            }
+            return self.accept(elem, styles);
+        }
+
+        // Prepare the element and apply user-defined show rules. Built-in
+        // show rules are only applied if there is no semantic conversion.
+        if content.needs_preparation() || has_recipe(content, styles) {
+            if let Some(realized) = realize(self.vt, content, styles)? {
+                return self.accept(&realized, styles);
+            }
+        }
+
+        if self.semantic(content, styles)? {
+            return Ok(());
+        }
+
+        if let Some(realized) = realize(self.vt, content, styles)? {
+            return self.accept(&realized, styles);
+        }
+
+        self.primitive(content, styles)
+    }
+
+    /// Convert an element with a direct HTML equivalent.
+    ///
+    /// Returns whether the element was handled.
+    fn semantic(&mut self, content: &Content, styles: StyleChain) -> SourceResult<bool> {
+        if let Some(elem) = content.to::<HeadingElem>() {
+            let level = elem.level(styles).get().min(6);
+            let mut body = elem.body();
+            if let Some(numbering) = elem.numbering(styles) {
+                body = Counter::of(HeadingElem::func())
+                    .display(Some(numbering), false)
+                    .spanned(elem.span())
+                    + TextElem::packed(" ")
+                    + body;
+            }
+            let body = self.nested(&body, styles)?;
+            let id = Id(content.label());
+            self.push_block(&format!("<h{level}{id}>{body}</h{level}>"));
+        } else if let Some(elem) = content.to::<FigureElem>() {
+            let mut html = format!("<figure{}>\n", Id(content.label()));
+            html.push_str(&self.nested(&elem.body(), styles)?);
+            if let Some(mut caption) = elem.caption(styles) {
+                if let Some(numbering) = elem.numbering(styles) {
+                    let name = elem.local_name(TextElem::lang_in(styles));
+                    caption = TextElem::packed(eco_format!("{name}\u{a0}"))
+                        + Counter::of(FigureElem::func())
+                            .display(Some(numbering), false)
+                            .spanned(elem.span())
+                        + TextElem::packed(": ")
+                        + caption;
+                }
+                let caption = self.nested(&caption, styles)?;
+                write!(html, "\n<figcaption>{caption}</figcaption>").unwrap();
+            }
+            html.push_str("\n</figure>");
+            self.push_block(&html);
+        } else if let Some(elem) = content.to::<LinkElem>() {
+            let href = match elem.dest() {
+                LinkTarget::Dest(Destination::Url(url)) => Some(url),
+                LinkTarget::Label(label) => Some(eco_format!("#{}", label.0)),
+                LinkTarget::Dest(_) => None,
+            };
+            match href {
+                Some(href) => {
+                    self.push_tag(&format!("<a href=\"{}\">", Escaped(&href)));
+                    self.accept(&elem.body(), styles)?;
+                    self.push_tag("</a>");
+                }
+                None => self.accept(&elem.body(), styles)?,
+            }
+        } else if let Some(elem) = content.to::<RawElem>() {
+            let text = elem.text();
+            if elem.block(styles) {
+                let class = match elem.lang(styles) {
+                    Some(lang) => format!(" class=\"language-{}\"", Escaped(&lang)),
+                    None => String::new(),
+                };
+                let text = Escaped(&text);
+                self.push_block(&format!("<pre><code{class}>{text}</code></pre>"));
+            } else {
+                self.push_object(&format!("<code>{}</code>", Escaped(&text)));
+            }
+        } else if let Some(elem) = content.to::<StrongElem>() {
+            self.push_tag("<strong>");
+            self.accept(&elem.body(), styles)?;
+            self.push_tag("</strong>");
+        } else if let Some(elem) = content.to::<EmphElem>() {
+            self.push_tag("<em>");
+            self.accept(&elem.body(), styles)?;
+            self.push_tag("</em>");
+        } else if let Some(elem) = content.to::<ListElem>() {
+            let mut list = ListBuilder::new(ListKind::Bullet);
+            for item in elem.children() {
+                list.items.push_str(&self.list_item(&item.pack(), styles)?);
+            }
+            self.push_block(&list.finish());
+        } else if let Some(elem) = content.to::<EnumElem>() {
+            let mut list = ListBuilder::new(ListKind::Numbered(elem.start(styles)));
+            for item in elem.children() {
+                list.items.push_str(&self.list_item(&item.pack(), styles)?);
+            }
+            self.push_block(&list.finish());
+        } else if let Some(elem) = content.to::<TermsElem>() {
+            let mut list = ListBuilder::new(ListKind::Terms);
+            for item in elem.children() {
+                list.items.push_str(&self.list_item(&item.pack(), styles)?);
+            }
+            self.push_block(&list.finish());
+        } else if let Some(kind) = ListKind::of_item(content) {
+            self.flush_par();
+            if self.list.as_ref().map_or(false, |list| list.kind != kind) {
+                self.flush_list();
+            }
+            let item = self.list_item(content, styles)?;
+            self.list
+                .get_or_insert_with(|| ListBuilder::new(kind))
+                .items
+                .push_str(&item);
+        } else if let Some(elem) = content.to::<TableElem>() {
+            let columns = elem.columns(styles).0.len().max(1);
+            let (header, body, footer) = elem.sections()?;
+            let cells = |children: Vec<Content>| -> Vec<_> {
+                children.into_iter().filter(|child| !TableElem::is_rule(child)).collect()
+            };
+            let head = cells(header.as_ref().map_or(vec![], TableHeaderElem::children));
+            let foot = cells(footer.as_ref().map_or(vec![], TableFooterElem::children));
+            let (h, f) = (head.len(), foot.len());
+            let children: Vec<_> =
+                head.into_iter().chain(cells(body)).chain(foot).collect();
+            let spans: Vec<_> = children
+                .iter()
+                .map(|child| TableCellElem::span_of(child, styles))
+                .collect();
+            let (positions, _) = place_cells(&spans, columns, &[h, children.len() - f]);
+
+            let mut html = String::from("<table>\n");
+            let mut section = None;
+            let mut row = None;
+            let mut end = 0;
+            for (i, ((child, span), pos)) in
+                children.iter().zip(&spans).zip(&positions).enumerate()
+            {
+                let tag = if i < h {
+                    "thead"
+                } else if i >= children.len() - f {
+                    "tfoot"
+                } else {
+                    "tbody"
+                };
+
+                if section != Some(tag) {
+                    if let Some(y) = row.take() {
+                        finish_row(&mut html, y, end);
+                    }
+                    if let Some(prev) = section {
+                        writeln!(html, "</{prev}>").unwrap();
+                    }
+                    writeln!(html, "<{tag}>").unwrap();
+                    section = Some(tag);
+                }
+
+                if row != Some(pos.y) {
+                    if let Some(y) = row {
+                        finish_row(&mut html, y, pos.y);
+                    }
+                    html.push_str("<tr>");
+                    row = Some(pos.y);
+                }
+                end = end.max(pos.y + span.y.get());
+
+                let body = match child.to::<TableCellElem>() {
+                    Some(cell) => cell.body(),
+                    None => child.clone(),
+                };
+
+                let cell = if tag == "thead" { "th" } else { "td" };
+                write!(html, "<{cell}").unwrap();
+                if span.x.get() > 1 {
+                    write!(html, " colspan=\"{}\"", span.x).unwrap();
+                }
+                if span.y.get() > 1 {
+                    write!(html, " rowspan=\"{}\"", span.y).unwrap();
+                }
+                write!(html, ">{}</{cell}>", self.nested(&body, styles)?).unwrap();
+            }
+            if let Some(y) = row {
+                finish_row(&mut html, y, end);
+            }
+            if let Some(section) = section {
+                writeln!(html, "</{section}>").unwrap();
+            }
+            html.push_str("</table>");
+            self.push_block(&html);
+        } else if let Some(elem) = content.to::<EquationElem>() {
+            let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+            let frame = elem.layout(self.vt, styles, pod)?.into_frame();
+            let svg = typst::export::svg(&frame);
+            if elem.block(styles) {
+                self.push_block(&format!("<div class=\"equation\">{svg}</div>"));
+            } else {
+                let shift = -frame.descent().to_pt();
+                let style = format!("vertical-align: {shift:.2}pt");
+                self.push_object(&format!(
+                    "<span class=\"equation\" style=\"{style}\">{svg}</span>"
+                ));
+            }
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Convert an element that is left after realization.
+    fn primitive(&mut self, content: &Content, styles: StyleChain) -> SourceResult<()> {
+        if let Some(elem) = content.to::<TextElem>() {
+            self.lang.get_or_insert_with(|| TextElem::lang_in(styles));
+            self.push_text(&elem.text());
+        } else if content.is::<SpaceElem>() {
+            if self.list.is_none() && !self.par.is_empty() {
+                self.push_text(" ");
+            }
+        } else if content.is::<LinebreakElem>() {
+            self.resolve_quote(Some('\n'));
+            self.push_tag("<br>");
+        } else if content.is::<ParbreakElem>() {
+            self.flush_par();
+        } else if let Some(elem) = content.to::<SmartQuoteElem>() {
+            let double = elem.double(styles);
+            if SmartQuoteElem::enabled_in(styles) {
+                self.flush_list();
+                self.resolve_quote(None);
+                let lang = TextElem::lang_in(styles);
+                let region = TextElem::region_in(styles);
+                let quotes = Quotes::from_lang(lang, region);
+                self.quote = Some((self.par.len(), quotes, double));
+            } else {
+                self.push_text(if double { "\"" } else { "'" });
+            }
+        } else if let Some(elem) = content.to::<BoxElem>() {
+            if let Some(body) = elem.body(styles) {
+                let prev = std::mem::replace(&mut self.inline, true);
+                self.accept(&body, styles)?;
+                self.inline = prev;
+            }
+        } else if let Some(elem) = content.to::<BlockElem>() {
+            if let Some(body) = elem.body(styles) {
+                self.flush_par();
+                self.accept(&body, styles)?;
+                self.flush_par();
+            }
+        } else if let Some(body) = container_body(content) {
+            self.accept(&body, styles)?;
+        } else if content.is::<MetaElem>()
+            || content.is::<HElem>()
+            || content.is::<VElem>()
+            || content.is::<PagebreakElem>()
+            || content.is::<ColbreakElem>()
+            || content.is::<HideElem>()
+        {
+            // Nothing to export.
+        } else if let Some(layoutable) = content.with::<dyn Layout>() {
+            // Elements without an HTML equivalent are embedded as SVG.
+            let pod = text_area(styles);
+            let frame = layoutable.layout(self.vt, styles, pod)?.into_frame();
+            let svg = typst::export::svg(&frame);
+            if self.inline {
+                self.push_object(&svg);
+            } else {
+                self.push_block(&format!("<div>{svg}</div>"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Convert a loose or explicit list item into HTML.
+    fn list_item(&mut self, item: &Content, styles: StyleChain) -> SourceResult<String> {
+        Ok(if let Some(item) = item.to::<ListItem>() {
+            format!("<li>{}</li>\n", self.nested(&item.body(), styles)?)
+        } else if let Some(item) = item.to::<EnumItem>() {
+            let body = self.nested(&item.body(), styles)?;
+            match item.number(styles) {
+                Some(number) => format!("<li value=\"{number}\">{body}</li>\n"),
+                None => format!("<li>{body}</li>\n"),
+            }
+        } else if let Some(item) = item.to::<TermItem>() {
+            let term = self.nested(&item.term(), styles)?;
+            let description = self.nested(&item.description(), styles)?;
+            format!("<dt>{term}</dt>\n<dd>{description}</dd>\n")
+        } else {
+            String::new()
+        })
+    }
+
+    /// Convert content in a separate writer.
+    ///
+    /// If the content forms a single paragraph, its inline HTML is returned
+    /// without a surrounding `<p>` tag.
+    fn nested(&mut self, content: &Content, styles: StyleChain) -> SourceResult<String> {
+        let mut writer = HtmlWriter::new(self.vt);
+        writer.inline = self.inline;
+        writer.accept(content, styles)?;
+        writer.flush_list();
+        writer.resolve_quote(None);
+        if self.lang.is_none() {
+            self.lang = writer.lang;
+        }
+        Ok(if writer.body.is_empty() {
+            writer.par.trim().into()
+        } else {
+            writer.finish()
+        })
+    }
+
+    /// Push escaped text into the current paragraph.
+    fn push_text(&mut self, text: &str) {
+        self.flush_list();
+        self.resolve_quote(text.chars().next());
+        write!(self.par, "{}", Escaped(text)).unwrap();
+        if let Some(c) = text.chars().last() {
+            self.quoter.last(c);
+        }
+    }
+
+    /// Push an inline tag into the current paragraph.
+    fn push_tag(&mut self, tag: &str) {
+        self.flush_list();
+        self.par.push_str(tag);
+    }
+
+    /// Push an opaque inline object into the current paragraph.
+    fn push_object(&mut self, html: &str) {
+        const OBJ_REPLACE: char = '\u{FFFC}';
+        self.flush_list();
+        self.resolve_quote(Some(OBJ_REPLACE));
+        self.par.push_str(html);
+        self.quoter.last(OBJ_REPLACE);
+    }
+
+    /// Push a block-level element.
+    fn push_block(&mut self, html: &str) {
+        self.flush_par();
+        self.flush_list();
+        self.body.push_str(html);
+        self.body.push('\n');
+    }
+
+    /// Substitute the pending smart quote, if any.
+    fn resolve_quote(&mut self, peeked: Option<char>) {
+        if let Some((pos, quotes, double)) = self.quote.take() {
+            let quote = self.quoter.quote(&quotes, double, peeked);
+            self.par.insert_str(pos, quote);
+            if let Some(c) = quote.chars().last() {
+                self.quoter.last(c);
+            }
+        }
+    }
+
+    /// Finish the current paragraph.
+    fn flush_par(&mut self) {
+        self.resolve_quote(None);
+        let par = std::mem::take(&mut self.par);
+        let par = par.trim();
+        if !par.is_empty() {
+            writeln!(self.body, "<p>{par}</p>").unwrap();
+        }
+        self.quoter = Quoter::new();
+    }
+
+    /// Finish the current list.
+    fn flush_list(&mut self) {
+        if let Some(list) = self.list.take() {
+            self.body.push_str(&list.finish());
+            self.body.push('\n');
+        }
+    }
+
+    /// Finish all pending content and return the block-level HTML.
+    fn finish(mut self) -> String {
+        self.flush_par();
+        self.flush_list();
+        self.body
+    }
+}
+
+/// A list that is being built.
+struct ListBuilder {
+    /// The kind of list.
+    kind: ListKind,
+    /// The HTML of the list's items.
+    items: String,
+}
+
+impl ListBuilder {
+    /// Start a new list.
+    fn new(kind: ListKind) -> Self {
+        Self { kind, items: String::new() }
+    }
+
+    /// Wrap the items into the list's tag.
+    fn finish(self) -> String {
+        match self.kind {
+            ListKind::Bullet => format!("<ul>\n{}</ul>", self.items),
+            ListKind::Numbered(1) => format!("<ol>\n{}</ol>", self.items),
+            ListKind::Numbered(start) => {
+                format!("<ol start=\"{start}\">\n{}</ol>", self.items)
+            }
+            ListKind::Terms => format!("<dl>\n{}</dl>", self.items),
+        }
+    }
+}
+
+/// The kind of a list.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ListKind {
+    /// A bullet list: `<ul>`.
+    Bullet,
+    /// A numbered list with a start number: `<ol>`.
+    Numbered(usize),
+    /// A term list: `<dl>`.
+    Terms,
+}
+
+impl ListKind {
+    /// The kind of list a loose list item belongs to.
+    fn of_item(content: &Content) -> Option<Self> {
+        if content.is::<ListItem>() {
+            Some(Self::Bullet)
+        } else if content.is::<EnumItem>() {
+            Some(Self::Numbered(1))
+        } else if content.is::<TermItem>() {
+            Some(Self::Terms)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a user-defined show rule applies to the content.
+fn has_recipe(content: &Content, styles: StyleChain) -> bool {
+    let mut n = styles.recipes().count();
+    for recipe in styles.recipes() {
+        if recipe.applicable(content) && !content.is_guarded(Guard::Nth(n)) {
+            return true;
+        }
+        n -= 1;
+    }
+    false
+}
+
+/// The body of an element that only positions or transforms its content.
+fn container_body(content: &Content) -> Option<Content> {
+    if let Some(elem) = content.to::<AlignElem>() {
+        Some(elem.body())
+    } else if let Some(elem) = content.to::<PadElem>() {
+        Some(elem.body())
+    } else if let Some(elem) = content.to::<ColumnsElem>() {
+        Some(elem.body())
+    } else if let Some(elem) = content.to::<PlaceElem>() {
+        Some(elem.body())
+    } else if let Some(elem) = content.to::<MoveElem>() {
+        Some(elem.body())
+    } else if let Some(elem) = content.to::<RotateElem>() {
+        Some(elem.body())
+    } else if let Some(elem) = content.to::<ScaleElem>() {
+        Some(elem.body())
+    } else if let Some(elem) = content.to::<PageElem>() {
+        Some(elem.body())
+    } else {
+        None
+    }
+}
+
+/// The region in which elements without an HTML equivalent are laid out: The
+/// text area of the current page with its configured margins.
+fn text_area(styles: StyleChain) -> Regions<'static> {
+    let width = PageElem::width_in(styles).unwrap_or(Paper::A4.width());
+    let height = PageElem::height_in(styles).unwrap_or(Paper::A4.height());
+    let page = Size::new(width, height);
+    let default = Rel::from(DEFAULT_MARGIN * width.min(height));
+    let margin = PageElem::margin_in(styles)
+        .sides
+        .map(|side| side.unwrap_or(Smart::Auto).unwrap_or(default))
+        .resolve(styles)
+        .relative_to(page)
+        .sum_by_axis();
+    Regions::one(page - margin, Axes::splat(false))
+}
+
+/// Closes the table row `y` and emits empty rows up to `next`, as rows that are
+/// fully covered by rowspans have no cells of their own.
+fn finish_row(html: &mut String, y: usize, next: usize) {
+    html.push_str("</tr>\n");
+    for _ in y + 1..next {
+        html.push_str("<tr></tr>\n");
+    }
+}
+
+/// Displays an optional label as an `id` attribute.
+struct Id<'a>(Option<&'a Label>);
+
+impl fmt::Display for Id<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.0 {
+            Some(label) => write!(f, " id=\"{}\"", Escaped(&label.0)),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use std::collections::HashSet;
+
 use super::{AlignElem, BlockElem, ColbreakElem, ParElem, PlaceElem, Spacing, VElem};
+use crate::meta::FootnoteElem;
 use crate::prelude::*;
+use crate::text::TextDir;
 use crate::visualize::{CircleElem, EllipseElem, ImageElem, RectElem, SquareElem};
 
 /// Arrange spacing, paragraphs and block-level elements into a flow.
@@ -23,7 +27,11 @@ impl Layout for FlowElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let mut layouter = FlowLayouter::new(regions);
+        let mut layouter = FlowLayouter::new(regions, styles);
+
+        // The locations of elements whose metadata moves along with a float's
+        // frame instead of staying where the float was specified.
+        let mut float_locs = HashSet::new();
 
         for mut child in &self.children() {
             let outer = styles;
@@ -34,7 +42,7 @@ impl Layout for FlowElem {
             }
 
             if let Some(elem) = child.to::<VElem>() {
-                layouter.layout_spacing(elem, styles);
+                layouter.layout_spacing(vt, elem, styles)?;
             } else if let Some(elem) = child.to::<ParElem>() {
                 layouter.layout_par(vt, elem, styles)?;
             } else if child.is::<RectElem>()
@@ -46,6 +54,11 @@ impl Layout for FlowElem {
                 let layoutable = child.with::<dyn Layout>().unwrap();
                 layouter.layout_single(vt, layoutable, styles)?;
             } else if child.is::<MetaElem>() {
+                let locs: Vec<_> = meta_locations(styles).collect();
+                if !locs.is_empty() && locs.iter().all(|loc| float_locs.contains(loc)) {
+                    continue;
+                }
+
                 let mut frame = Frame::new(Size::zero());
                 frame.meta(styles, true);
                 layouter.items.push(FlowItem::Frame(
@@ -54,6 +67,9 @@ impl Layout for FlowElem {
                     true,
                 ));
             } else if child.can::<dyn Layout>() {
+                if child.to::<PlaceElem>().map_or(false, |placed| placed.float(styles)) {
+                    float_locs.extend(meta_locations(styles));
+                }
                 layouter.layout_multiple(vt, child, styles)?;
             } else if child.is::<ColbreakElem>() {
                 if !layouter.regions.backlog.is_empty() || layouter.regions.last.is_some()
@@ -71,8 +87,15 @@ impl Layout for FlowElem {
 
 /// Performs flow layout.
 struct FlowLayouter<'a> {
+    /// Whether this is the root flow of a page.
+    root: bool,
+    /// Whether the flow is set in vertical writing mode. Then, its items are
+    /// stacked from right to left instead of from top to bottom.
+    vertical: bool,
     /// The regions to layout children into.
     regions: Regions<'a>,
+    /// The shared styles.
+    styles: StyleChain<'a>,
     /// Whether the flow should expand to fill the region.
     expand: Axes<bool>,
     /// The initial size of `regions.size` that was available before we started
@@ -82,6 +105,11 @@ struct FlowLayouter<'a> {
     last_was_par: bool,
     /// Spacing and layouted blocks.
     items: Vec<FlowItem>,
+    /// Floats that didn't fit into the current region and wait for the next
+    /// one.
+    pending_floats: Vec<(Frame, Smart<Align>, Abs)>,
+    /// Whether the current region already contains footnotes.
+    has_footnotes: bool,
     /// Finished frames for previous regions.
     finished: Vec<Frame>,
 }
@@ -97,35 +125,55 @@ enum FlowItem {
     Frame(Frame, Axes<Align>, bool),
     /// An absolutely placed frame.
     Placed(Frame),
+    /// A floating frame, whether it floats to the top or bottom, and how
+    /// much clearance it keeps to the other content.
+    Float(Frame, Smart<Align>, Abs),
+    /// A footnote entry or the separator above the footnote listing, and the
+    /// gap above it.
+    Footnote(Frame, Abs),
 }
 
 impl<'a> FlowLayouter<'a> {
     /// Create a new flow layouter.
-    fn new(mut regions: Regions<'a>) -> Self {
+    fn new(mut regions: Regions<'a>, styles: StyleChain<'a>) -> Self {
         let expand = regions.expand;
 
-        // Disable vertical expansion for children.
+        // Disable vertical expansion and root status for children.
         regions.expand.y = false;
+        let root = std::mem::replace(&mut regions.root, false);
 
         Self {
+            root,
+            vertical: TextDir::is_vertical(styles),
             regions,
+            styles,
             expand,
             initial: regions.size,
             last_was_par: false,
             items: vec![],
+            pending_floats: vec![],
+            has_footnotes: false,
             finished: vec![],
         }
     }
 
     /// Layout vertical spacing.
-    fn layout_spacing(&mut self, v: &VElem, styles: StyleChain) {
-        self.layout_item(match v.amount() {
-            Spacing::Rel(rel) => FlowItem::Absolute(
-                rel.resolve(styles).relative_to(self.initial.y),
-                v.weakness(styles) > 0,
-            ),
-            Spacing::Fr(fr) => FlowItem::Fractional(fr),
-        });
+    fn layout_spacing(
+        &mut self,
+        vt: &mut Vt,
+        v: &VElem,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        self.layout_item(
+            vt,
+            match v.amount() {
+                Spacing::Rel(rel) => FlowItem::Absolute(
+                    rel.resolve(styles).relative_to(self.extent(self.initial)),
+                    v.weakness(styles) > 0,
+                ),
+                Spacing::Fr(fr) => FlowItem::Fractional(fr),
+            },
+        )
     }
 
     /// Layout a paragraph.
@@ -137,9 +185,15 @@ impl<'a> FlowLayouter<'a> {
     ) -> SourceResult<()> {
         let aligns = AlignElem::alignment_in(styles).resolve(styles);
         let leading = ParElem::leading_in(styles);
+        let orphans = ParElem::orphans_in(styles).get();
+        let widows = ParElem::widows_in(styles).get();
         let consecutive = self.last_was_par;
-        let frames = par
-            .layout(vt, styles, consecutive, self.regions.base(), self.regions.expand.x)?
+
+        // In vertical writing mode, the lines are columns which span the
+        // region's height.
+        let expand = if self.vertical { self.expand.y } else { self.regions.expand.x };
+        let mut frames = par
+            .layout(vt, styles, consecutive, self.regions.base(), expand)?
             .into_frames();
 
         let mut sticky = self.items.len();
@@ -151,22 +205,61 @@ impl<'a> FlowLayouter<'a> {
             }
         }
 
-        if let [first, ..] = frames.as_slice() {
-            if !self.regions.size.y.fits(first.height()) && !self.regions.in_last() {
+        // Place the lines chunk by chunk, one chunk per region. Each chunk is
+        // chosen such that neither too few lines stay behind at the bottom of
+        // a region nor too few are left over for the top of the next one.
+        let mut start = 0;
+        let mut fresh = false;
+        while !frames.is_empty() {
+            let rest = frames.len();
+            let mut take = rest;
+
+            if !self.regions.in_last() {
+                let fit = self.fitting_lines(&frames, start > 0, leading);
+                if fit < rest {
+                    take = fit;
+                    if rest - take < widows {
+                        take = take.saturating_sub(widows - (rest - take));
+                    }
+                    if start == 0 && take < orphans {
+                        take = 0;
+                    }
+                }
+
+                // Moving lines to the next region only helps if this one
+                // already contains something else.
+                let empty =
+                    !self.items.iter().any(|item| matches!(item, FlowItem::Frame(..)));
+                if take == 0 && (fresh || empty) {
+                    take = fit.max(1);
+                }
+            }
+
+            if take == 0 {
+                // The paragraph moves to the next region as a whole, together
+                // with sticky blocks like headings in front of it.
                 let carry: Vec<_> = self.items.drain(sticky..).collect();
                 self.finish_region();
                 for item in carry {
-                    self.layout_item(item);
+                    self.layout_item(vt, item)?;
                 }
+                fresh = true;
+                continue;
             }
-        }
 
-        for (i, frame) in frames.into_iter().enumerate() {
-            if i > 0 {
-                self.layout_item(FlowItem::Absolute(leading, true));
+            for (i, frame) in frames.drain(..take).enumerate() {
+                if start + i > 0 {
+                    self.layout_item(vt, FlowItem::Absolute(leading, true))?;
+                }
+
+                self.layout_item(vt, FlowItem::Frame(frame, aligns, false))?;
             }
 
-            self.layout_item(FlowItem::Frame(frame, aligns, false));
+            start += take;
+            if !frames.is_empty() {
+                self.finish_region();
+                fresh = true;
+            }
         }
 
         self.last_was_par = true;
@@ -174,6 +267,26 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// How many of the given lines fit into the current region, including the
+    /// leading in front of them.
+    fn fitting_lines(&self, lines: &[Frame], leading_first: bool, leading: Abs) -> usize {
+        let mut remaining = self.extent(self.regions.size);
+        for (i, line) in lines.iter().enumerate() {
+            let mut needed = self.extent(line.size());
+            if i > 0 || leading_first {
+                needed += leading;
+            }
+
+            if !remaining.fits(needed) {
+                return i;
+            }
+
+            remaining -= needed;
+        }
+
+        lines.len()
+    }
+
     /// Layout into a single region.
     fn layout_single(
         &mut self,
@@ -185,7 +298,7 @@ impl<'a> FlowLayouter<'a> {
         let sticky = BlockElem::sticky_in(styles);
         let pod = Regions::one(self.regions.base(), Axes::splat(false));
         let frame = content.layout(vt, styles, pod)?.into_frame();
-        self.layout_item(FlowItem::Frame(frame, aligns, sticky));
+        self.layout_item(vt, FlowItem::Frame(frame, aligns, sticky))?;
         self.last_was_par = false;
         Ok(())
     }
@@ -200,9 +313,19 @@ impl<'a> FlowLayouter<'a> {
         // Placed elements that are out of flow produce placed items which
         // aren't aligned later.
         if let Some(placed) = block.to::<PlaceElem>() {
+            // Floats are only supported in horizontal writing mode.
+            if placed.float(styles) && !self.vertical {
+                let mut frame = block.layout(vt, styles, self.regions)?.into_frame();
+                frame.meta(styles, true);
+                let align = placed.float_align(styles);
+                let clearance = placed.clearance(styles);
+                self.layout_item(vt, FlowItem::Float(frame, align, clearance))?;
+                return Ok(());
+            }
+
             if placed.out_of_flow(styles) {
                 let frame = block.layout(vt, styles, self.regions)?.into_frame();
-                self.layout_item(FlowItem::Placed(frame));
+                self.layout_item(vt, FlowItem::Placed(frame))?;
                 return Ok(());
             }
         }
@@ -224,7 +347,7 @@ impl<'a> FlowLayouter<'a> {
             if i > 0 {
                 self.finish_region();
             }
-            self.layout_item(FlowItem::Frame(frame, aligns, sticky));
+            self.layout_item(vt, FlowItem::Frame(frame, aligns, sticky))?;
         }
 
         self.last_was_par = false;
@@ -233,22 +356,104 @@ impl<'a> FlowLayouter<'a> {
     }
 
     /// Layout a finished frame.
-    fn layout_item(&mut self, item: FlowItem) {
+    fn layout_item(&mut self, vt: &mut Vt, item: FlowItem) -> SourceResult<()> {
         match item {
-            FlowItem::Absolute(v, _) => self.regions.size.y -= v,
+            FlowItem::Absolute(v, _) => *self.remaining() -= v,
             FlowItem::Fractional(_) => {}
             FlowItem::Frame(ref frame, ..) => {
                 let size = frame.size();
-                if !self.regions.size.y.fits(size.y) && !self.regions.in_last() {
+                let extent = self.extent(size);
+                if !self.remaining().fits(extent) && !self.regions.in_last() {
                     self.finish_region();
                 }
 
-                self.regions.size.y -= size.y;
+                *self.remaining() -= extent;
+
+                // Footnotes are only supported in horizontal writing mode.
+                if self.root && !self.vertical {
+                    let mut notes = vec![];
+                    find_footnotes(&mut notes, frame);
+                    if !notes.is_empty() {
+                        // If the footnotes don't fit, the frame moves to the
+                        // next region together with them. That's pointless
+                        // if the region doesn't contain anything else yet.
+                        let alone = !self
+                            .items
+                            .iter()
+                            .any(|item| matches!(item, FlowItem::Frame(..)));
+                        if !alone && !self.footnotes_fit(vt, &notes)? {
+                            self.regions.size.y += size.y;
+                            self.finish_region();
+                            self.regions.size.y -= size.y;
+                        }
+
+                        self.items.push(item);
+                        return self.handle_footnotes(vt, notes);
+                    }
+                }
             }
             FlowItem::Placed(_) => {}
+            FlowItem::Float(frame, align, clearance) => {
+                self.layout_float(frame, align, clearance);
+                return Ok(());
+            }
+            FlowItem::Footnote(..) => {}
         }
 
         self.items.push(item);
+        Ok(())
+    }
+
+    /// Layout a floating frame at the top or bottom of the region, or defer
+    /// it to the next region if it doesn't fit anymore.
+    fn layout_float(&mut self, mut frame: Frame, align: Smart<Align>, clearance: Abs) {
+        let height = frame.height() + clearance;
+        if !self.regions.size.y.fits(height)
+            && !self.regions.in_last()
+            && !self.items.is_empty()
+        {
+            self.pending_floats.push((frame, align, clearance));
+            return;
+        }
+
+        // Pick the closer of top and bottom, measured from the center of the
+        // float if it was laid out at the current position.
+        let align = align.unwrap_or_else(|| {
+            let ratio = (self.regions.size.y - height / 2.0) / self.regions.full;
+            if ratio <= 0.5 {
+                Align::Bottom
+            } else {
+                Align::Top
+            }
+        });
+
+        // Add the clearance on the side facing the other content.
+        frame.size_mut().y += clearance;
+        if align == Align::Bottom {
+            frame.translate(Point::with_y(clearance));
+        }
+
+        self.regions.size.y -= frame.height();
+        self.items.push(FlowItem::Float(frame, Smart::Custom(align), clearance));
+    }
+
+    /// The extent of a size in the direction in which items are stacked.
+    fn extent(&self, size: Size) -> Abs {
+        if self.vertical {
+            size.x
+        } else {
+            size.y
+        }
+    }
+
+    /// The space that is left in the current region in the direction in which
+    /// items are stacked.
+    fn remaining(&mut self) -> &mut Abs {
+        if self.vertical {
+            &mut self.regions.size.x
+        } else {
+            &mut self.regions.size.y
+        }
     }
 
     /// Finish the frame for one region.
@@ -262,9 +467,20 @@ impl<'a> FlowLayouter<'a> {
             self.items.pop();
         }
 
+        if self.vertical {
+            let output = self.finish_vertical();
+            self.finished.push(output);
+            self.regions.next();
+            self.initial = self.regions.size;
+            return;
+        }
+
         // Determine the used size.
         let mut fr = Fr::zero();
         let mut used = Size::zero();
+        let mut float_top_height = Abs::zero();
+        let mut float_bottom_height = Abs::zero();
+        let mut footnote_height = Abs::zero();
         for item in &self.items {
             match item {
                 FlowItem::Absolute(v, _) => used.y += *v,
@@ -275,8 +491,21 @@ impl<'a> FlowLayouter<'a> {
                     used.x.set_max(size.x);
                 }
                 FlowItem::Placed(_) => {}
+                FlowItem::Float(frame, align, _) => {
+                    if *align == Smart::Custom(Align::Bottom) {
+                        float_bottom_height += frame.height();
+                    } else {
+                        float_top_height += frame.height();
+                    }
+                    used.x.set_max(frame.width());
+                }
+                FlowItem::Footnote(frame, gap) => {
+                    footnote_height += *gap + frame.height();
+                    used.x.set_max(frame.width());
+                }
             }
         }
+        used.y += float_top_height + float_bottom_height + footnote_height;
 
         // Determine the size of the flow in this region depending on whether
         // the region expands. Also account for fractional spacing.
@@ -286,8 +515,11 @@ impl<'a> FlowLayouter<'a> {
         }
 
         let mut output = Frame::new(size);
-        let mut offset = Abs::zero();
+        let mut offset = float_top_height;
         let mut ruler = Align::Top;
+        let mut float_top_offset = Abs::zero();
+        let mut float_bottom_offset = size.y - footnote_height - float_bottom_height;
+        let mut footnote_offset = size.y - footnote_height;
 
         // Place all frames.
         for item in self.items.drain(..) {
@@ -310,6 +542,22 @@ impl<'a> FlowLayouter<'a> {
                 FlowItem::Placed(frame) => {
                     output.push_frame(Point::zero(), frame);
                 }
+                FlowItem::Float(frame, align, _) => {
+                    let y = if align == Smart::Custom(Align::Bottom) {
+                        &mut float_bottom_offset
+                    } else {
+                        &mut float_top_offset
+                    };
+                    let pos = Point::with_y(*y);
+                    *y += frame.height();
+                    output.push_frame(pos, frame);
+                }
+                FlowItem::Footnote(frame, gap) => {
+                    footnote_offset += gap;
+                    let pos = Point::with_y(footnote_offset);
+                    footnote_offset += frame.height();
+                    output.push_frame(pos, frame);
+                }
             }
         }
 
@@ -317,6 +565,67 @@ impl<'a> FlowLayouter<'a> {
         self.finished.push(output);
         self.regions.next();
         self.initial = self.regions.size;
+        self.has_footnotes = false;
+
+        // Try to place the floats that didn't fit into the previous region.
+        for (frame, align, clearance) in std::mem::take(&mut self.pending_floats) {
+            self.layout_float(frame, align, clearance);
+        }
+    }
+
+    /// Arrange the items of a region in vertical writing mode from right to
+    /// left.
+    fn finish_vertical(&mut self) -> Frame {
+        // Determine the used size.
+        let mut fr = Fr::zero();
+        let mut used = Size::zero();
+        for item in &self.items {
+            match item {
+                FlowItem::Absolute(v, _) => used.x += *v,
+                FlowItem::Fractional(v) => fr += *v,
+                FlowItem::Frame(frame, ..) => {
+                    let size = frame.size();
+                    used.x += size.x;
+                    used.y.set_max(size.y);
+                }
+                FlowItem::Placed(_) => {}
+                // These don't occur in vertical writing mode.
+                FlowItem::Float(..) | FlowItem::Footnote(..) => {}
+            }
+        }
+
+        // Determine the size of the flow in this region depending on whether
+        // the region expands. Also account for fractional spacing.
+        let mut size = self.expand.select(self.initial, used).min(self.initial);
+        if fr.get() > 0.0 && self.initial.x.is_finite() {
+            size.x = self.initial.x;
+        }
+
+        // Place all frames, starting at the right edge.
+        let mut output = Frame::new(size);
+        let mut offset = Abs::zero();
+        for item in self.items.drain(..) {
+            match item {
+                FlowItem::Absolute(v, _) => {
+                    offset += v;
+                }
+                FlowItem::Fractional(v) => {
+                    let remaining = self.initial.x - used.x;
+                    offset += v.share(fr, remaining);
+                }
+                FlowItem::Frame(frame, aligns, _) => {
+                    offset += frame.width();
+                    let y = aligns.y.position(size.y - frame.height());
+                    output.push_frame(Point::new(size.x - offset, y), frame);
+                }
+                FlowItem::Placed(frame) => {
+                    output.push_frame(Point::zero(), frame);
+                }
+                FlowItem::Float(..) | FlowItem::Footnote(..) => {}
+            }
+        }
+
+        output
     }
 
     /// Finish layouting and return the resulting fragment.
@@ -328,6 +637,156 @@ impl<'a> FlowLayouter<'a> {
         }
 
         self.finish_region();
+
+        // Floats that were deferred may still need further regions.
+        while !self.items.is_empty() {
+            self.finish_region();
+        }
+
         Fragment::frames(self.finished)
     }
 }
+
+impl FlowLayouter<'_> {
+    /// Whether the entries of the given footnotes fit into the current region
+    /// together with the separator.
+    ///
+    /// Only the start of each entry needs to fit as entries can break across
+    /// regions.
+    fn footnotes_fit(
+        &mut self,
+        vt: &mut Vt,
+        notes: &[FootnoteElem],
+    ) -> SourceResult<bool> {
+        if self.regions.in_last() {
+            return Ok(true);
+        }
+
+        let mut regions = self.regions;
+        if let (false, Some(first)) = (self.has_footnotes, notes.first()) {
+            let separator = self.layout_footnote_separator(vt, first)?;
+            regions.size.y -= separator.height();
+        }
+
+        for note in notes {
+            regions.size.y -= note.gap(StyleChain::default());
+            let entry = note.entry(vt, self.styles)?;
+            let frames = entry.measure(vt, self.styles, regions)?.into_frames();
+            match frames.first() {
+                Some(first) if !first.is_empty() => regions.size.y -= first.height(),
+                _ => return Ok(false),
+            }
+
+            // Later entries don't fit if this one already broke.
+            if frames.len() > 1 {
+                break;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Layout the entries of the given footnotes and of footnotes nested in
+    /// them, breaking them across regions if necessary.
+    fn handle_footnotes(
+        &mut self,
+        vt: &mut Vt,
+        mut notes: Vec<FootnoteElem>,
+    ) -> SourceResult<()> {
+        let mut k = 0;
+        while k < notes.len() {
+            let note = notes[k].clone();
+            let gap = note.gap(StyleChain::default());
+            if !self.has_footnotes {
+                self.push_footnote_separator(vt, &note)?;
+            }
+
+            self.regions.size.y -= gap;
+            let entry = note.entry(vt, self.styles)?;
+            let frames = entry.layout(vt, self.styles, self.regions)?.into_frames();
+
+            let prev = notes.len();
+            for (i, frame) in frames.into_iter().enumerate() {
+                find_footnotes(&mut notes, &frame);
+                if i > 0 {
+                    self.finish_region();
+                    self.push_footnote_separator(vt, &note)?;
+                    self.regions.size.y -= gap;
+                }
+                self.regions.size.y -= frame.height();
+                self.items.push(FlowItem::Footnote(frame, gap));
+            }
+
+            k += 1;
+
+            // Process the nested notes before dealing with further top-level
+            // notes.
+            let nested = notes.len() - prev;
+            if nested > 0 {
+                notes[k..].rotate_right(nested);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Layout the separator of the given footnote, the first one in the
+    /// current region, and add it to the region.
+    fn push_footnote_separator(
+        &mut self,
+        vt: &mut Vt,
+        note: &FootnoteElem,
+    ) -> SourceResult<()> {
+        let frame = self.layout_footnote_separator(vt, note)?;
+        self.regions.size.y -= frame.height();
+        self.items.push(FlowItem::Footnote(frame, Abs::zero()));
+        self.has_footnotes = true;
+        Ok(())
+    }
+
+    /// Layout the separator of a footnote, typically a line, with the
+    /// clearance above it.
+    ///
+    /// The separator and clearance were synthesized from the styles at the
+    /// footnote.
+    fn layout_footnote_separator(
+        &self,
+        vt: &mut Vt,
+        note: &FootnoteElem,
+    ) -> SourceResult<Frame> {
+        let expand = Axes::new(self.regions.expand.x, false);
+        let pod = Regions::one(self.regions.base(), expand);
+        let separator = note.separator(StyleChain::default());
+        let clearance = note.clearance(StyleChain::default());
+
+        let mut frame = separator.measure(vt, self.styles, pod)?.into_frame();
+        frame.size_mut().y += clearance;
+        frame.translate(Point::with_y(clearance));
+        Ok(frame)
+    }
+}
+
+/// The locations of the elements whose metadata is attached by the styles.
+fn meta_locations(styles: StyleChain) -> impl Iterator<Item = Location> {
+    MetaElem::data_in(styles).into_iter().filter_map(|meta| match meta {
+        Meta::Elem(elem) => elem.location(),
+        _ => None,
+    })
+}
+
+/// Collect all footnotes in the frame that aren't in the list yet.
+fn find_footnotes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => find_footnotes(notes, &group.frame),
+            FrameItem::Meta(Meta::Elem(content), _)
+                if !notes.iter().any(|note| note.0.location() == content.location()) =>
+            {
+                if let Some(note) = content.to::<FootnoteElem>() {
+                    notes.push(note.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
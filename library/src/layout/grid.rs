@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -115,6 +117,9 @@ impl Layout for GridElem {
             Axes::new(&self.columns(styles).0, &self.rows(styles).0),
             Axes::new(&self.column_gutter(styles).0, &self.row_gutter(styles).0),
             &cells,
+            &[],
+            0,
+            0,
             regions,
             styles,
         );
@@ -145,10 +150,8 @@ pub struct GridLayouter<'a, 'v> {
     vt: &'a mut Vt<'v>,
     /// The grid cells.
     cells: &'a [Content],
-    /// Whether this is an RTL grid.
-    is_rtl: bool,
-    /// Whether this grid has gutters.
-    has_gutter: bool,
+    /// The positions and spans of the cells in track coordinates.
+    placements: Placements,
     /// The column tracks including gutter tracks.
     cols: Vec<Sizing>,
     /// The row tracks including gutter tracks.
@@ -169,6 +172,20 @@ pub struct GridLayouter<'a, 'v> {
     initial: Size,
     /// Frames for finished regions.
     finished: Vec<Frame>,
+    /// The number of leading row tracks that are repeated at the top of every
+    /// region.
+    header: usize,
+    /// The first of the trailing row tracks that are repeated at the bottom of
+    /// every region.
+    footer: usize,
+    /// The height of the footer, which is reserved in every region.
+    footer_height: Abs,
+    /// The height of the header and the footer together.
+    repeated: Abs,
+    /// Whether the header or footer is currently being laid out.
+    repeating: bool,
+    /// The structure tag of each cell, if any.
+    tags: Vec<Option<Tag>>,
 }
 
 /// The resulting sizes of columns and rows in a grid.
@@ -180,6 +197,63 @@ pub struct GridLayout {
     pub cols: Vec<Abs>,
     /// The heights of the resulting rows segments, by region.
     pub rows: Vec<Vec<RowPiece>>,
+    /// The positions and spans of the cells in track coordinates.
+    pub placements: Placements,
+}
+
+/// The positions and spans of the cells in a grid.
+#[derive(Debug)]
+pub struct Placements {
+    /// The placement of each cell.
+    list: Vec<Placement>,
+    /// For each track cell in row-major order, the index of the placement
+    /// that occupies it.
+    occupancy: Vec<Option<usize>>,
+    /// The number of column tracks.
+    cols: usize,
+    /// Whether every other track is a gutter track.
+    gutter: bool,
+    /// Whether the column tracks are in right-to-left order.
+    rtl: bool,
+}
+
+impl Placements {
+    /// The cell that occupies the track cell in column `x` and row `y`.
+    pub fn at(&self, x: usize, y: usize) -> Option<&Placement> {
+        let index = self.occupancy.get(y * self.cols + x).copied()??;
+        self.list.get(index)
+    }
+
+    /// Convert the track cell in column `x` and row `y` into the logical
+    /// content column and row it belongs to.
+    ///
+    /// Gutter tracks belong to the content track before them.
+    pub fn content(&self, x: usize, y: usize) -> Axes<usize> {
+        let x = if self.rtl { self.cols - 1 - x } else { x };
+        if self.gutter {
+            Axes::new(x / 2, y / 2)
+        } else {
+            Axes::new(x, y)
+        }
+    }
+
+    /// Iterate over the placements of all cells.
+    pub fn iter(&self) -> std::slice::Iter<Placement> {
+        self.list.iter()
+    }
+}
+
+/// The position and span of a cell in track coordinates.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Placement {
+    /// The index of the cell in the grid's cells.
+    pub index: usize,
+    /// The column of the cell's left-most track.
+    pub x: usize,
+    /// The row of the cell's top-most track.
+    pub y: usize,
+    /// How many tracks the cell spans, including gutter tracks.
+    pub span: Axes<usize>,
 }
 
 /// Details about a resulting row piece.
@@ -204,11 +278,22 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
     /// Create a new grid layouter.
     ///
     /// This prepares grid layout by unifying content and gutter tracks.
+    ///
+    /// The `spans` define how many columns and rows each cell spans. Cells
+    /// without a span take up a single column and row.
+    ///
+    /// The first `header` and the last `footer` cells form a header and a
+    /// footer. They start on rows of their own and are repeated in every region
+    /// the grid occupies.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         vt: &'a mut Vt<'v>,
         tracks: Axes<&[Sizing]>,
         gutter: Axes<&[Sizing]>,
         cells: &'a [Content],
+        spans: &[Axes<NonZeroUsize>],
+        header: usize,
+        footer: usize,
         regions: Regions<'a>,
         styles: StyleChain<'a>,
     ) -> Self {
@@ -218,14 +303,27 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         // Number of content columns: Always at least one.
         let c = tracks.x.len().max(1);
 
+        // Place the cells.
+        let spans: Vec<_> = (0..cells.len())
+            .map(|i| spans.get(i).copied().unwrap_or(Axes::splat(NonZeroUsize::ONE)))
+            .collect();
+        let header = header.min(cells.len());
+        let footer = footer.min(cells.len() - header);
+        let (positions, needed) =
+            place_cells(&spans, c, &[header, cells.len() - footer]);
+
         // Number of content rows: At least as many as given, but also at least
         // as many as needed to place each item.
-        let r = {
-            let len = cells.len();
-            let given = tracks.y.len();
-            let needed = len / c + (len % c).clamp(0, 1);
-            given.max(needed)
-        };
+        let r = tracks.y.len().max(needed);
+
+        // The content rows occupied by the header and the first footer row.
+        let header_rows = positions[..header]
+            .iter()
+            .zip(&spans)
+            .map(|(pos, span)| pos.y + span.y.get())
+            .max()
+            .unwrap_or(0);
+        let footer_row = positions.get(cells.len() - footer).map_or(r, |pos| pos.y);
 
         let has_gutter = gutter.any(|tracks| !tracks.is_empty());
         let auto = Sizing::Auto;
@@ -262,6 +360,33 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             cols.reverse();
         }
 
+        // Convert the cell positions into track coordinates. Even tracks are
+        // content and odd ones are gutter if there is gutter.
+        let track = |i: usize| if has_gutter { 2 * i } else { i };
+        let extent = |n: usize| if has_gutter { 2 * n - 1 } else { n };
+        let list: Vec<_> = positions
+            .iter()
+            .zip(&spans)
+            .enumerate()
+            .map(|(index, (pos, span))| {
+                let span = Axes::new(extent(span.x.get().min(c)), extent(span.y.get()));
+                let mut x = track(pos.x);
+                if is_rtl {
+                    x = cols.len() - x - span.x;
+                }
+                Placement { index, x, y: track(pos.y), span }
+            })
+            .collect();
+
+        let mut occupancy = vec![None; cols.len() * rows.len()];
+        for (i, placement) in list.iter().enumerate() {
+            for y in placement.y..placement.y + placement.span.y {
+                for x in placement.x..placement.x + placement.span.x {
+                    occupancy[y * cols.len() + x] = Some(i);
+                }
+            }
+        }
+
         let rcols = vec![Abs::zero(); cols.len()];
         let lrows = vec![];
 
@@ -270,11 +395,26 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         let mut regions = regions;
         regions.expand = Axes::new(true, false);
 
+        let placements = Placements {
+            list,
+            occupancy,
+            cols: cols.len(),
+            gutter: has_gutter,
+            rtl: is_rtl,
+        };
+
+        // The repeated row tracks include the gutter between them and the body.
+        let header = track(header_rows).min(rows.len());
+        let footer = if has_gutter && footer_row > 0 {
+            2 * footer_row - 1
+        } else {
+            footer_row
+        };
+
         Self {
             vt,
             cells,
-            is_rtl,
-            has_gutter,
+            placements,
             cols,
             rows,
             regions,
@@ -285,33 +425,52 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             lrows,
             initial: regions.size,
             finished: vec![],
+            header,
+            footer: footer.clamp(header, rows.len()),
+            footer_height: Abs::zero(),
+            repeated: Abs::zero(),
+            repeating: false,
+            tags: vec![],
         }
     }
 
+    /// Attribute the cells to units of the document's logical structure.
+    ///
+    /// Copies of the header and footer in followup regions are marked as
+    /// artifacts.
+    pub fn tag_cells(&mut self, tags: Vec<Option<Tag>>) {
+        self.tags = tags;
+    }
+
     /// Determines the columns sizes and then layouts the grid row-by-row.
     pub fn layout(mut self) -> SourceResult<GridLayout> {
         self.measure_columns()?;
 
-        for y in 0..self.rows.len() {
+        // Measure the repeated rows so that followup regions can make room for
+        // them.
+        let header = self.measure_rows(0..self.header)?;
+        self.footer_height = self.measure_rows(self.footer..self.rows.len())?;
+        self.repeated = header + self.footer_height;
+        self.start_region()?;
+
+        for y in self.header..self.footer {
             // Skip to next region if current one is full, but only for content
             // rows, not for gutter rows.
-            if y % 2 == 0 && self.regions.is_full() {
+            if y % 2 == 0 && self.is_full() {
                 self.finish_region()?;
             }
 
-            match self.rows[y] {
-                Sizing::Auto => self.layout_auto_row(y)?,
-                Sizing::Rel(v) => self.layout_relative_row(v, y)?,
-                Sizing::Fr(v) => self.lrows.push(Row::Fr(v, y)),
-            }
+            self.layout_row(y)?;
         }
 
-        self.finish_region()?;
+        self.finish_rows()?;
+        self.layout_row_spans()?;
 
         Ok(GridLayout {
             fragment: Fragment::frames(self.finished),
             cols: self.rcols,
             rows: self.rrows,
+            placements: self.placements,
         })
     }
 
@@ -362,43 +521,79 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
     /// Measure the size that is available to auto columns.
     fn measure_auto_columns(&mut self, available: Abs) -> SourceResult<(Abs, usize)> {
-        let mut auto = Abs::zero();
-        let mut count = 0;
-
         // Determine size of auto columns by laying out all cells in those
         // columns, measuring them and finding the largest one.
-        for (x, &col) in self.cols.iter().enumerate() {
-            if col != Sizing::Auto {
+        for x in 0..self.cols.len() {
+            if self.cols[x] != Sizing::Auto {
                 continue;
             }
 
             let mut resolved = Abs::zero();
             for y in 0..self.rows.len() {
-                if let Some(cell) = self.cell(x, y) {
-                    // For relative rows, we can already resolve the correct
-                    // base and for auto and fr we could only guess anyway.
-                    let height = match self.rows[y] {
-                        Sizing::Rel(v) => {
-                            v.resolve(self.styles).relative_to(self.regions.base().y)
-                        }
-                        _ => self.regions.base().y,
-                    };
-
-                    let size = Size::new(available, height);
-                    let pod = Regions::one(size, Axes::splat(false));
-                    let frame = cell.measure(self.vt, self.styles, pod)?.into_frame();
-                    resolved.set_max(frame.width());
+                if let Some((cell, span)) = self.cell(x, y) {
+                    if span.x == 1 {
+                        resolved.set_max(self.measure_width(cell, y, available)?);
+                    }
                 }
             }
 
             self.rcols[x] = resolved;
-            auto += resolved;
-            count += 1;
+        }
+
+        // Cells spanning multiple columns enlarge the auto columns among them
+        // if they don't fit otherwise.
+        for i in 0..self.placements.list.len() {
+            let Placement { index, x, y, span } = self.placements.list[i];
+            let autos: Vec<_> = (x..x + span.x)
+                .filter(|&col| self.cols[col] == Sizing::Auto)
+                .collect();
+            if span.x == 1 || autos.is_empty() {
+                continue;
+            }
+
+            let cells = self.cells;
+            let width = self.measure_width(&cells[index], y, available)?;
+            let current: Abs = self.rcols[x..x + span.x].iter().sum();
+            if width > current {
+                let share = (width - current) / autos.len() as f64;
+                for col in autos {
+                    self.rcols[col] += share;
+                }
+            }
+        }
+
+        let mut auto = Abs::zero();
+        let mut count = 0;
+        for (&col, &rcol) in self.cols.iter().zip(&self.rcols) {
+            if col == Sizing::Auto {
+                auto += rcol;
+                count += 1;
+            }
         }
 
         Ok((auto, count))
     }
 
+    /// Measure the natural width of a cell that starts in row `y`.
+    fn measure_width(
+        &mut self,
+        cell: &Content,
+        y: usize,
+        available: Abs,
+    ) -> SourceResult<Abs> {
+        // For relative rows, we can already resolve the correct base and for
+        // auto and fr we could only guess anyway.
+        let height = match self.rows[y] {
+            Sizing::Rel(v) => v.resolve(self.styles).relative_to(self.regions.base().y),
+            _ => self.regions.base().y,
+        };
+
+        let size = Size::new(available, height);
+        let pod = Regions::one(size, Axes::splat(false));
+        let frame = cell.measure(self.vt, self.styles, pod)?.into_frame();
+        Ok(frame.width())
+    }
+
     /// Distribute remaining space to fractional columns.
     fn grow_fractional_columns(&mut self, remaining: Abs, fr: Fr) {
         if fr.is_zero() {
@@ -445,17 +640,79 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         }
     }
 
+    /// Measure the total height of the given rows on their own.
+    fn measure_rows(&mut self, rows: Range<usize>) -> SourceResult<Abs> {
+        let mut total = Abs::zero();
+        for y in rows {
+            match self.rows[y] {
+                Sizing::Auto => {
+                    let mut height = Abs::zero();
+                    for x in 0..self.rcols.len() {
+                        if let Some((cell, span)) = self.cell(x, y) {
+                            if span.y > 1 {
+                                continue;
+                            }
+
+                            let width = self.span_width(x, span.x);
+                            let size = Size::new(width, self.regions.base().y);
+                            let pod = Regions::one(size, Axes::new(true, false));
+                            let frame =
+                                cell.measure(self.vt, self.styles, pod)?.into_frame();
+                            height.set_max(frame.height());
+                        }
+                    }
+                    total += height;
+                }
+                Sizing::Rel(v) => {
+                    total += v.resolve(self.styles).relative_to(self.regions.base().y);
+                }
+                Sizing::Fr(_) => {}
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Layout the row with index `y`.
+    fn layout_row(&mut self, y: usize) -> SourceResult<()> {
+        match self.rows[y] {
+            Sizing::Auto => self.layout_auto_row(y)?,
+            Sizing::Rel(v) => self.layout_relative_row(v, y)?,
+            Sizing::Fr(v) => self.lrows.push(Row::Fr(v, y)),
+        }
+
+        Ok(())
+    }
+
+    /// The regions for rows in the current region. Followup regions lose the
+    /// space taken up by the header and the footer.
+    fn row_regions<'b>(&self, backlog: &'b mut Vec<Abs>) -> Regions<'b> {
+        let repeated = self.repeated;
+        let mut regions =
+            self.regions.map(backlog, |size| Size::new(size.x, size.y - repeated));
+        regions.size = self.regions.size;
+        regions.full = self.regions.full;
+        regions
+    }
+
     /// Layout a row with automatic height. Such a row may break across multiple
     /// regions.
     fn layout_auto_row(&mut self, y: usize) -> SourceResult<()> {
         let mut resolved: Vec<Abs> = vec![];
         let mut skip = false;
+        let mut backlog = vec![];
+        let regions = self.row_regions(&mut backlog);
+
+        // Determine the size for each region of the row. Cells spanning
+        // multiple rows are laid out at the end.
+        for x in 0..self.rcols.len() {
+            if let Some((cell, span)) = self.cell(x, y) {
+                if span.y > 1 {
+                    continue;
+                }
 
-        // Determine the size for each region of the row.
-        for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let mut pod = self.regions;
-                pod.size.x = rcol;
+                let mut pod = regions;
+                pod.size.x = self.span_width(x, span.x);
 
                 let frames = cell.measure(self.vt, self.styles, pod)?.into_frames();
                 if let [first, rest @ ..] = frames.as_slice() {
@@ -476,9 +733,27 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             }
         }
 
-        // Nothing to layout.
+        // Make room for cells spanning multiple rows that end in this row.
+        if resolved.len() <= 1 {
+            if let Some(needed) = self.measure_row_spans(y)? {
+                match resolved.first_mut() {
+                    Some(first) => first.set_max(needed),
+                    None => resolved.push(needed),
+                }
+            }
+        }
+
+        // Nothing to layout. Rows covered by a cell spanning multiple rows are
+        // kept nonetheless so that the cell can be placed into them.
         if resolved.is_empty() {
-            return Ok(());
+            let spanned = self.placements.iter().any(|placement| {
+                placement.span.y > 1
+                    && (placement.y..placement.y + placement.span.y).contains(&y)
+            });
+            if !spanned {
+                return Ok(());
+            }
+            resolved.push(Abs::zero());
         }
 
         // Layout into a single region.
@@ -489,16 +764,16 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         }
 
         // Skip the first region if it's empty for some cell.
-        if skip && !self.regions.in_last() {
+        if skip && !self.in_last() {
             self.finish_region()?;
             resolved.remove(0);
         }
 
         // Expand all but the last region.
         // Skip the first region if the space is eaten up by an fr row.
+        let regions = self.row_regions(&mut backlog);
         let len = resolved.len();
-        for (region, target) in self
-            .regions
+        for (region, target) in regions
             .iter()
             .zip(&mut resolved[..len - 1])
             .skip(self.lrows.iter().any(|row| matches!(row, Row::Fr(..))) as usize)
@@ -527,7 +802,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
         // Skip to fitting region.
         let height = frame.height();
-        while !self.regions.size.y.fits(height) && !self.regions.in_last() {
+        while !self.regions.size.y.fits(height) && !self.in_last() {
             self.finish_region()?;
 
             // Don't skip multiple regions for gutter and don't push a row.
@@ -546,18 +821,21 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         let mut output = Frame::new(Size::new(self.width, height));
         let mut pos = Point::zero();
 
-        for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let size = Size::new(rcol, height);
-                let mut pod = Regions::one(size, Axes::splat(true));
-                if self.rows[y] == Sizing::Auto {
-                    pod.full = self.regions.full;
+        for x in 0..self.rcols.len() {
+            if let Some((cell, span)) = self.cell(x, y) {
+                if span.y == 1 {
+                    let size = Size::new(self.span_width(x, span.x), height);
+                    let mut pod = Regions::one(size, Axes::splat(true));
+                    if self.rows[y] == Sizing::Auto {
+                        pod.full = self.regions.full;
+                    }
+                    let mut frame = cell.layout(self.vt, self.styles, pod)?.into_frame();
+                    self.tag_cell(x, y, self.repeated_copy(), &mut frame);
+                    output.push_frame(pos, frame);
                 }
-                let frame = cell.layout(self.vt, self.styles, pod)?.into_frame();
-                output.push_frame(pos, frame);
             }
 
-            pos.x += rcol;
+            pos.x += self.rcols[x];
         }
 
         Ok(output)
@@ -579,18 +857,22 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
         // Layout the row.
         let mut pos = Point::zero();
-        for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                pod.size.x = rcol;
-
-                // Push the layouted frames into the individual output frames.
-                let fragment = cell.layout(self.vt, self.styles, pod)?;
-                for (output, frame) in outputs.iter_mut().zip(fragment) {
-                    output.push_frame(pos, frame);
+        for x in 0..self.rcols.len() {
+            if let Some((cell, span)) = self.cell(x, y) {
+                if span.y == 1 {
+                    pod.size.x = self.span_width(x, span.x);
+
+                    // Push the layouted frames into the individual output
+                    // frames.
+                    let fragment = cell.layout(self.vt, self.styles, pod)?;
+                    for (output, mut frame) in outputs.iter_mut().zip(fragment) {
+                        self.tag_cell(x, y, self.repeated_copy(), &mut frame);
+                        output.push_frame(pos, frame);
+                    }
                 }
             }
 
-            pos.x += rcol;
+            pos.x += self.rcols[x];
         }
 
         Ok(Fragment::frames(outputs))
@@ -602,8 +884,43 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         self.lrows.push(Row::Frame(frame, y));
     }
 
-    /// Finish rows for one region.
+    /// Finish rows for one region and continue in the next one.
     fn finish_region(&mut self) -> SourceResult<()> {
+        self.finish_rows()?;
+        self.regions.next();
+        self.initial = self.regions.size;
+
+        // A header or footer that breaks across regions isn't repeated again.
+        if !self.repeating {
+            self.start_region()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reserve space for the footer and layout the header at the top of the
+    /// current region.
+    fn start_region(&mut self) -> SourceResult<()> {
+        self.regions.size.y -= self.footer_height;
+        self.repeating = true;
+        for y in 0..self.header {
+            self.layout_row(y)?;
+        }
+        self.repeating = false;
+        Ok(())
+    }
+
+    /// Layout the footer and finish the rows of the current region.
+    fn finish_rows(&mut self) -> SourceResult<()> {
+        if !self.repeating {
+            self.regions.size.y += self.footer_height;
+            self.repeating = true;
+            for y in self.footer..self.rows.len() {
+                self.layout_row(y)?;
+            }
+            self.repeating = false;
+        }
+
         // Determine the height of existing rows in the region.
         let mut used = Abs::zero();
         let mut fr = Fr::zero();
@@ -645,36 +962,204 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
         self.finished.push(output);
         self.rrows.push(rrows);
-        self.regions.next();
-        self.initial = self.regions.size;
 
         Ok(())
     }
 
-    /// Get the content of the cell in column `x` and row `y`.
+    /// Determine the height that cells spanning multiple rows and ending in
+    /// row `y` still need in addition to the rows above, if any.
+    ///
+    /// Only cells whose rows all ended up in the current region are taken into
+    /// account.
+    fn measure_row_spans(&mut self, y: usize) -> SourceResult<Option<Abs>> {
+        let mut needed = None;
+        for i in 0..self.placements.list.len() {
+            let Placement { index, x, y: start, span } = self.placements.list[i];
+            if span.y == 1 || start + span.y - 1 != y {
+                continue;
+            }
+
+            // Sum up the height of the spanned rows in this region.
+            let mut used = Abs::zero();
+            let mut complete = false;
+            for row in &self.lrows {
+                match *row {
+                    Row::Frame(ref frame, ry) if (start..y).contains(&ry) => {
+                        complete |= ry == start;
+                        used += frame.height();
+                    }
+                    Row::Fr(_, ry) if (start..y).contains(&ry) => {
+                        complete = false;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            if !complete {
+                continue;
+            }
+
+            let size = Size::new(self.span_width(x, span.x), self.regions.base().y);
+            let pod = Regions::one(size, Axes::splat(false));
+            let cells = self.cells;
+            let frame = cells[index].measure(self.vt, self.styles, pod)?.into_frame();
+            let remaining = frame.height() - used;
+            if remaining > Abs::zero() {
+                needed.get_or_insert(Abs::zero()).set_max(remaining);
+            }
+        }
+
+        Ok(needed)
+    }
+
+    /// Layout cells that span multiple rows into the finished regions.
+    fn layout_row_spans(&mut self) -> SourceResult<()> {
+        for i in 0..self.placements.list.len() {
+            let Placement { index, x, y, span } = self.placements.list[i];
+            if span.y == 1 {
+                continue;
+            }
+
+            // Find the extent of the spanned rows in each region.
+            let mut pieces = vec![];
+            for (r, rows) in self.rrows.iter().enumerate() {
+                let mut top = Abs::zero();
+                let mut piece: Option<(Abs, Abs)> = None;
+                for row in rows {
+                    if (y..y + span.y).contains(&row.y) {
+                        piece.get_or_insert((top, Abs::zero())).1 += row.height;
+                    }
+                    top += row.height;
+                }
+                if let Some((top, height)) = piece {
+                    pieces.push((r, top, height));
+                }
+            }
+
+            // Cells in the header or footer are laid out anew in each region,
+            // all others flow through the regions.
+            let repeated = y + span.y <= self.header || y >= self.footer;
+            let chunk = if repeated { 1 } else { pieces.len().max(1) };
+            for group in pieces.chunks(chunk) {
+                let (_, _, first) = group[0];
+                let backlog: Vec<_> = group[1..].iter().map(|&(_, _, h)| h).collect();
+                let size = Size::new(self.span_width(x, span.x), first);
+                let mut pod = Regions::one(size, Axes::splat(true));
+                pod.backlog = &backlog;
+
+                let dx = self.rcols[..x].iter().sum();
+                let cells = self.cells;
+                let fragment = cells[index].layout(self.vt, self.styles, pod)?;
+                for (&(r, top, _), mut frame) in group.iter().zip(fragment) {
+                    self.tag_cell(x, y, repeated && r > 0, &mut frame);
+                    self.finished[r].push_frame(Point::new(dx, top), frame);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the current region is full and a region break is called for.
+    fn is_full(&self) -> bool {
+        Abs::zero().fits(self.regions.size.y) && !self.in_last()
+    }
+
+    /// Whether the current region is the last usable region.
     ///
-    /// Returns `None` if it's a gutter cell.
+    /// Since the header and footer take up space in every region, a region
+    /// that doesn't contain anything else yet is as good as the next one.
+    fn in_last(&self) -> bool {
+        self.regions.backlog.is_empty()
+            && self.regions.last.map_or(true, |height| {
+                self.initial.y == height
+                    && self.lrows.iter().all(|row| match *row {
+                        Row::Frame(_, y) => y < self.header,
+                        Row::Fr(..) => true,
+                    })
+            })
+    }
+
+    /// Whether the header or footer is being laid out again in a followup
+    /// region.
+    fn repeated_copy(&self) -> bool {
+        self.repeating && !self.finished.is_empty()
+    }
+
+    /// Tag the frame of the cell whose top-left track is in column `x` and
+    /// row `y`.
+    fn tag_cell(&self, x: usize, y: usize, artifact: bool, frame: &mut Frame) {
+        let Some(placement) = self.placements.at(x, y) else { return };
+        if let Some(&Some(tag)) = self.tags.get(placement.index) {
+            frame.tag(if artifact { Tag::Artifact } else { tag });
+        }
+    }
+
+    /// The width of `span` columns starting at column `x`.
+    fn span_width(&self, x: usize, span: usize) -> Abs {
+        self.rcols[x..x + span].iter().sum()
+    }
+
+    /// Get the content and span of the cell whose top-left track is in column
+    /// `x` and row `y`.
+    ///
+    /// Returns `None` if it's a gutter cell, an empty cell or covered by a cell
+    /// that starts elsewhere.
     #[track_caller]
-    fn cell(&self, mut x: usize, y: usize) -> Option<&'a Content> {
+    fn cell(&self, x: usize, y: usize) -> Option<(&'a Content, Axes<usize>)> {
         assert!(x < self.cols.len());
         assert!(y < self.rows.len());
 
-        // Columns are reorder, but the cell slice is not.
-        if self.is_rtl {
-            x = self.cols.len() - 1 - x;
+        let placement = *self.placements.at(x, y)?;
+        let cells = self.cells;
+        (placement.x == x && placement.y == y)
+            .then(|| (&cells[placement.index], placement.span))
+    }
+}
+
+/// Place cells with the given spans into a grid with `c` columns.
+///
+/// The cells are placed in row-major order, each at the next position where it
+/// fits. The cells at the indices in `breaks` start on a new row below all
+/// earlier cells. Returns the column and row of each cell and the number of
+/// rows.
+pub fn place_cells(
+    spans: &[Axes<NonZeroUsize>],
+    c: usize,
+    breaks: &[usize],
+) -> (Vec<Axes<usize>>, usize) {
+    let mut occupied: Vec<bool> = vec![];
+    let mut positions = Vec::with_capacity(spans.len());
+    let mut rows = 0;
+    let mut cursor = 0;
+
+    for (i, span) in spans.iter().enumerate() {
+        if breaks.contains(&i) {
+            cursor = rows * c;
         }
 
-        if self.has_gutter {
-            // Even columns and rows are children, odd ones are gutter.
-            if x % 2 == 0 && y % 2 == 0 {
-                let c = 1 + self.cols.len() / 2;
-                self.cells.get((y / 2) * c + x / 2)
-            } else {
-                None
-            }
-        } else {
-            let c = self.cols.len();
-            self.cells.get(y * c + x)
+        let w = span.x.get().min(c);
+        let h = span.y.get();
+
+        // Earlier cells can only reach into later rows at the top of this cell,
+        // so checking the top row suffices.
+        let free = |i: usize| !occupied.get(i).copied().unwrap_or(false);
+        while cursor % c + w > c || !(cursor..cursor + w).all(free) {
+            cursor += 1;
+        }
+
+        let (x, y) = (cursor % c, cursor / c);
+        occupied.resize(occupied.len().max((y + h) * c), false);
+        for dy in 0..h {
+            let start = (y + dy) * c + x;
+            occupied[start..start + w].fill(true);
         }
+
+        positions.push(Axes::new(x, y));
+        rows = rows.max(y + h);
+        cursor += w;
     }
+
+    (positions, rows)
 }
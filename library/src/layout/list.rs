@@ -138,7 +138,7 @@ impl Layout for ListElem {
             cells.push(item.body().styled(Self::set_depth(Depth)));
         }
 
-        let layouter = GridLayouter::new(
+        let mut layouter = GridLayouter::new(
             vt,
             Axes::with_x(&[
                 Sizing::Rel(indent.into()),
@@ -148,11 +148,30 @@ impl Layout for ListElem {
             ]),
             Axes::with_y(&[gutter.into()]),
             &cells,
+            &[],
+            0,
+            0,
             regions,
             styles,
         );
 
-        Ok(layouter.layout()?.fragment)
+        // The markers are the labels of the items in the document's structure.
+        layouter.tag_cells(
+            (0..cells.len())
+                .map(|i| match i % 4 {
+                    1 => Some(Tag::ListLabel(i / 4)),
+                    3 => Some(Tag::ListBody(i / 4)),
+                    _ => None,
+                })
+                .collect(),
+        );
+
+        let mut fragment = layouter.layout()?.fragment;
+        for (i, frame) in fragment.iter_mut().enumerate() {
+            frame.tag(Tag::List(i > 0));
+        }
+
+        Ok(fragment)
     }
 }
 
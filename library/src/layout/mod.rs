@@ -398,7 +398,8 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
 
 /// Accepts pagebreaks and pages.
 struct DocBuilder<'a> {
-    /// The page runs built so far.
+    /// The page runs built so far and the page breaks between them that
+    /// require a page of a certain parity.
     pages: StyleVecBuilder<'a, Content>,
     /// Whether to keep a following page even if it is empty.
     keep_next: bool,
@@ -408,6 +409,9 @@ impl<'a> DocBuilder<'a> {
     fn accept(&mut self, content: &Content, styles: StyleChain<'a>) -> bool {
         if let Some(pagebreak) = content.to::<PagebreakElem>() {
             self.keep_next = !pagebreak.weak(styles);
+            if pagebreak.to(styles).is_some() {
+                self.pages.push(content.clone(), styles);
+            }
             return true;
         }
 
@@ -4,6 +4,11 @@ use std::str::FromStr;
 use super::{AlignElem, ColumnsElem};
 use crate::meta::{Counter, CounterKey, Numbering};
 use crate::prelude::*;
+use crate::text::{TextDir, TextElem};
+
+/// The default margins as a fraction of the page's shorter side. This results
+/// in margins of 2.5cm on A4 paper.
+pub(crate) const DEFAULT_MARGIN: f64 = 2.5 / 21.0;
 
 /// Layouts its child onto one or multiple pages.
 ///
@@ -97,11 +102,19 @@ pub struct PageElem {
     ///   - `right`: The right margin.
     ///   - `bottom`: The bottom margin.
     ///   - `left`: The left margin.
+    ///   - `inside`: The margin at the inner side of the page (where the
+    ///     [binding]($func/page.binding) is).
+    ///   - `outside`: The margin at the outer side of the page (opposite to the
+    ///     [binding]($func/page.binding)).
     ///   - `x`: The horizontal margins.
     ///   - `y`: The vertical margins.
     ///   - `rest`: The margins on all sides except those for which the
     ///     dictionary explicitly sets a size.
     ///
+    /// The `left` and `right` keys cannot be combined with `inside` and
+    /// `outside`. When the latter are used, the horizontal margins swap
+    /// between odd and even pages.
+    ///
     /// ```example
     /// #set page(
     ///  width: 3cm,
@@ -116,7 +129,29 @@ pub struct PageElem {
     /// )
     /// ```
     #[fold]
-    pub margin: Sides<Option<Smart<Rel<Length>>>>,
+    pub margin: Margin,
+
+    /// On which side the pages will be bound.
+    ///
+    /// This determines which side the `inside` and `outside`
+    /// [margins]($func/page.margin) refer to. When set to `{auto}`, the binding
+    /// is on the left for left-to-right text and on the right for
+    /// right-to-left and vertical text.
+    ///
+    /// ```example
+    /// #set page(
+    ///   width: 80pt,
+    ///   height: 60pt,
+    ///   margin: (inside: 24pt, outside: 8pt, y: 8pt),
+    ///   binding: right,
+    ///   fill: aqua,
+    /// )
+    ///
+    /// Odd page
+    /// #pagebreak()
+    /// Even page
+    /// ```
+    pub binding: Smart<Binding>,
 
     /// How many columns the page has.
     ///
@@ -193,7 +228,33 @@ pub struct PageElem {
     ///
     /// #lorem(19)
     /// ```
-    pub header: Option<Content>,
+    ///
+    /// The header can also be given as a dictionary to use different headers
+    /// on different pages:
+    ///
+    /// - `odd`: The header on odd pages.
+    /// - `even`: The header on even pages.
+    /// - `first`: The header on the first page of a page run. A page run starts
+    ///   at the beginning of the document, after a [page break]($func/pagebreak)
+    ///   and after a page set rule. Thus, when chapters start with a page break,
+    ///   this is the header of their opening pages.
+    /// - `rest`: The header on all pages for which the dictionary doesn't set
+    ///   one explicitly.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 80pt,
+    ///   margin: (top: 24pt),
+    ///   header: (
+    ///     first: none,
+    ///     odd: align(right)[_Odd_],
+    ///     even: [_Even_],
+    ///   ),
+    /// )
+    ///
+    /// #lorem(36)
+    /// ```
+    pub header: Option<Marginal>,
 
     /// The amount the header is raised into the top margin.
     #[resolve]
@@ -223,7 +284,10 @@ pub struct PageElem {
     ///
     /// #lorem(48)
     /// ```
-    pub footer: Option<Content>,
+    ///
+    /// Just like the [header]($func/page.header), the footer can differ
+    /// between odd, even and first pages by passing a dictionary.
+    pub footer: Option<Marginal>,
 
     /// The amount the footer is lowered into the bottom margin.
     #[resolve]
@@ -272,7 +336,15 @@ pub struct PageElem {
 
 impl PageElem {
     /// Layout the page run into a sequence of frames, one per page.
-    pub fn layout(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Fragment> {
+    ///
+    /// The `number` is the physical page number of the run's first page in
+    /// the document. It decides which pages are odd and even.
+    pub fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        mut number: NonZeroUsize,
+    ) -> SourceResult<Fragment> {
         // When one of the lengths is infinite the page fits its content along
         // that axis.
         let width = self.width(styles).unwrap_or(Abs::inf());
@@ -287,9 +359,21 @@ impl PageElem {
             min = Paper::A4.width();
         }
 
-        // Determine the margins.
-        let default = Rel::from(0.1190 * min);
-        let padding = self.margin(styles).map(|side| side.unwrap_or(default));
+        // Determine the margins. For two-sided margins, the inside margin is
+        // on the left at first and swapped per page further below.
+        let default = Rel::from(DEFAULT_MARGIN * min);
+        let margin = self.margin(styles);
+        let two_sided = margin.two_sided.unwrap_or(false);
+        let padding = margin
+            .sides
+            .map(|side| side.unwrap_or(Smart::Auto).unwrap_or(default));
+        let binding = self.binding(styles).unwrap_or_else(|| {
+            if TextElem::dir_in(styles) == Dir::LTR && !TextDir::is_vertical(styles) {
+                Binding::Left
+            } else {
+                Binding::Right
+            }
+        });
 
         let mut child = self.body();
 
@@ -303,15 +387,16 @@ impl PageElem {
         child = child.padded(padding);
 
         // Layout the child.
-        let regions = Regions::repeat(size, size.map(Abs::is_finite));
+        let mut regions = Regions::repeat(size, size.map(Abs::is_finite));
+        regions.root = true;
         let mut fragment = child.layout(vt, styles, regions)?;
 
         let fill = self.fill(styles);
         let foreground = self.foreground(styles);
         let background = self.background(styles);
-        let header = self.header(styles);
+        let headers = self.header(styles);
         let header_ascent = self.header_ascent(styles);
-        let footer = self.footer(styles).or_else(|| {
+        let footers = self.footer(styles).or_else(|| {
             self.numbering(styles).map(|numbering| {
                 let both = match &numbering {
                     Numbering::Pattern(pattern) => pattern.pieces() >= 2,
@@ -320,15 +405,26 @@ impl PageElem {
                 Counter::new(CounterKey::Page)
                     .display(Some(numbering), both)
                     .aligned(self.number_align(styles))
+                    .into()
             })
         });
         let footer_descent = self.footer_descent(styles);
 
         // Realize overlays.
-        for frame in &mut fragment {
+        for (i, frame) in fragment.iter_mut().enumerate() {
             let size = frame.size();
-            let pad = padding.resolve(styles).relative_to(size);
+            let mut pad = padding.resolve(styles).relative_to(size);
+            if two_sided && binding.swap(number) {
+                frame.translate(Point::with_x(pad.right - pad.left));
+                std::mem::swap(&mut pad.left, &mut pad.right);
+            }
+
             let pw = size.x - pad.left - pad.right;
+            let select = |marginal: &Option<Marginal>| {
+                marginal.as_ref().and_then(|m| m.select(number, i == 0)).cloned()
+            };
+            let header = select(&headers);
+            let footer = select(&footers);
             for marginal in [&header, &footer, &background, &foreground] {
                 let Some(content) = marginal else { continue };
 
@@ -365,6 +461,8 @@ impl PageElem {
             if let Some(fill) = &fill {
                 frame.fill(fill.clone());
             }
+
+            number = number.saturating_add(1);
         }
 
         Ok(fragment)
@@ -393,37 +491,245 @@ pub struct PagebreakElem {
     /// empty.
     #[default(false)]
     pub weak: bool,
+
+    /// If given, ensures that the next page will be an even or odd page. If
+    /// the page after the break would have the wrong parity, an empty page is
+    /// inserted in between. The empty page has no header, footer, page number
+    /// or foreground, but it still counts towards the page numbers.
+    ///
+    /// This is useful to start chapters on right-hand pages in books.
+    ///
+    /// ```example
+    /// #set page(height: 30pt)
+    ///
+    /// First.
+    /// #pagebreak(to: "odd")
+    /// Third.
+    /// ```
+    pub to: Option<Parity>,
+}
+
+/// Whether something should be even or odd.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Parity {
+    /// Next page will be an even page.
+    Even,
+    /// Next page will be an odd page.
+    Odd,
+}
+
+impl Parity {
+    /// Whether the given page number matches the parity.
+    pub fn matches(self, number: NonZeroUsize) -> bool {
+        match self {
+            Self::Even => number.get() % 2 == 0,
+            Self::Odd => number.get() % 2 == 1,
+        }
+    }
 }
 
-/// A header, footer, foreground or background definition.
-#[derive(Debug, Clone, Hash)]
-pub enum Marginal {
-    /// Bare content.
-    Content(Content),
-    /// A closure mapping from a page number to content.
-    Func(Func),
+/// The margins of a page.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Margin {
+    /// The margins for each side. For two-sided margins, `left` holds the
+    /// inside and `right` the outside margin.
+    pub sides: Sides<Option<Smart<Rel<Length>>>>,
+    /// Whether the horizontal margins are inside and outside margins that
+    /// swap between odd and even pages.
+    pub two_sided: Option<bool>,
+}
+
+impl Margin {
+    /// Create an instance with the same margin on all sides.
+    pub fn splat(value: Option<Smart<Rel<Length>>>) -> Self {
+        Self { sides: Sides::splat(value), two_sided: None }
+    }
+}
+
+impl Fold for Margin {
+    type Output = Self;
+
+    fn fold(self, outer: Self::Output) -> Self::Output {
+        let sides = self
+            .sides
+            .zip(outer.sides)
+            .map(|(inner, outer)| inner.or(outer));
+        let two_sided = self.two_sided.or(outer.two_sided);
+        Self { sides, two_sided }
+    }
+}
+
+cast_from_value! {
+    Margin,
+    v: Smart<Rel<Length>> => Self::splat(Some(v)),
+    v: Dict => {
+        let mut dict = v;
+        let mut take = |key| {
+            dict.take(key).ok().map(Value::cast::<Smart<Rel<Length>>>).transpose()
+        };
+
+        let rest = take("rest")?;
+        let x = take("x")?.or(rest);
+        let y = take("y")?.or(rest);
+        let top = take("top")?.or(y);
+        let bottom = take("bottom")?.or(y);
+        let left = take("left")?;
+        let right = take("right")?;
+        let inside = take("inside")?;
+        let outside = take("outside")?;
+
+        let two_sided = inside.is_some() || outside.is_some();
+        if two_sided && (left.is_some() || right.is_some()) {
+            Err("inside and outside margins cannot be combined with left and right")?;
+        }
+
+        dict.finish(&[
+            "left", "top", "right", "bottom", "inside", "outside", "x", "y", "rest",
+        ])?;
+
+        Self {
+            sides: Sides {
+                left: inside.or(left).or(x),
+                top,
+                right: outside.or(right).or(x),
+                bottom,
+            },
+            two_sided: if two_sided {
+                Some(true)
+            } else if left.is_some() || right.is_some() {
+                Some(false)
+            } else {
+                None
+            },
+        }
+    },
+}
+
+cast_to_value! {
+    v: Margin => {
+        if v.two_sided != Some(true) {
+            return v.sides.into();
+        }
+
+        let mut dict = Dict::new();
+        let sides = v.sides;
+        let pairs = [
+            ("inside", sides.left),
+            ("top", sides.top),
+            ("outside", sides.right),
+            ("bottom", sides.bottom),
+        ];
+        for (key, side) in pairs {
+            if let Some(side) = side {
+                dict.insert(key.into(), side.into());
+            }
+        }
+        dict.into()
+    }
+}
+
+/// On which side the pages will be bound.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Binding {
+    /// Bound on the left, as customary in LTR settings.
+    Left,
+    /// Bound on the right, as customary in RTL settings.
+    Right,
+}
+
+impl Binding {
+    /// Whether the binding is on the right side of the page with the given
+    /// number, so that inside and outside margins must be swapped.
+    fn swap(self, number: NonZeroUsize) -> bool {
+        match self {
+            Self::Left => number.get() % 2 == 0,
+            Self::Right => number.get() % 2 == 1,
+        }
+    }
+}
+
+cast_from_value! {
+    Binding,
+    v: GenAlign => match v {
+        GenAlign::Specific(Align::Left) => Self::Left,
+        GenAlign::Specific(Align::Right) => Self::Right,
+        _ => Err("binding must be left or right")?,
+    },
+}
+
+cast_to_value! {
+    v: Binding => GenAlign::from(match v {
+        Binding::Left => Align::Left,
+        Binding::Right => Align::Right,
+    })
+    .into()
+}
+
+/// A header or footer definition that can differ between pages.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Marginal {
+    /// The content on odd pages.
+    pub odd: Option<Content>,
+    /// The content on even pages.
+    pub even: Option<Content>,
+    /// The content on the first page of a page run, if it differs from the
+    /// odd or even one.
+    pub first: Option<Option<Content>>,
 }
 
 impl Marginal {
-    /// Resolve the marginal based on the page number.
-    pub fn resolve(&self, vt: &mut Vt, page: usize) -> SourceResult<Content> {
-        Ok(match self {
-            Self::Content(content) => content.clone(),
-            Self::Func(func) => func.call_vt(vt, [Value::Int(page as i64)])?.display(),
-        })
+    /// Select the content for the page with the given physical number.
+    pub fn select(&self, number: NonZeroUsize, first: bool) -> Option<&Content> {
+        match &self.first {
+            Some(content) if first => content.as_ref(),
+            _ if number.get() % 2 == 1 => self.odd.as_ref(),
+            _ => self.even.as_ref(),
+        }
+    }
+}
+
+impl From<Content> for Marginal {
+    fn from(content: Content) -> Self {
+        Self {
+            odd: Some(content.clone()),
+            even: Some(content),
+            first: None,
+        }
     }
 }
 
 cast_from_value! {
     Marginal,
-    v: Content => Self::Content(v),
-    v: Func => Self::Func(v),
+    v: Content => v.into(),
+    v: Dict => {
+        let mut dict = v;
+        let mut take = |key| {
+            dict.take(key).ok().map(Value::cast::<Option<Content>>).transpose()
+        };
+
+        let rest = take("rest")?.flatten();
+        let odd = take("odd")?.unwrap_or_else(|| rest.clone());
+        let even = take("even")?.unwrap_or_else(|| rest.clone());
+        let first = take("first")?;
+        dict.finish(&["odd", "even", "first", "rest"])?;
+
+        Self { odd, even, first }
+    },
 }
 
 cast_to_value! {
-    v: Marginal => match v {
-        Marginal::Content(v) => v.into(),
-        Marginal::Func(v) => v.into(),
+    v: Marginal => {
+        if v.first.is_none() && v.odd == v.even {
+            return v.odd.into();
+        }
+
+        let mut dict = Dict::new();
+        dict.insert("odd".into(), v.odd.into());
+        dict.insert("even".into(), v.even.into());
+        if let Some(first) = v.first {
+            dict.insert("first".into(), first.into());
+        }
+        dict.into()
     }
 }
 
@@ -8,7 +8,8 @@ use crate::layout::AlignElem;
 use crate::math::EquationElem;
 use crate::prelude::*;
 use crate::text::{
-    shape, LinebreakElem, Quoter, Quotes, ShapedText, SmartQuoteElem, SpaceElem, TextElem,
+    shape, warn_unknown_families, LinebreakElem, Quoter, Quotes, ShapedText,
+    SmartQuoteElem, SpaceElem, TextDir, TextElem,
 };
 
 /// Arrange text, spacing and inline-level elements into a paragraph.
@@ -98,6 +99,48 @@ pub struct ParElem {
     #[resolve]
     pub hanging_indent: Length,
 
+    /// The minimum number of lines of a paragraph that must stay together at
+    /// the bottom of a page or column.
+    ///
+    /// If fewer lines would fit, the whole paragraph moves to the next page
+    /// or column, together with a preceding heading.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #set par(orphans: 3)
+    /// #lorem(10)
+    ///
+    /// #lorem(20)
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub orphans: NonZeroUsize,
+
+    /// The minimum number of lines of a paragraph that must stay together at
+    /// the top of a page or column.
+    ///
+    /// If fewer lines would be left over, more lines move along to the next
+    /// page or column.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #set par(widows: 3)
+    /// #lorem(30)
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub widows: NonZeroUsize,
+
+    /// The maximum number of consecutive lines that may end with a hyphen.
+    ///
+    /// When set to `{none}`, any number of consecutive lines can be
+    /// hyphenated.
+    ///
+    /// ```example
+    /// #set page(width: 120pt)
+    /// #set par(justify: true, consecutive-hyphens: 1)
+    /// #lorem(20)
+    /// ```
+    pub consecutive_hyphens: Option<NonZeroUsize>,
+
     /// The contents of the paragraph.
     #[external]
     pub body: Content,
@@ -148,6 +191,10 @@ impl ParElem {
             let mut vt = Vt { world, tracer, provider, introspector };
             let children = par.children();
 
+            // In vertical writing mode, lines run along the region's height.
+            let vertical = TextDir::is_vertical(styles);
+            let region = if vertical { Size::new(region.y, region.x) } else { region };
+
             // Collect all text into one string for BiDi analysis.
             let (text, segments, spans) = collect(&children, &styles, consecutive)?;
 
@@ -159,8 +206,9 @@ impl ParElem {
             // Break the paragraph into lines.
             let lines = linebreak(&vt, &p, region.x - p.hang);
 
-            // Stack the lines into one frame per region.
-            finalize(&mut vt, &p, &lines, region, expand)
+            // Build one frame per line.
+            let fragment = finalize(&mut vt, &p, &lines, region, expand)?;
+            Ok(if vertical { rotate(fragment) } else { fragment })
         }
 
         cached(
@@ -474,6 +522,12 @@ impl<'a> Line<'a> {
         stretch
     }
 
+    /// How much the line's glyphs can be stretched or shrunk by font
+    /// expansion.
+    fn expandability(&self) -> Abs {
+        self.items().filter_map(Item::text).map(ShapedText::expandability).sum()
+    }
+
     /// The sum of fractions in the line.
     fn fr(&self) -> Fr {
         self.items()
@@ -639,6 +693,7 @@ fn prepare<'a>(
         let end = cursor + segment.len();
         match segment {
             Segment::Text(_) => {
+                warn_unknown_families(vt, styles, spans.span_at(cursor).0);
                 shape_range(&mut items, vt, &bidi, cursor..end, &spans, styles);
             }
             Segment::Spacing(spacing) => match spacing {
@@ -782,8 +837,16 @@ fn linebreak_simple<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line
     let mut lines = vec![];
     let mut start = 0;
     let mut last = None;
+    let limit = ParElem::consecutive_hyphens_in(p.styles);
+    let mut hyphens = 0;
 
     for (end, mandatory, hyphen) in breakpoints(p) {
+        // Skip hyphenation opportunities once too many lines in a row already
+        // end with a hyphen.
+        if hyphen && limit.map_or(false, |limit| hyphens >= limit.get()) {
+            continue;
+        }
+
         // Compute the line and its size.
         let mut attempt = line(vt, p, start..end, mandatory, hyphen);
 
@@ -791,9 +854,13 @@ fn linebreak_simple<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line
         // into the stack and rebuild the line from the attempt's end. The
         // resulting line cannot be broken up further.
         if !width.fits(attempt.width) {
-            if let Some((last_attempt, last_end)) = last.take() {
+            if let Some((last_attempt, last_end, last_hyphen)) = last.take() {
                 lines.push(last_attempt);
                 start = last_end;
+                hyphens = if last_hyphen { hyphens + 1 } else { 0 };
+                if hyphen && limit.map_or(false, |limit| hyphens >= limit.get()) {
+                    continue;
+                }
                 attempt = line(vt, p, start..end, mandatory, hyphen);
             }
         }
@@ -805,12 +872,13 @@ fn linebreak_simple<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line
             lines.push(attempt);
             start = end;
             last = None;
+            hyphens = if hyphen { hyphens + 1 } else { 0 };
         } else {
-            last = Some((attempt, end));
+            last = Some((attempt, end, hyphen));
         }
     }
 
-    if let Some((line, _)) = last {
+    if let Some((line, ..)) = last {
         lines.push(line);
     }
 
@@ -839,10 +907,15 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
     type Cost = f64;
 
     /// An entry in the dynamic programming table.
+    ///
+    /// There is one entry per breakpoint and number of consecutive lines
+    /// ending with a hyphen. Otherwise, a cheap chain of hyphenated lines could
+    /// crowd out a slightly worse one that may still take another hyphen.
     struct Entry<'a> {
         pred: usize,
         total: Cost,
         line: Line<'a>,
+        hyphens: usize,
     }
 
     // Cost parameters.
@@ -858,14 +931,19 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
         pred: 0,
         total: 0.0,
         line: line(vt, p, 0..0, false, false),
+        hyphens: 0,
     }];
 
     let em = TextElem::size_in(p.styles);
+    let limit = ParElem::consecutive_hyphens_in(p.styles);
+
+    // Where the entries for the current breakpoint start in the table.
+    let mut k = 0;
 
     for (end, mandatory, hyphen) in breakpoints(p) {
-        let k = table.len();
+        k = table.len();
         let eof = end == p.bidi.text.len();
-        let mut best: Option<Entry> = None;
+        let mut best: Vec<Entry> = vec![];
 
         // Find the optimal predecessor.
         for (i, pred) in table.iter_mut().enumerate().skip(active) {
@@ -874,10 +952,16 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
             let attempt = line(vt, p, start..end, mandatory, hyphen);
 
             // Determine how much the line's spaces would need to be stretched
-            // to make it the desired width.
-            let delta = width - attempt.width;
+            // to make it the desired width. Font expansion absorbs part of
+            // the difference first.
+            let mut delta = width - attempt.width;
+            if attempt.justify || delta < Abs::zero() {
+                let expandability = attempt.expandability();
+                delta -= delta.clamp(-expandability, expandability);
+            }
+
             let mut ratio = delta / attempt.stretch();
-            if ratio.is_infinite() {
+            if !ratio.is_finite() {
                 ratio = delta / (em / 2.0);
             }
 
@@ -905,9 +989,18 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
                 ratio.powi(3).abs()
             };
 
-            // Penalize hyphens.
+            // Penalize hyphens and rule them out once too many lines in a row
+            // would end with one. Without a limit, the count doesn't matter.
+            let hyphens = match limit {
+                Some(limit) if hyphen => (pred.hyphens + 1).min(limit.get() + 1),
+                _ => 0,
+            };
+
             if hyphen {
                 cost += HYPH_COST;
+                if limit.map_or(false, |limit| hyphens > limit.get()) {
+                    cost += MAX_COST;
+                }
             }
 
             // Penalize two consecutive dashes (not necessarily hyphens) extra.
@@ -918,18 +1011,25 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
             // The total cost of this line and its chain of predecessors.
             let total = pred.total + cost;
 
-            // If this attempt is better than what we had before, take it!
-            if best.as_ref().map_or(true, |best| best.total >= total) {
-                best = Some(Entry { pred: i, total, line: attempt });
+            // If this attempt is better than what we had before with the same
+            // number of consecutive hyphens, take it!
+            let entry = Entry { pred: i, total, line: attempt, hyphens };
+            match best.iter_mut().find(|best| best.hyphens == hyphens) {
+                Some(best) if best.total >= total => *best = entry,
+                Some(_) => {}
+                None => best.push(entry),
             }
         }
 
-        table.push(best.unwrap());
+        table.extend(best);
     }
 
-    // Retrace the best path.
+    // Retrace the best path, starting at the cheapest entry for the end of
+    // the paragraph.
     let mut lines = vec![];
-    let mut idx = table.len() - 1;
+    let mut idx = (k..table.len())
+        .min_by(|&a, &b| table[a].total.total_cmp(&table[b].total))
+        .unwrap_or(0);
     while idx != 0 {
         table.truncate(idx + 1);
         let entry = table.pop().unwrap();
@@ -964,8 +1064,8 @@ struct Breakpoints<'a> {
     p: &'a Preparation<'a>,
     /// The inner iterator over the unicode line break opportunities.
     linebreaks: LineBreakIterator<'a>,
-    /// Iterator over syllables of the current word.
-    syllables: Option<hypher::Syllables<'a>>,
+    /// Iterator over the byte lengths of the current word's syllables.
+    syllables: Option<std::vec::IntoIter<usize>>,
     /// The current text offset.
     offset: usize,
     /// The trimmed end of the current word.
@@ -982,7 +1082,7 @@ impl Iterator for Breakpoints<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         // If we're currently in a hyphenated "word", process the next syllable.
         if let Some(syllable) = self.syllables.as_mut().and_then(Iterator::next) {
-            self.offset += syllable.len();
+            self.offset += syllable;
             if self.offset == self.suffix {
                 self.offset = self.end;
             }
@@ -1002,12 +1102,12 @@ impl Iterator for Breakpoints<'_> {
 
         // Hyphenate the next word.
         if self.p.hyphenate != Some(false) {
-            if let Some(lang) = self.lang(self.offset) {
-                let word = &self.p.bidi.text[self.offset..self.end];
-                let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
-                if !trimmed.is_empty() {
+            let word = &self.p.bidi.text[self.offset..self.end];
+            let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
+            if !trimmed.is_empty() {
+                if let Some(syllables) = self.syllables(self.offset, trimmed) {
                     self.suffix = self.offset + trimmed.len();
-                    self.syllables = Some(hypher::hyphenate(trimmed, lang));
+                    self.syllables = Some(syllables.into_iter());
                     return self.next();
                 }
             }
@@ -1030,6 +1130,59 @@ impl Breakpoints<'_> {
             .unwrap_or(false)
     }
 
+    /// Split the word at the given offset into syllables and return their byte
+    /// lengths.
+    ///
+    /// Exceptions take precedence over custom patterns, which in turn take
+    /// precedence over the built-in patterns for the text language.
+    fn syllables(&self, offset: usize, word: &str) -> Option<Vec<usize>> {
+        let styles = self
+            .p
+            .find(offset)
+            .and_then(Item::text)
+            .map_or(self.p.styles, |shaped| shaped.styles);
+
+        let breaks = if let Some(breaks) =
+            TextElem::hyphenation_exceptions_in(styles).find(word)
+        {
+            breaks
+        } else {
+            let mut breaks = if let Some(patterns) =
+                TextElem::hyphenation_patterns_in(styles)
+            {
+                patterns.hyphenate(word)
+            } else {
+                let lang = self.lang(offset)?;
+                hypher::hyphenate(word, lang)
+                    .scan(0, |i, syllable| {
+                        *i += syllable.chars().count();
+                        Some(*i)
+                    })
+                    .collect()
+            };
+
+            // Respect the minimum fragment lengths.
+            let len = word.chars().count();
+            let left = TextElem::hyphenation_left_min_in(styles).get();
+            let right = TextElem::hyphenation_right_min_in(styles).get();
+            breaks.retain(|&i| i >= left && i + right <= len);
+            breaks
+        };
+
+        let mut syllables = vec![];
+        let mut prev = 0;
+        let mut breaks = breaks.into_iter().peekable();
+        for (i, (idx, _)) in word.char_indices().enumerate() {
+            if breaks.next_if_eq(&i).is_some() && idx > prev {
+                syllables.push(idx - prev);
+                prev = idx;
+            }
+        }
+
+        syllables.push(word.len() - prev);
+        Some(syllables)
+    }
+
     /// The text language at the given offset.
     fn lang(&self, offset: usize) -> Option<hypher::Lang> {
         let lang = self.p.lang.or_else(|| {
@@ -1168,37 +1321,41 @@ fn finalize(
         region.x
     };
 
-    // Stack the lines into one frame per region.
+    // Build one frame per line. Widows and orphans are prevented by the flow
+    // layouter, which knows where the regions end.
     let mut frames: Vec<Frame> = lines
         .iter()
         .map(|line| commit(vt, p, line, width, region.y))
         .collect::<SourceResult<_>>()?;
 
-    // Prevent orphans.
-    let leading = ParElem::leading_in(p.styles);
-    if frames.len() >= 2 && !frames[1].is_empty() {
-        let second = frames.remove(1);
-        let first = &mut frames[0];
-        merge(first, second, leading);
-    }
-
-    // Prevent widows.
-    let len = frames.len();
-    if len >= 2 && !frames[len - 2].is_empty() {
-        let second = frames.pop().unwrap();
-        let first = frames.last_mut().unwrap();
-        merge(first, second, leading);
+    // All lines make up one paragraph in the document's structure.
+    for (i, frame) in frames.iter_mut().enumerate() {
+        frame.tag(Tag::Par(i > 0));
     }
 
     Ok(Fragment::frames(frames))
 }
 
-/// Merge two line frames
-fn merge(first: &mut Frame, second: Frame, leading: Abs) {
-    let offset = first.height() + leading;
-    let total = offset + second.height();
-    first.push_frame(Point::with_y(offset), second);
-    first.size_mut().y = total;
+/// Rotate the lines of a paragraph in vertical writing mode clockwise, turning
+/// each of them into a column.
+///
+/// The flow stacks the columns from right to left and breaks them across
+/// regions.
+fn rotate(fragment: Fragment) -> Fragment {
+    let columns = fragment
+        .into_iter()
+        .map(|mut frame| {
+            let size = frame.size();
+            frame.transform(
+                Transform::translate(size.y, Abs::zero())
+                    .pre_concat(Transform::rotate(Angle::deg(90.0))),
+            );
+            frame.set_size(Size::new(size.y, size.x));
+            frame
+        })
+        .collect();
+
+    Fragment::frames(columns)
 }
 
 /// Commit to a line and build its frame.
@@ -1218,14 +1375,14 @@ fn commit(
         offset += p.hang;
     }
 
-    // Handle hanging punctuation to the left.
+    // Handle hanging punctuation to the left. In right-to-left text, the
+    // leftmost glyph is at the end of the line.
     if let Some(Item::Text(text)) = reordered.first() {
         if let Some(glyph) = text.glyphs.first() {
-            if !text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+            if reordered.len() > 1 || text.glyphs.len() > 1 {
+                let (start, end) = TextElem::overhang_in(text.styles).amounts(glyph.c);
+                let ratio = if text.dir.is_positive() { start } else { end };
+                let amount = ratio * glyph.x_advance.at(text.size);
                 offset -= amount;
                 remaining += amount;
             }
@@ -1235,20 +1392,28 @@ fn commit(
     // Handle hanging punctuation to the right.
     if let Some(Item::Text(text)) = reordered.last() {
         if let Some(glyph) = text.glyphs.last() {
-            if text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
+            if reordered.len() > 1 || text.glyphs.len() > 1 {
+                let (start, end) = TextElem::overhang_in(text.styles).amounts(glyph.c);
+                let ratio = if text.dir.is_positive() { end } else { start };
+                let amount = ratio * glyph.x_advance.at(text.size);
                 remaining += amount;
             }
         }
     }
 
-    // Determine how much to justify each space.
+    // Determine how much to expand the glyphs and justify each space. Font
+    // expansion absorbs as much of the remaining space as it can, the spaces
+    // take the rest.
     let fr = line.fr();
     let mut justification = Abs::zero();
+    let mut expansion = 0.0;
     if remaining < Abs::zero() || (line.justify && fr.is_zero()) {
+        let expandability = line.expandability();
+        if expandability > Abs::zero() {
+            expansion = (remaining / expandability).clamp(-1.0, 1.0);
+            remaining -= expandability * expansion;
+        }
+
         let justifiables = line.justifiables();
         if justifiables > 0 {
             justification = remaining / justifiables as f64;
@@ -1287,7 +1452,7 @@ fn commit(
                 }
             }
             Item::Text(shaped) => {
-                let frame = shaped.build(vt, justification);
+                let frame = shaped.build(vt, justification, expansion);
                 push(&mut offset, frame);
             }
             Item::Frame(frame) => {
@@ -1354,25 +1519,3 @@ fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
 
     (reordered, starts_rtl)
 }
-
-/// How much a character should hang into the end margin.
-///
-/// For more discussion, see:
-/// https://recoveringphysicist.com/21/
-fn overhang(c: char) -> f64 {
-    match c {
-        // Dashes.
-        '–' | '—' => 0.2,
-        '-' => 0.55,
-
-        // Punctuation.
-        '.' | ',' => 0.8,
-        ':' | ';' => 0.3,
-
-        // Arabic and Ideographic
-        '\u{60C}' | '\u{6D4}' => 0.4,
-        '\u{3001}' | '\u{3002}' => 1.0,
-
-        _ => 0.0,
-    }
-}
@@ -6,6 +6,9 @@ use crate::prelude::*;
 /// always relative to its parent container and will be in the foreground of all
 /// other content in the container. Page margins will be respected.
 ///
+/// With `{float: true}`, the content instead floats to the top or bottom of
+/// its container and other content flows around it. If there isn't enough
+/// space left, it moves to the top or bottom of the next page or column.
 ///
 /// ## Example
 /// ```example
@@ -30,9 +33,44 @@ pub struct PlaceElem {
     /// When an axis of the page is `{auto}` sized, all alignments relative to that
     /// axis will be ignored, instead, the item will be placed in the origin of the
     /// axis.
+    ///
+    /// For floating content, only `{top}` and `{bottom}` are allowed
+    /// vertically. The alignment may also be `{auto}`, which picks whichever
+    /// of the two is closer to where the content appears.
     #[positional]
-    #[default(Axes::with_x(Some(GenAlign::Start)))]
-    pub alignment: Axes<Option<GenAlign>>,
+    #[default(Smart::Custom(Axes::with_x(Some(GenAlign::Start))))]
+    pub alignment: Smart<Axes<Option<GenAlign>>>,
+
+    /// Whether the content floats to the top or bottom of its container
+    /// instead of overlapping other content.
+    ///
+    /// Floating content takes up space in its container so that the remaining
+    /// content flows around it.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #let note(where, body) = place(
+    ///   center + where,
+    ///   float: true,
+    ///   clearance: 6pt,
+    ///   rect(body),
+    /// )
+    ///
+    /// #lorem(10)
+    /// #note(bottom)[Bottom 1]
+    /// #note(bottom)[Bottom 2]
+    /// #lorem(40)
+    /// #note(top)[Top]
+    /// #lorem(10)
+    /// ```
+    #[default(false)]
+    pub float: bool,
+
+    /// The amount of clearance between floating content and the other content
+    /// of the container.
+    #[resolve]
+    #[default(Em::new(1.5).into())]
+    pub clearance: Length,
 
     /// The horizontal displacement of the placed content.
     ///
@@ -60,21 +98,41 @@ impl Layout for PlaceElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        let float = self.float(styles);
+        let alignment = self.alignment(styles);
+        if float {
+            let vertical = alignment.map_or(true, |aligns| {
+                matches!(aligns.y, Some(GenAlign::Specific(Align::Top | Align::Bottom)))
+            });
+            if !vertical {
+                bail!(self.span(), "floating placement must be auto, top, or bottom");
+            }
+        } else if alignment.is_auto() {
+            bail!(self.span(), "automatic positioning is only available for floats");
+        }
+
         let out_of_flow = self.out_of_flow(styles);
+        let finite = regions.base().map(Abs::is_finite);
+
+        let child = self.body().moved(Axes::new(self.dx(styles), self.dy(styles)));
+
+        // Floating content is only aligned horizontally. The flow takes care
+        // of its vertical position.
+        if float {
+            let pod = Regions::one(regions.base(), Axes::new(finite.x, false));
+            let x = alignment.map_or(Some(Align::Center.into()), |aligns| aligns.x);
+            let child = child.aligned(Axes::new(x, None));
+            return child.layout(vt, styles, pod);
+        }
 
         // The pod is the base area of the region because for absolute
         // placement we don't really care about the already used area.
         let pod = {
-            let finite = regions.base().map(Abs::is_finite);
             let expand = finite & (regions.expand | out_of_flow);
             Regions::one(regions.base(), expand)
         };
 
-        let child = self
-            .body()
-            .moved(Axes::new(self.dx(styles), self.dy(styles)))
-            .aligned(self.alignment(styles));
-
+        let child = child.aligned(alignment.unwrap_or_default());
         let mut frame = child.layout(vt, styles, pod)?.into_frame();
 
         // If expansion is off, zero all sizes so that we don't take up any
@@ -91,7 +149,14 @@ impl PlaceElem {
     /// base origin. Instead of relative to the parent's current flow/cursor
     /// position.
     pub fn out_of_flow(&self, styles: StyleChain) -> bool {
-        self.alignment(styles).y.is_some()
+        self.alignment(styles).map_or(true, |aligns| aligns.y.is_some())
+    }
+
+    /// The vertical alignment of floating content: Either top or bottom, or
+    /// `auto` if the flow should pick the closer one.
+    pub fn float_align(&self, styles: StyleChain) -> Smart<Align> {
+        self.alignment(styles)
+            .map(|aligns| aligns.y.map_or(Align::Top, |y| y.resolve(styles)))
     }
 }
 
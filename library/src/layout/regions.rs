@@ -17,6 +17,9 @@ pub struct Regions<'a> {
     /// Whether elements should expand to fill the regions instead of shrinking
     /// to fit the content.
     pub expand: Axes<bool>,
+    /// Whether these are the root regions of a page or direct descendants
+    /// of them. Only in root regions are footnotes placed.
+    pub root: bool,
 }
 
 impl Regions<'_> {
@@ -28,6 +31,7 @@ impl Regions<'_> {
             backlog: &[],
             last: None,
             expand,
+            root: false,
         }
     }
 
@@ -39,6 +43,7 @@ impl Regions<'_> {
             backlog: &[],
             last: Some(size.y),
             expand,
+            root: false,
         }
     }
 
@@ -67,6 +72,7 @@ impl Regions<'_> {
             backlog,
             last: self.last.map(|y| f(Size::new(x, y)).y),
             expand: self.expand,
+            root: self.root,
         }
     }
 
@@ -1,6 +1,12 @@
-use crate::layout::{AlignElem, GridLayouter, TrackSizings};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use typst::eval::Scope;
+
+use crate::layout::{place_cells, AlignElem, GridLayouter, Placement, TrackSizings};
 use crate::meta::LocalName;
 use crate::prelude::*;
+use crate::text::TextElem;
 
 /// A table of items.
 ///
@@ -30,9 +36,24 @@ use crate::prelude::*;
 /// )
 /// ```
 ///
+/// Cells can span multiple columns and rows and override the table's
+/// properties with the [`table.cell`]($func/table.cell) function. Leading and
+/// trailing rows can be marked as a [header]($func/table.header) and a
+/// [footer]($func/table.footer) that are repeated on every page the table
+/// breaks across.
+///
 /// Display: Table
 /// Category: layout
 #[element(Layout, LocalName)]
+#[scope(
+    let mut scope = Scope::new();
+    scope.define("cell", TableCellElem::func());
+    scope.define("header", TableHeaderElem::func());
+    scope.define("footer", TableFooterElem::func());
+    scope.define("hline", TableHLineElem::func());
+    scope.define("vline", TableVLineElem::func());
+    scope
+)]
 pub struct TableElem {
     /// Defines the column sizes. See the [grid documentation]($func/grid) for
     /// more information on track sizing.
@@ -101,11 +122,30 @@ pub struct TableElem {
     /// How to stroke the cells.
     ///
     /// This can be a color, a stroke width, both, or `{none}` to disable
-    /// the stroke.
+    /// the stroke. To stroke the sides of the cells differently, pass a
+    /// dictionary with the keys `top`, `right`, `bottom`, `left`, `x`, `y`, and
+    /// `rest`. Like the fill, the stroke can also be a function that returns
+    /// one of these for a cell's column and row index. Where two cells meet,
+    /// the stroke of the lower or right cell takes precedence.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (x, y) => (
+    ///     top: if y == 1 { 1pt },
+    ///     left: if x > 0 { 0.5pt + gray },
+    ///   ),
+    ///   [*A*], [*B*], [*C*],
+    ///   [1], [2], [3],
+    /// )
+    /// ```
+    ///
+    /// Additional rules can be drawn with [`table.hline`]($func/table.hline)
+    /// and [`table.vline`]($func/table.vline).
     #[resolve]
     #[fold]
-    #[default(Some(PartialStroke::default()))]
-    pub stroke: Option<PartialStroke>,
+    #[default(Celled::Value(Sides::splat(Some(PartialStroke::default()))))]
+    pub stroke: Celled<Sides<Option<Option<PartialStroke>>>>,
 
     /// How much to pad the cells's content.
     ///
@@ -114,10 +154,51 @@ pub struct TableElem {
     pub inset: Rel<Length>,
 
     /// The contents of the table cells.
+    ///
+    /// A [header]($func/table.header) may only be the first and a
+    /// [footer]($func/table.footer) only the last child. Rules can be placed
+    /// between the cells.
     #[variadic]
     pub children: Vec<Content>,
 }
 
+impl TableElem {
+    /// Split the table's children into its header, its body cells and its
+    /// footer.
+    pub fn sections(
+        &self,
+    ) -> SourceResult<(Option<TableHeaderElem>, Vec<Content>, Option<TableFooterElem>)>
+    {
+        let children = self.children();
+        let len = children.len();
+        let mut header = None;
+        let mut body = vec![];
+        let mut footer = None;
+        for (i, child) in children.into_iter().enumerate() {
+            if let Some(elem) = child.to::<TableHeaderElem>() {
+                if i > 0 {
+                    bail!(child.span(), "table header must come first");
+                }
+                header = Some(elem.clone());
+            } else if let Some(elem) = child.to::<TableFooterElem>() {
+                if i + 1 < len {
+                    bail!(child.span(), "table footer must come last");
+                }
+                footer = Some(elem.clone());
+            } else {
+                body.push(child);
+            }
+        }
+
+        Ok((header, body, footer))
+    }
+
+    /// Whether a child of the table is a rule rather than a cell.
+    pub fn is_rule(child: &Content) -> bool {
+        child.is::<TableHLineElem>() || child.is::<TableVLineElem>()
+    }
+}
+
 impl Layout for TableElem {
     fn layout(
         &self,
@@ -131,88 +212,575 @@ impl Layout for TableElem {
         let tracks = Axes::new(self.columns(styles).0, self.rows(styles).0);
         let gutter = Axes::new(self.column_gutter(styles).0, self.row_gutter(styles).0);
         let cols = tracks.x.len().max(1);
-        let cells: Vec<_> = self
-            .children()
+        let (header, body, footer) = self.sections()?;
+        let head = header.as_ref().map_or(vec![], TableHeaderElem::children);
+        let foot = footer.as_ref().map_or(vec![], TableFooterElem::children);
+        let count = |list: &[Content]| list.iter().filter(|c| !Self::is_rule(c)).count();
+        let (h, f) = (count(&head), count(&foot));
+
+        // Separate the rules from the cells, remembering how many cells precede
+        // each rule.
+        let mut children = vec![];
+        let mut rules = vec![];
+        for child in head.into_iter().chain(body).chain(foot) {
+            if Self::is_rule(&child) {
+                rules.push((children.len(), child));
+            } else {
+                children.push(child);
+            }
+        }
+
+        let spans: Vec<_> = children
+            .iter()
+            .map(|child| TableCellElem::span_of(child, styles))
+            .collect();
+        let (positions, needed) = place_cells(&spans, cols, &[h, children.len() - f]);
+
+        // Convert the rules into track coordinates.
+        let rows = tracks.y.len().max(needed);
+        let has_gutter = gutter.any(|tracks| !tracks.is_empty());
+        let track = |i: usize| if has_gutter { 2 * i } else { i };
+        let extent = |n: usize| track(n).saturating_sub(has_gutter as usize);
+        let boundary = |i: usize, n: usize| if i >= n { extent(n) } else { track(i) };
+        let range = |start: usize, end: Option<usize>, n: usize| {
+            let end = end.map_or(extent(n), |end| extent(end.min(n)));
+            boundary(start, n)..end
+        };
+        let is_rtl = TextElem::dir_in(styles) == Dir::RTL;
+        let mirror = |range: Range<usize>| {
+            if is_rtl {
+                extent(cols) - range.end..extent(cols) - range.start
+            } else {
+                range
+            }
+        };
+        let rules: Vec<_> = rules
             .into_iter()
+            .map(|(n, child)| {
+                let before = n.checked_sub(1).map(|i| (positions[i], spans[i]));
+                if let Some(hline) = child.to::<TableHLineElem>() {
+                    let y = hline
+                        .y(styles)
+                        .unwrap_or_else(|| before.map_or(0, |(pos, _)| pos.y + 1));
+                    Rule {
+                        axis: Axis::X,
+                        at: boundary(y, rows),
+                        span: mirror(range(hline.start(styles), hline.end(styles), cols)),
+                        stroke: hline.stroke(styles).unwrap_or_default(),
+                    }
+                } else {
+                    let vline = child.to::<TableVLineElem>().unwrap();
+                    let x = vline.x(styles).unwrap_or_else(|| {
+                        before.map_or(0, |(pos, span)| pos.x + span.x.get())
+                    });
+                    let mut at = boundary(x, cols);
+                    if is_rtl {
+                        at = extent(cols) - at;
+                    }
+                    Rule {
+                        axis: Axis::Y,
+                        at,
+                        span: range(vline.start(styles), vline.end(styles), rows),
+                        stroke: vline.stroke(styles).unwrap_or_default(),
+                    }
+                }
+            })
+            .collect();
+
+        // Attribute the cells to the rows of their sections in the document's
+        // structure.
+        let body_start = positions[..h]
+            .iter()
+            .zip(&spans)
+            .map(|(pos, span)| pos.y + span.y.get())
+            .max()
+            .unwrap_or(0);
+        let footer_start = positions.get(children.len() - f).map_or(rows, |pos| pos.y);
+        let tags = positions
+            .iter()
             .enumerate()
-            .map(|(i, child)| {
-                let mut child = child.padded(Sides::splat(inset));
+            .map(|(i, pos)| {
+                let (section, start) = if i < h {
+                    (TableSection::Header, 0)
+                } else if i >= children.len() - f {
+                    (TableSection::Footer, footer_start)
+                } else {
+                    (TableSection::Body, body_start)
+                };
+                Some(Tag::TableCell(section, pos.x, pos.y - start))
+            })
+            .collect();
 
-                let x = i % cols;
-                let y = i / cols;
-                if let Smart::Custom(alignment) = align.resolve(vt, x, y)? {
-                    child = child.styled(AlignElem::set_alignment(alignment));
+        // Properties of explicit table cells override those of the table.
+        let mut fills = vec![];
+        let mut strokes = vec![];
+        let cells: Vec<_> = children
+            .iter()
+            .zip(&positions)
+            .map(|(child, pos)| {
+                let cell = child.to::<TableCellElem>();
+                let padding = cell.map_or(Smart::Auto, |cell| cell.inset(styles));
+                let mut body = cell
+                    .map_or_else(|| child.clone(), TableCellElem::body)
+                    .padded(Sides::splat(padding.unwrap_or(inset)));
+
+                let mut alignment = cell.map_or(Smart::Auto, |cell| cell.align(styles));
+                if alignment.is_auto() {
+                    alignment = align.resolve(vt, pos.x, pos.y)?;
+                }
+                if let Smart::Custom(alignment) = alignment {
+                    body = body.styled(AlignElem::set_alignment(alignment));
                 }
 
-                Ok(child)
+                fills.push(cell.map_or(Smart::Auto, |cell| cell.fill(styles)));
+                strokes.push(cell.map_or(Smart::Auto, |cell| cell.stroke(styles)));
+                Ok(body)
             })
             .collect::<SourceResult<_>>()?;
 
         let fill = self.fill(styles);
-        let stroke = self.stroke(styles).map(PartialStroke::unwrap_or_default);
+        let stroke = self.stroke(styles);
 
         // Prepare grid layout by unifying content and gutter tracks.
-        let layouter = GridLayouter::new(
+        let mut layouter = GridLayouter::new(
             vt,
             tracks.as_deref(),
             gutter.as_deref(),
             &cells,
+            &spans,
+            h,
+            f,
             regions,
             styles,
         );
+        layouter.tag_cells(tags);
 
         // Measure the columns and layout the grid row-by-row.
         let mut layout = layouter.layout()?;
+        let placements = &layout.placements;
+
+        // Resolve the strokes of all cells in content coordinates. A cell
+        // spanning multiple tracks is only resolved once, at its top-left track.
+        let origin = |x, y| placements.at(x, y).map_or((x, y), |p| (p.x, p.y));
+        let mut sides = HashMap::new();
+        for row in layout.rows.iter().flatten() {
+            for x in 0..layout.cols.len() {
+                let (px, py) = origin(x, row.y);
+                if sides.contains_key(&(px, py)) {
+                    continue;
+                }
+
+                let placement = placements.at(x, row.y);
+                let custom = placement.map_or(Smart::Auto, |p| strokes[p.index].clone());
+                let resolved = match custom {
+                    Smart::Custom(custom) => custom,
+                    Smart::Auto => {
+                        let pos = placement.map_or_else(
+                            || placements.content(px, py),
+                            |p| positions[p.index],
+                        );
+                        type Unresolved = Sides<Option<Option<PartialStroke>>>;
+                        stroke.resolve_fold::<Unresolved>(vt, styles, pos.x, pos.y)?
+                    }
+                };
+
+                let resolved =
+                    resolved.map(|side| side.map(PartialStroke::unwrap_or_default));
+                sides.insert((px, py), resolved);
+            }
+        }
+        let sides = |x, y| &sides[&origin(x, y)];
 
         // Add lines and backgrounds.
-        for (frame, rows) in layout.fragment.iter_mut().zip(&layout.rows) {
-            // Render table lines.
-            if let Some(stroke) = &stroke {
-                let thickness = stroke.thickness;
-                let half = thickness / 2.0;
-
-                // Render horizontal lines.
-                for offset in points(rows.iter().map(|piece| piece.height)) {
-                    let target = Point::with_x(frame.width() + thickness);
-                    let hline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(-half, offset),
-                        FrameItem::Shape(hline, self.span()),
-                    );
+        let mut prev: Option<usize> = None;
+        let count = layout.rows.len();
+        let frames = layout.fragment.iter_mut().zip(&layout.rows);
+        for (r, (frame, rows)) in frames.enumerate() {
+            let xs: Vec<_> = points(layout.cols.iter().copied()).collect();
+            let ys: Vec<_> = points(rows.iter().map(|piece| piece.height)).collect();
+            let spanned = |a: Option<&Placement>, b: Option<&Placement>| {
+                a.is_some() && a == b
+            };
+
+            // Render the rules on top of the cell lines.
+            for rule in &rules {
+                let Rule { axis, at, ref span, ref stroke } = *rule;
+                match axis {
+                    Axis::X => {
+                        // A horizontal rule sits at the top of the first row
+                        // after its boundary, unless that row continues from
+                        // the previous region.
+                        let i = rows.iter().position(|row| row.y >= at);
+                        let visible = match i {
+                            Some(0) => {
+                                prev.map_or(true, |y| y < at)
+                                    || (rows[0].y == at && prev != Some(at))
+                            }
+                            Some(_) => true,
+                            None => r + 1 == count,
+                        };
+
+                        if visible && !span.is_empty() {
+                            let offset = ys[i.unwrap_or(rows.len())];
+                            let start = Point::new(xs[span.start], offset);
+                            let target = Point::with_x(xs[span.end] - xs[span.start]);
+                            line(frame, start, target, stroke.clone(), self.span());
+                        }
+                    }
+                    Axis::Y => {
+                        let segments: Vec<_> = rows
+                            .iter()
+                            .map(|row| span.contains(&row.y).then_some(stroke))
+                            .collect();
+                        for (start, end, stroke) in runs(&segments) {
+                            let start_pos = Point::new(xs[at], ys[start]);
+                            let target = Point::with_y(ys[end] - ys[start]);
+                            line(frame, start_pos, target, stroke.clone(), self.span());
+                        }
+                    }
+                }
+            }
+
+            // Render horizontal cell lines, except where a cell spans across
+            // them. Where two cells meet, the lower cell's stroke takes
+            // precedence.
+            for (i, &offset) in ys.iter().enumerate() {
+                let above = i.checked_sub(1).map(|k| rows[k].y);
+                let below = rows.get(i).map(|row| row.y);
+                let segments: Vec<_> = (0..layout.cols.len())
+                    .map(|x| {
+                        if let (Some(a), Some(b)) = (above, below) {
+                            if spanned(placements.at(x, a), placements.at(x, b)) {
+                                return None;
+                            }
+                        }
+                        below
+                            .and_then(|y| sides(x, y).top.as_ref())
+                            .or_else(|| above.and_then(|y| sides(x, y).bottom.as_ref()))
+                    })
+                    .collect();
+
+                for (start, end, stroke) in runs(&segments) {
+                    let start = Point::new(xs[start], offset);
+                    let target = Point::with_x(xs[end] - start.x);
+                    line(frame, start, target, stroke.clone(), self.span());
                 }
+            }
+
+            // Render vertical cell lines in the same way. Where two cells
+            // meet, the right cell's stroke takes precedence.
+            for (x, &offset) in xs.iter().enumerate() {
+                let left = x.checked_sub(1);
+                let right = (x < layout.cols.len()).then_some(x);
+                let segments: Vec<_> = rows
+                    .iter()
+                    .map(|row| {
+                        if let (Some(a), Some(b)) = (left, right) {
+                            if spanned(placements.at(a, row.y), placements.at(b, row.y)) {
+                                return None;
+                            }
+                        }
+                        right
+                            .and_then(|x| sides(x, row.y).left.as_ref())
+                            .or_else(|| left.and_then(|x| sides(x, row.y).right.as_ref()))
+                    })
+                    .collect();
 
-                // Render vertical lines.
-                for offset in points(layout.cols.iter().copied()) {
-                    let target = Point::with_y(frame.height() + thickness);
-                    let vline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(offset, -half),
-                        FrameItem::Shape(vline, self.span()),
-                    );
+                for (start, end, stroke) in runs(&segments) {
+                    let start = Point::new(offset, ys[start]);
+                    let target = Point::with_y(ys[end] - start.y);
+                    line(frame, start, target, stroke.clone(), self.span());
                 }
             }
 
-            // Render cell backgrounds.
+            // Render cell backgrounds. A cell spanning multiple tracks gets a
+            // single background in each region.
             let mut dx = Abs::zero();
             for (x, &col) in layout.cols.iter().enumerate() {
                 let mut dy = Abs::zero();
-                for row in rows {
-                    if let Some(fill) = fill.resolve(vt, x, row.y)? {
+                for (i, row) in rows.iter().enumerate() {
+                    let placement = placements.at(x, row.y);
+                    let mut size = Size::new(col, row.height);
+                    if let Some(placement) = placement {
+                        let range = placement.y..placement.y + placement.span.y;
+                        let within = |y| range.contains(&y);
+                        if x == placement.x && (i == 0 || !within(rows[i - 1].y)) {
+                            let end = x + placement.span.x;
+                            size.x = layout.cols[x..end].iter().sum();
+                            size.y = rows[i..]
+                                .iter()
+                                .take_while(|row| within(row.y))
+                                .map(|row| row.height)
+                                .sum();
+                        } else {
+                            dy += row.height;
+                            continue;
+                        }
+                    }
+
+                    let paint = match placement.map(|p| fills[p.index].clone()) {
+                        Some(Smart::Custom(paint)) => paint,
+                        _ => {
+                            let pos = placement.map_or_else(
+                                || placements.content(x, row.y),
+                                |p| positions[p.index],
+                            );
+                            fill.resolve(vt, pos.x, pos.y)?
+                        }
+                    };
+
+                    if let Some(paint) = paint {
                         let pos = Point::new(dx, dy);
-                        let size = Size::new(col, row.height);
-                        let rect = Geometry::Rect(size).filled(fill);
+                        let rect = Geometry::Rect(size).filled(paint);
                         frame.prepend(pos, FrameItem::Shape(rect, self.span()));
                     }
                     dy += row.height;
                 }
                 dx += col;
             }
+
+            prev = rows.last().map(|row| row.y);
+        }
+
+        for (i, frame) in layout.fragment.iter_mut().enumerate() {
+            frame.tag(Tag::Table(i > 0));
         }
 
         Ok(layout.fragment)
     }
 }
 
+/// A cell in a table.
+///
+/// Cells can span multiple columns and rows and override the table's fill,
+/// alignment and inset. Cells are placed into the next free position in
+/// row-major order, skipping positions covered by cells from earlier rows.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   align: center,
+///   table.cell(colspan: 2, fill: aqua)[*Merged*], [C],
+///   table.cell(rowspan: 2, align: horizon)[Tall], [B], [C],
+///   [B], [C],
+/// )
+/// ```
+///
+/// Display: Table Cell
+/// Category: layout
+#[element(Show)]
+pub struct TableCellElem {
+    /// The cell's body.
+    #[required]
+    pub body: Content,
+
+    /// The number of columns the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub colspan: NonZeroUsize,
+
+    /// The number of rows the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub rowspan: NonZeroUsize,
+
+    /// The cell's fill. If set to `{auto}`, the table's fill is used.
+    pub fill: Smart<Option<Paint>>,
+
+    /// The cell's alignment. If set to `{auto}`, the table's alignment is used.
+    pub align: Smart<Axes<Option<GenAlign>>>,
+
+    /// How much to pad the cell's content. If set to `{auto}`, the table's
+    /// inset is used.
+    pub inset: Smart<Rel<Length>>,
+
+    /// How to stroke the cell's sides. If set to `{auto}`, the table's stroke
+    /// is used.
+    #[resolve]
+    #[fold]
+    pub stroke: Smart<Sides<Option<Option<PartialStroke>>>>,
+}
+
+impl TableCellElem {
+    /// The number of columns and rows a table child spans.
+    pub fn span_of(child: &Content, styles: StyleChain) -> Axes<NonZeroUsize> {
+        match child.to::<Self>() {
+            Some(cell) => Axes::new(cell.colspan(styles), cell.rowspan(styles)),
+            None => Axes::splat(NonZeroUsize::ONE),
+        }
+    }
+}
+
+impl Show for TableCellElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body())
+    }
+}
+
+/// The header of a table.
+///
+/// The header's cells form the first rows of the table. When the table breaks
+/// across pages or columns, the header is repeated at the top of each of them.
+/// The header must be the first argument of the table.
+///
+/// ## Example
+/// ```example
+/// #set page(height: 100pt)
+/// #table(
+///   columns: 2,
+///   table.header[*Number*][*Square*],
+///   ..range(1, 7).map(n => ([#n], [#(n * n)])).flatten(),
+/// )
+/// ```
+///
+/// Display: Table Header
+/// Category: layout
+#[element(Show)]
+pub struct TableHeaderElem {
+    /// The cells of the header.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableHeaderElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// The footer of a table.
+///
+/// The footer's cells form the last rows of the table. When the table breaks
+/// across pages or columns, the footer is repeated at the bottom of each of
+/// them. The footer must be the last argument of the table.
+///
+/// ## Example
+/// ```example
+/// #set page(height: 100pt)
+/// #table(
+///   columns: 2,
+///   ..range(1, 7).map(n => ([#n], [#(n * n)])).flatten(),
+///   table.footer[*Number*][*Square*],
+/// )
+/// ```
+///
+/// Display: Table Footer
+/// Category: layout
+#[element(Show)]
+pub struct TableFooterElem {
+    /// The cells of the footer.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableFooterElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// A horizontal rule in a table.
+///
+/// By default, the rule is placed below the row of the cell that precedes it
+/// and spans the whole table.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   stroke: none,
+///   table.hline(stroke: 1pt),
+///   [*Name*], [*Age*], [*City*],
+///   table.hline(stroke: 0.5pt),
+///   [Ada], [36], [London],
+///   [Grace], [85], [New York],
+///   table.hline(stroke: 1pt),
+/// )
+/// ```
+///
+/// Display: Table Horizontal Rule
+/// Category: layout
+#[element]
+pub struct TableHLineElem {
+    /// The row above which the rule is placed, starting at zero. If set to
+    /// `{auto}`, the rule is placed below the row of the preceding cell.
+    pub y: Smart<usize>,
+
+    /// The column at which the rule starts.
+    pub start: usize,
+
+    /// The column before which the rule ends. If set to `{none}`, the rule
+    /// extends to the end of the table.
+    pub end: Option<usize>,
+
+    /// How to stroke the rule.
+    #[resolve]
+    #[fold]
+    pub stroke: PartialStroke,
+}
+
+/// A vertical rule in a table.
+///
+/// By default, the rule is placed to the right of the cell that precedes it and
+/// spans the whole table.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   stroke: none,
+///   [*Name*], table.vline(), [*Age*], [*City*],
+///   [Ada], [36], [London],
+/// )
+/// ```
+///
+/// Display: Table Vertical Rule
+/// Category: layout
+#[element]
+pub struct TableVLineElem {
+    /// The column left of which the rule is placed, starting at zero. If set
+    /// to `{auto}`, the rule is placed right of the preceding cell.
+    pub x: Smart<usize>,
+
+    /// The row at which the rule starts.
+    pub start: usize,
+
+    /// The row before which the rule ends. If set to `{none}`, the rule
+    /// extends to the bottom of the table.
+    pub end: Option<usize>,
+
+    /// How to stroke the rule.
+    #[resolve]
+    #[fold]
+    pub stroke: PartialStroke,
+}
+
+/// A rule in a table, in track coordinates.
+struct Rule {
+    /// The axis along which the rule runs.
+    axis: Axis,
+    /// The track boundary at which the rule is placed.
+    at: usize,
+    /// The tracks along which the rule extends.
+    span: Range<usize>,
+    /// How to stroke the rule.
+    stroke: Stroke,
+}
+
+/// Add a line from `start` to `start + target` to the background of a frame.
+///
+/// The line is extended by half its thickness at both ends so that lines meet
+/// cleanly at the corners.
+fn line(frame: &mut Frame, start: Point, target: Point, stroke: Stroke, span: Span) {
+    let (shift, extend) = if target.y.is_zero() {
+        (Point::with_x(stroke.thickness / 2.0), Point::with_x(stroke.thickness))
+    } else {
+        (Point::with_y(stroke.thickness / 2.0), Point::with_y(stroke.thickness))
+    };
+
+    let shape = Geometry::Line(target + extend).stroked(stroke);
+    frame.prepend(start - shift, FrameItem::Shape(shape, span));
+}
+
 /// Turn an iterator extents into an iterator of offsets before, in between, and
 /// after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
 fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
@@ -225,6 +793,21 @@ fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
         })
 }
 
+/// Find the ranges of consecutive equal values, skipping `None`s, e.g.
+/// [a, a, None, b] -> [(0, 2, a), (3, 4, b)].
+fn runs<T: PartialEq + Clone>(items: &[Option<T>]) -> Vec<(usize, usize, T)> {
+    let mut runs: Vec<(usize, usize, T)> = vec![];
+    for (i, item) in items.iter().enumerate() {
+        let Some(item) = item else { continue };
+        match runs.last_mut() {
+            Some((_, end, prev)) if *end == i && prev == item => *end += 1,
+            _ => runs.push((i, i + 1, item.clone())),
+        }
+    }
+
+    runs
+}
+
 /// A value that can be configured per cell.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Celled<T> {
@@ -247,6 +830,65 @@ impl<T: Cast + Clone> Celled<T> {
     }
 }
 
+impl<T: Clone + Default> Celled<T> {
+    /// Resolve a resolved and folded value based on the cell position.
+    ///
+    /// A function's result is cast to `U`, resolved and then folded, just
+    /// like a bare value is when accessed through the style chain.
+    pub fn resolve_fold<U>(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        x: usize,
+        y: usize,
+    ) -> SourceResult<T>
+    where
+        U: Cast + Clone + Resolve,
+        U::Output: Fold<Output = T>,
+    {
+        Ok(match self {
+            Self::Value(value) => value.clone(),
+            Self::Func(func) => Celled::<U>::Func(func.clone())
+                .resolve(vt, x, y)?
+                .resolve(styles)
+                .fold(T::default()),
+        })
+    }
+}
+
+impl<T: Resolve> Resolve for Celled<T> {
+    type Output = Celled<T::Output>;
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        match self {
+            Self::Value(value) => Celled::Value(value.resolve(styles)),
+            Self::Func(func) => Celled::Func(func),
+        }
+    }
+}
+
+/// A value folds with an outer value. A function can't be folded and replaces
+/// whatever is set further out.
+impl<T> Fold for Celled<T>
+where
+    T: Fold,
+    T::Output: Default,
+{
+    type Output = Celled<T::Output>;
+
+    fn fold(self, outer: Self::Output) -> Self::Output {
+        match (self, outer) {
+            (Self::Value(inner), Celled::Value(outer)) => {
+                Celled::Value(inner.fold(outer))
+            }
+            (Self::Value(inner), Celled::Func(_)) => {
+                Celled::Value(inner.fold(T::Output::default()))
+            }
+            (Self::Func(func), _) => Celled::Func(func),
+        }
+    }
+}
+
 impl<T: Default> Default for Celled<T> {
     fn default() -> Self {
         Self::Value(T::default())
@@ -121,9 +121,16 @@ impl Layout for TermsElem {
             seq.push(child.description());
         }
 
-        Content::sequence(seq)
+        // Each item is a paragraph, which makes up the body of a list item in
+        // the document's structure.
+        let mut fragment = Content::sequence(seq)
             .styled(ParElem::set_hanging_indent(hanging_indent + indent))
-            .layout(vt, styles, regions)
+            .layout(vt, styles, regions)?;
+        for (i, frame) in fragment.iter_mut().enumerate() {
+            frame.tag(Tag::List(i > 0));
+        }
+
+        Ok(fragment)
     }
 }
 
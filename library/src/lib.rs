@@ -1,6 +1,7 @@
 //! Typst's standard library.
 
 pub mod compute;
+pub mod html;
 pub mod layout;
 pub mod math;
 pub mod meta;
@@ -11,7 +12,7 @@ pub mod text;
 pub mod visualize;
 
 use typst::diag::At;
-use typst::eval::{LangItems, Library, Module, Scope};
+use typst::eval::{Dict, LangItems, Library, Module, Scope};
 use typst::geom::{Align, Color, Dir, GenAlign, Smart};
 use typst::model::{Element, Styles};
 
@@ -19,14 +20,21 @@ use self::layout::LayoutRoot;
 
 /// Construct the standard library.
 pub fn build() -> Library {
+    build_with_inputs(Dict::new())
+}
+
+/// Construct the standard library with inputs that documents can read from
+/// the `sys.inputs` dictionary.
+pub fn build_with_inputs(inputs: Dict) -> Library {
     let math = math::module();
     let calc = compute::calc::module();
-    let global = global(math.clone(), calc);
+    let sys = compute::sys::module(inputs);
+    let global = global(math.clone(), calc, sys);
     Library { global, math, styles: styles(), items: items() }
 }
 
 /// Construct the module with global definitions.
-fn global(math: Module, calc: Module) -> Module {
+fn global(math: Module, calc: Module, sys: Module) -> Module {
     let mut global = Scope::deduplicating();
 
     // Text.
@@ -92,6 +100,7 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("outline", meta::OutlineElem::func());
     global.define("heading", meta::HeadingElem::func());
     global.define("figure", meta::FigureElem::func());
+    global.define("footnote", meta::FootnoteElem::func());
     global.define("cite", meta::CiteElem::func());
     global.define("bibliography", meta::BibliographyElem::func());
     global.define("locate", meta::locate);
@@ -116,6 +125,9 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("luma", compute::luma);
     global.define("rgb", compute::rgb);
     global.define("cmyk", compute::cmyk);
+    global.define("datetime", compute::datetime);
+    global.define("duration", compute::duration);
+    global.define("today", compute::today);
     global.define("symbol", compute::symbol);
     global.define("str", compute::str);
     global.define("label", compute::label);
@@ -130,6 +142,9 @@ fn global(math: Module, calc: Module) -> Module {
     // Calc.
     global.define("calc", calc);
 
+    // System.
+    global.define("sys", sys);
+
     // Colors.
     global.define("black", Color::BLACK);
     global.define("gray", Color::GRAY);
@@ -150,6 +165,9 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("green", Color::GREEN);
     global.define("lime", Color::LIME);
 
+    // Gradients.
+    global.define("gradient", visualize::gradient::module());
+
     // Other constants.
     global.define("ltr", Dir::LTR);
     global.define("rtl", Dir::RTL);
@@ -176,6 +194,7 @@ fn styles() -> Styles {
 fn items() -> LangItems {
     LangItems {
         layout: |world, content, styles| content.layout_root(world, styles),
+        html: html::html,
         em: text::TextElem::size_in,
         dir: text::TextElem::dir_in,
         space: || text::SpaceElem::new().pack(),
@@ -1,5 +1,6 @@
-use crate::layout::{LayoutRoot, PageElem};
+use crate::layout::{LayoutRoot, PageElem, PagebreakElem};
 use crate::prelude::*;
+use crate::text::TextDir;
 
 /// The root element of a document and its metadata.
 ///
@@ -21,7 +22,8 @@ pub struct DocumentElem {
     /// The document's authors.
     pub author: Author,
 
-    /// The page runs.
+    /// The page runs and the page breaks between them that require a page of
+    /// a certain parity.
     #[internal]
     #[variadic]
     pub children: Vec<Content>,
@@ -40,9 +42,22 @@ impl LayoutRoot for DocumentElem {
                 child = elem;
             }
 
+            let number = NonZeroUsize::ONE.saturating_add(pages.len());
             if let Some(page) = child.to::<PageElem>() {
-                let fragment = page.layout(vt, styles)?;
+                let fragment = page.layout(vt, styles, number)?;
                 pages.extend(fragment);
+            } else if let Some(pagebreak) = child.to::<PagebreakElem>() {
+                // Insert an empty page if the next page would have the wrong
+                // parity. It's blank apart from the background, so it has no
+                // header, footer or page number.
+                if pagebreak.to(styles).map_or(false, |to| !to.matches(number)) {
+                    let blank = PageElem::new(Content::empty())
+                        .with_header(None)
+                        .with_footer(None)
+                        .with_numbering(None)
+                        .with_foreground(None);
+                    pages.extend(blank.layout(vt, styles, number)?);
+                }
             } else {
                 bail!(child.span(), "unexpected document child");
             }
@@ -52,6 +67,7 @@ impl LayoutRoot for DocumentElem {
             pages,
             title: self.title(styles),
             author: self.author(styles).0,
+            vertical: TextDir::is_vertical(styles),
         })
     }
 }
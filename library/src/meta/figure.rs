@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use super::{Count, Counter, CounterUpdate, LocalName, Numbering, NumberingPattern};
-use crate::layout::{BlockElem, VElem};
+use crate::layout::{BlockElem, PlaceElem, VElem};
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -21,6 +21,9 @@ use crate::text::TextElem;
 /// ) <lab>
 /// ```
 ///
+/// Figures can also float to the top or bottom of the page, so that they
+/// don't leave a gap in the text when there isn't enough space left for them.
+///
 /// Display: Figure
 /// Category: meta
 #[element(Locatable, Synthesize, Count, Show, LocalName)]
@@ -32,6 +35,32 @@ pub struct FigureElem {
     /// The figure's caption.
     pub caption: Option<Content>,
 
+    /// The figure's placement on the page.
+    ///
+    /// - `{none}`: The figure stays in-flow exactly where it was specified
+    ///   like other content.
+    /// - `{auto}`: The figure picks `{top}` or `{bottom}` depending on which
+    ///   is closer.
+    /// - `{top}`: The figure floats to the top of the page.
+    /// - `{bottom}`: The figure floats to the bottom of the page.
+    ///
+    /// Floating figures move to the next page if they don't fit onto the
+    /// current one anymore. The text continues to fill the current page in
+    /// the meantime.
+    ///
+    /// ```example
+    /// #set page(height: 200pt)
+    ///
+    /// = Introduction
+    /// #figure(
+    ///   placement: bottom,
+    ///   caption: [A glacier],
+    ///   image("glacier.jpg", width: 60%),
+    /// )
+    /// #lorem(60)
+    /// ```
+    pub placement: Option<Smart<VerticalAlign>>,
+
     /// How to number the figure. Accepts a
     /// [numbering pattern or function]($func/numbering).
     #[default(Some(NumberingPattern::from_str("1").unwrap().into()))]
@@ -67,11 +96,23 @@ impl Show for FigureElem {
             realized += caption;
         }
 
-        Ok(BlockElem::new()
+        realized = BlockElem::new()
             .with_body(Some(realized))
             .with_breakable(false)
             .pack()
-            .aligned(Axes::with_x(Some(Align::Center.into()))))
+            .aligned(Axes::with_x(Some(Align::Center.into())));
+
+        if let Some(placement) = self.placement(styles) {
+            let alignment = placement.map(|VerticalAlign(y)| {
+                Axes::new(Some(Align::Center.into()), Some(y))
+            });
+            realized = PlaceElem::new(realized)
+                .with_alignment(alignment)
+                .with_float(true)
+                .pack();
+        }
+
+        Ok(realized)
     }
 }
 
@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use super::{Count, Counter, CounterUpdate, Numbering, NumberingPattern};
+use crate::layout::{HElem, ParElem};
+use crate::prelude::*;
+use crate::text::{SuperElem, TextElem, TextSize};
+use crate::visualize::LineElem;
+
+/// A footnote.
+///
+/// Includes additional remarks and references on the same page with footnotes.
+/// A footnote will insert a superscript number that links to the note at the
+/// bottom of the page. Notes are numbered sequentially throughout your
+/// document and can break across multiple pages.
+///
+/// To customize the appearance of the entry in the footnote listing, you can
+/// use the `separator`, `clearance`, `gap` and `indent` properties. They are
+/// taken from the styles in effect where a footnote appears. The listing on a
+/// page uses the `separator` and `clearance` of its first footnote.
+///
+/// ## Example
+/// ```example
+/// Check the docs for more details.
+/// #footnote[https://typst.app/docs]
+/// ```
+///
+/// The footnote automatically attaches itself to the preceding word, even if
+/// there is a space before it in the markup. To force a space, you can use the
+/// string `[#" "]` or explicit [horizontal spacing]($func/h).
+///
+/// Display: Footnote
+/// Category: meta
+#[element(Locatable, Synthesize, Count, Show)]
+pub struct FootnoteElem {
+    /// How to number footnotes.
+    ///
+    /// By default, the footnote numbering continues throughout your document.
+    /// If you prefer per-page footnote numbering, you can reset the footnote
+    /// [counter]($func/counter) in the page [header]($func/page.header).
+    ///
+    /// ```example
+    /// #set footnote(numbering: "*")
+    ///
+    /// Footnotes:
+    /// #footnote[Star],
+    /// #footnote[Dagger]
+    /// ```
+    #[default(NumberingPattern::from_str("1").unwrap().into())]
+    pub numbering: Numbering,
+
+    /// The content to put into the footnote.
+    #[required]
+    pub body: Content,
+
+    /// The separator between the document body and the footnote listing.
+    ///
+    /// ```example
+    /// #set footnote(separator: repeat[.])
+    ///
+    /// Testing a different separator.
+    /// #footnote[
+    ///   Unconventional, but maybe
+    ///   not that bad?
+    /// ]
+    /// ```
+    #[default(
+        LineElem::new()
+            .with_length(Ratio::new(0.3).into())
+            .with_stroke(PartialStroke {
+                thickness: Smart::Custom(Abs::pt(0.5).into()),
+                ..Default::default()
+            })
+            .pack()
+    )]
+    pub separator: Content,
+
+    /// The amount of clearance between the document body and the separator.
+    ///
+    /// ```example
+    /// #set footnote(clearance: 3em)
+    ///
+    /// Footnotes also need ...
+    /// #footnote[
+    ///   ... some space to breathe.
+    /// ]
+    /// ```
+    #[default(Em::new(1.0).into())]
+    #[resolve]
+    pub clearance: Length,
+
+    /// The gap between footnote entries.
+    ///
+    /// ```example
+    /// #set footnote(gap: 0.8em)
+    ///
+    /// Footnotes:
+    /// #footnote[Spaced],
+    /// #footnote[Apart]
+    /// ```
+    #[default(Em::new(0.5).into())]
+    #[resolve]
+    pub gap: Length,
+
+    /// The indent of each footnote entry.
+    ///
+    /// ```example
+    /// #set footnote(indent: 0em)
+    ///
+    /// Footnotes:
+    /// #footnote[No],
+    /// #footnote[Indent]
+    /// ```
+    #[default(Em::new(1.0).into())]
+    pub indent: Length,
+}
+
+impl FootnoteElem {
+    /// Create the entry for this footnote in the listing at the bottom of the
+    /// page.
+    ///
+    /// The entry's number links back to the footnote in the text.
+    pub fn entry(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let loc = self.0.location().unwrap();
+        let num = self.number(vt)?;
+
+        // Make the link from the footnote to its entry work.
+        let backlink = {
+            let mut content = Content::empty();
+            content.set_location(loc.variant(1));
+            MetaElem::set_data(vec![Meta::Elem(content)])
+        };
+
+        let sup = SuperElem::new(num)
+            .pack()
+            .linked(Destination::Location(loc))
+            .styled(backlink);
+
+        Ok(Content::sequence(vec![
+            HElem::new(self.indent(styles).into()).pack(),
+            sup,
+            HElem::new(Em::new(0.05).into()).with_weak(true).pack(),
+            self.body(),
+        ])
+        .styled(TextElem::set_size(TextSize(Em::new(0.85).into())))
+        .styled(ParElem::set_leading(Em::new(0.5).into())))
+    }
+
+    /// The formatted number of this footnote.
+    fn number(&self, vt: &mut Vt) -> SourceResult<Content> {
+        let loc = self.0.location().unwrap();
+        let numbering = self.numbering(StyleChain::default());
+        Counter::of(Self::func()).at(vt, loc)?.display(vt, &numbering)
+    }
+}
+
+impl Synthesize for FootnoteElem {
+    fn synthesize(&mut self, styles: StyleChain) {
+        self.push_numbering(self.numbering(styles));
+        self.push_separator(self.separator(styles));
+        self.push_clearance(self.clearance(styles).into());
+        self.push_gap(self.gap(styles).into());
+        self.push_indent(self.indent(styles));
+    }
+}
+
+impl Show for FootnoteElem {
+    fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        let loc = self.0.location().unwrap();
+        let num = self.number(vt)?;
+        let sup = SuperElem::new(num).pack();
+        let hole = HElem::new(Abs::zero().into()).with_weak(true).pack();
+        Ok(hole + sup.linked(Destination::Location(loc.variant(1))))
+    }
+}
+
+impl Count for FootnoteElem {
+    fn update(&self) -> Option<CounterUpdate> {
+        Some(CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+}
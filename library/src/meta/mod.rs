@@ -5,6 +5,7 @@ mod context;
 mod counter;
 mod document;
 mod figure;
+mod footnote;
 mod heading;
 mod link;
 mod numbering;
@@ -18,6 +19,7 @@ pub use self::context::*;
 pub use self::counter::*;
 pub use self::document::*;
 pub use self::figure::*;
+pub use self::footnote::*;
 pub use self::heading::*;
 pub use self::link::*;
 pub use self::numbering::*;
@@ -36,13 +36,26 @@ use crate::text::Case;
 pub fn numbering(
     /// Defines how the numbering works.
     ///
-    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I` and `*`. They are
+    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I` and `*` as well as a
+    /// number of symbols for other writing systems (see below). They are
     /// replaced by the number in the sequence, in the given case.
     ///
     /// The `*` character means that symbols should be used to count, in the
     /// order of `*`, `†`, `‡`, `§`, `¶`, and `‖`. If there are more than six
     /// items, the number is represented using multiple symbols.
     ///
+    /// The following counting symbols count in other writing systems:
+    /// - `א`: Hebrew numerals.
+    /// - `一`: Chinese numerals, e.g. `二十三`.
+    /// - `壹`: Formal Chinese numerals, e.g. `贰拾叁`.
+    /// - `١`: Arabic-Indic digits.
+    /// - `१`: Devanagari digits.
+    /// - `α` and `Α`: Lowercase and uppercase Greek letters.
+    /// - `①`: Circled numbers, up to fifty.
+    /// - `가` and `ㄱ`: Korean Hangul syllables and consonants.
+    /// - `イ` and `い`: Japanese Iroha order in katakana and hiragana.
+    /// - `ア` and `あ`: Japanese Aiueo order in katakana and hiragana.
+    ///
     /// **Suffixes** are all characters after the last counting symbol. They are
     /// repeated as-is at the end of any rendered number.
     ///
@@ -129,13 +142,14 @@ cast_to_value! {
 
 /// How to turn a number into text.
 ///
-/// A pattern consists of a prefix, followed by one of `1`, `a`, `A`, `i`, `I`
-/// or `*`, and then a suffix.
+/// A pattern consists of a prefix, followed by one of `1`, `a`, `A`, `i`, `I`,
+/// `*` or another counting symbol, and then a suffix.
 ///
 /// Examples of valid patterns:
 /// - `1)`
 /// - `a.`
 /// - `(I)`
+/// - `第一章`
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct NumberingPattern {
     pieces: EcoVec<(EcoString, NumberingKind, Case)>,
@@ -208,7 +222,8 @@ impl FromStr for NumberingPattern {
         let mut handled = 0;
 
         for (i, c) in pattern.char_indices() {
-            let Some(kind) = NumberingKind::from_char(c.to_ascii_lowercase()) else {
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            let Some(kind) = NumberingKind::from_char(lower) else {
                 continue;
             };
 
@@ -239,7 +254,7 @@ cast_to_value! {
             pat.push_str(prefix);
             let mut c = kind.to_char();
             if *case == Case::Upper {
-                c = c.to_ascii_uppercase();
+                c = c.to_uppercase().next().unwrap_or(c);
             }
             pat.push(c);
         }
@@ -256,6 +271,18 @@ enum NumberingKind {
     Roman,
     Symbol,
     Hebrew,
+    Chinese,
+    ChineseFormal,
+    ArabicIndic,
+    Devanagari,
+    Greek,
+    Circled,
+    Hangul,
+    HangulJamo,
+    KatakanaIroha,
+    HiraganaIroha,
+    KatakanaAiueo,
+    HiraganaAiueo,
 }
 
 impl NumberingKind {
@@ -267,6 +294,18 @@ impl NumberingKind {
             'i' => NumberingKind::Roman,
             '*' => NumberingKind::Symbol,
             'א' => NumberingKind::Hebrew,
+            '一' => NumberingKind::Chinese,
+            '壹' => NumberingKind::ChineseFormal,
+            '١' => NumberingKind::ArabicIndic,
+            '१' => NumberingKind::Devanagari,
+            'α' => NumberingKind::Greek,
+            '①' => NumberingKind::Circled,
+            '가' => NumberingKind::Hangul,
+            'ㄱ' => NumberingKind::HangulJamo,
+            'イ' => NumberingKind::KatakanaIroha,
+            'い' => NumberingKind::HiraganaIroha,
+            'ア' => NumberingKind::KatakanaAiueo,
+            'あ' => NumberingKind::HiraganaAiueo,
             _ => return None,
         })
     }
@@ -279,6 +318,18 @@ impl NumberingKind {
             Self::Roman => 'i',
             Self::Symbol => '*',
             Self::Hebrew => 'א',
+            Self::Chinese => '一',
+            Self::ChineseFormal => '壹',
+            Self::ArabicIndic => '١',
+            Self::Devanagari => '१',
+            Self::Greek => 'α',
+            Self::Circled => '①',
+            Self::Hangul => '가',
+            Self::HangulJamo => 'ㄱ',
+            Self::KatakanaIroha => 'イ',
+            Self::HiraganaIroha => 'い',
+            Self::KatakanaAiueo => 'ア',
+            Self::HiraganaAiueo => 'あ',
         }
     }
 
@@ -417,6 +468,180 @@ impl NumberingKind {
                 }
                 fmt
             }
+            Self::Chinese => chinese(n, CHINESE_DIGITS, ['十', '百', '千'], true),
+            Self::ChineseFormal => {
+                chinese(n, CHINESE_FORMAL_DIGITS, ['拾', '佰', '仟'], false)
+            }
+            Self::ArabicIndic => {
+                digits(n, ['٠', '١', '٢', '٣', '٤', '٥', '٦', '٧', '٨', '٩'])
+            }
+            Self::Devanagari => {
+                digits(n, ['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'])
+            }
+            Self::Greek => {
+                let mut fmt = alphabetic(n, GREEK);
+                if case == Case::Upper {
+                    fmt = fmt.to_uppercase();
+                }
+                fmt
+            }
+            Self::Circled => match n {
+                1..=20 => char::from_u32(0x2460 + n as u32 - 1).unwrap().into(),
+                21..=35 => char::from_u32(0x3251 + n as u32 - 21).unwrap().into(),
+                36..=50 => char::from_u32(0x32B1 + n as u32 - 36).unwrap().into(),
+                _ => eco_format!("{n}"),
+            },
+            Self::Hangul => alphabetic(n, HANGUL),
+            Self::HangulJamo => alphabetic(n, HANGUL_JAMO),
+            Self::KatakanaIroha => alphabetic(n, KATAKANA_IROHA),
+            Self::HiraganaIroha => alphabetic(n, HIRAGANA_IROHA),
+            Self::KatakanaAiueo => alphabetic(n, KATAKANA_AIUEO),
+            Self::HiraganaAiueo => alphabetic(n, HIRAGANA_AIUEO),
+        }
+    }
+}
+
+/// Chinese digits from zero to nine.
+const CHINESE_DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+/// Formal Chinese digits from zero to nine, as used in finance.
+const CHINESE_FORMAL_DIGITS: [char; 10] =
+    ['零', '壹', '贰', '叁', '肆', '伍', '陆', '柒', '捌', '玖'];
+
+/// The lowercase Greek alphabet.
+const GREEK: &[char] = &[
+    'α', 'β', 'γ', 'δ', 'ε', 'ζ', 'η', 'θ', 'ι', 'κ', 'λ', 'μ', 'ν', 'ξ', 'ο', 'π',
+    'ρ', 'σ', 'τ', 'υ', 'φ', 'χ', 'ψ', 'ω',
+];
+
+/// The Korean Hangul syllables used for counting.
+const HANGUL: &[char] = &[
+    '가', '나', '다', '라', '마', '바', '사', '아', '자', '차', '카', '타', '파', '하',
+];
+
+/// The Korean Hangul consonants used for counting.
+const HANGUL_JAMO: &[char] = &[
+    'ㄱ', 'ㄴ', 'ㄷ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅅ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+/// The katakana in Iroha order.
+const KATAKANA_IROHA: &[char] = &[
+    'イ', 'ロ', 'ハ', 'ニ', 'ホ', 'ヘ', 'ト', 'チ', 'リ', 'ヌ', 'ル', 'ヲ', 'ワ', 'カ',
+    'ヨ', 'タ', 'レ', 'ソ', 'ツ', 'ネ', 'ナ', 'ラ', 'ム', 'ウ', 'ヰ', 'ノ', 'オ', 'ク',
+    'ヤ', 'マ', 'ケ', 'フ', 'コ', 'エ', 'テ', 'ア', 'サ', 'キ', 'ユ', 'メ', 'ミ', 'シ',
+    'ヱ', 'ヒ', 'モ', 'セ', 'ス',
+];
+
+/// The hiragana in Iroha order.
+const HIRAGANA_IROHA: &[char] = &[
+    'い', 'ろ', 'は', 'に', 'ほ', 'へ', 'と', 'ち', 'り', 'ぬ', 'る', 'を', 'わ', 'か',
+    'よ', 'た', 'れ', 'そ', 'つ', 'ね', 'な', 'ら', 'む', 'う', 'ゐ', 'の', 'お', 'く',
+    'や', 'ま', 'け', 'ふ', 'こ', 'え', 'て', 'あ', 'さ', 'き', 'ゆ', 'め', 'み', 'し',
+    'ゑ', 'ひ', 'も', 'せ', 'す',
+];
+
+/// The katakana in Aiueo (gojūon) order.
+const KATAKANA_AIUEO: &[char] = &[
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ',
+    'ソ', 'タ', 'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ',
+    'ヘ', 'ホ', 'マ', 'ミ', 'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ',
+    'ロ', 'ワ', 'ヲ', 'ン',
+];
+
+/// The hiragana in Aiueo (gojūon) order.
+const HIRAGANA_AIUEO: &[char] = &[
+    'あ', 'い', 'う', 'え', 'お', 'か', 'き', 'く', 'け', 'こ', 'さ', 'し', 'す', 'せ',
+    'そ', 'た', 'ち', 'つ', 'て', 'と', 'な', 'に', 'ぬ', 'ね', 'の', 'は', 'ひ', 'ふ',
+    'へ', 'ほ', 'ま', 'み', 'む', 'め', 'も', 'や', 'ゆ', 'よ', 'ら', 'り', 'る', 'れ',
+    'ろ', 'わ', 'を', 'ん',
+];
+
+/// Count with the given symbols like with letters: After the last symbol come
+/// two-symbol combinations, starting with the first symbol twice.
+fn alphabetic(mut n: usize, symbols: &[char]) -> EcoString {
+    if n == 0 {
+        return '-'.into();
+    }
+
+    let mut fmt = vec![];
+    while n > 0 {
+        n -= 1;
+        fmt.push(symbols[n % symbols.len()]);
+        n /= symbols.len();
+    }
+
+    fmt.into_iter().rev().collect()
+}
+
+/// Write a number in decimal notation with the given digits.
+fn digits(n: usize, digits: [char; 10]) -> EcoString {
+    eco_format!("{n}")
+        .chars()
+        .map(|c| digits[c.to_digit(10).unwrap() as usize])
+        .collect()
+}
+
+/// Write a number with Chinese numerals.
+///
+/// The digits are grouped into myriads (`万`, `亿`, `兆`), each of which is
+/// written with the units for tens, hundreds and thousands. When `short` is
+/// true, numbers from ten to nineteen leave out the leading one (`十二`
+/// instead of `一十二`).
+fn chinese(n: usize, digits: [char; 10], units: [char; 3], short: bool) -> EcoString {
+    const MYRIADS: [&str; 5] = ["", "万", "亿", "兆", "京"];
+
+    if n == 0 {
+        return digits[0].into();
+    }
+
+    let mut groups = vec![];
+    let mut rest = n;
+    while rest > 0 {
+        groups.push(rest % 10000);
+        rest /= 10000;
+    }
+
+    let mut fmt = EcoString::new();
+    let mut zero = false;
+    for (i, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            zero = true;
+            continue;
+        }
+
+        if !fmt.is_empty() && (zero || group < 1000) {
+            fmt.push(digits[0]);
+        }
+
+        zero = false;
+        let mut started = false;
+        for pos in (0..4).rev() {
+            let digit = group / 10usize.pow(pos) % 10;
+            if digit == 0 {
+                zero |= started;
+                continue;
+            }
+
+            if zero {
+                fmt.push(digits[0]);
+                zero = false;
+            }
+
+            let leading_ten = short && pos == 1 && digit == 1 && n < 100;
+            if !leading_ten {
+                fmt.push(digits[digit]);
+            }
+
+            if pos > 0 {
+                fmt.push(units[pos as usize - 1]);
+            }
+
+            started = true;
         }
+
+        zero = false;
+        fmt.push_str(MYRIADS[i]);
     }
+
+    fmt
 }
@@ -1,4 +1,6 @@
-use super::{BibliographyElem, CiteElem, Counter, LocalName, Numbering};
+use typst::diag::SourceError;
+
+use super::{BibliographyElem, CiteElem, Counter, HeadingElem, LocalName, Numbering};
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -130,7 +132,22 @@ impl Show for RefElem {
         }
 
         let Some(numbering) = elem.cast_field::<Numbering>("numbering") else {
-            bail!(self.span(), "only numbered elements can be referenced");
+            if !elem.is::<HeadingElem>() {
+                bail!(self.span(), "only numbered elements can be referenced");
+            }
+
+            // Fall back to the heading's title for unnumbered headings.
+            vt.tracer.warn(warning!(
+                self.span(),
+                "cannot reference heading without numbering"
+            ));
+            vt.tracer.warn(SourceError::hint(
+                self.span(),
+                "enable numbering with `#set heading(numbering: \"1.\")`",
+            ));
+
+            let body = elem.expect_field::<Content>("body");
+            return Ok(body.linked(Destination::Location(elem.location().unwrap())));
         };
 
         let numbers = Counter::of(elem.func())
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use comemo::Prehashed;
+
+use crate::prelude::*;
+
+/// Words with custom hyphenation opportunities.
+///
+/// Each word is given with hyphens at the points where it may be hyphenated.
+/// A word without any hyphens is never hyphenated.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct HyphenationExceptions(Vec<EcoString>);
+
+impl HyphenationExceptions {
+    /// The hyphenation opportunities for the given word as char indices, if
+    /// it is an exception.
+    pub fn find(&self, word: &str) -> Option<Vec<usize>> {
+        self.0.iter().find_map(|exception| exception_breaks(exception, word))
+    }
+}
+
+cast_from_value! {
+    HyphenationExceptions,
+    values: Array => Self(values
+        .into_iter()
+        .map(|v| -> StrResult<EcoString> {
+            let word: EcoString = v.cast()?;
+            if word.split('-').any(str::is_empty) {
+                Err("hyphenation exception must not have empty parts")?;
+            }
+            Ok(word)
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+cast_to_value! {
+    v: HyphenationExceptions => v.0.into()
+}
+
+/// Hyphenation patterns in the format of TeX's pattern files.
+///
+/// The patterns are applied with Liang's algorithm. Exceptions from a
+/// `\hyphenation` block in the file take precedence over the patterns.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct HyphenationPatterns(Arc<Prehashed<Patterns>>);
+
+/// The parsed contents of a pattern file.
+#[derive(Debug, Hash)]
+struct Patterns {
+    /// The original pattern source.
+    source: EcoString,
+    /// Maps from letter sequences to the values between the letters.
+    patterns: BTreeMap<EcoString, Vec<u8>>,
+    /// The length of the longest pattern in chars.
+    longest: usize,
+    /// Words with explicit hyphenation opportunities.
+    exceptions: Vec<EcoString>,
+}
+
+impl HyphenationPatterns {
+    /// Parse patterns from the contents of a TeX pattern file.
+    ///
+    /// Both full TeX files with `\patterns{...}` and `\hyphenation{...}`
+    /// blocks and plain lists of patterns are supported.
+    pub fn parse(source: &str) -> StrResult<Self> {
+        let text: String = source
+            .lines()
+            .map(|line| line.split('%').next().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let list = block(&text, "\\patterns")?;
+        let exceptions = block(&text, "\\hyphenation")?
+            .map(|block| block.split_whitespace().map(Into::into).collect())
+            .unwrap_or_default();
+
+        let mut patterns = BTreeMap::new();
+        let mut longest = 0;
+        for pattern in list.unwrap_or(&text).split_whitespace() {
+            if pattern.starts_with('\\') {
+                continue;
+            }
+
+            let mut letters = EcoString::new();
+            let mut values = vec![0];
+            for c in pattern.chars() {
+                if let Some(digit) = c.to_digit(10) {
+                    *values.last_mut().unwrap() = digit as u8;
+                } else {
+                    letters.push(c.to_lowercase().next().unwrap_or(c));
+                    values.push(0);
+                }
+            }
+
+            if letters.is_empty() {
+                Err("hyphenation pattern must contain letters")?;
+            }
+
+            longest = longest.max(values.len() - 1);
+            patterns.insert(letters, values);
+        }
+
+        if patterns.is_empty() {
+            Err("hyphenation patterns must not be empty")?;
+        }
+
+        Ok(Self(Arc::new(Prehashed::new(Patterns {
+            source: source.into(),
+            patterns,
+            longest,
+            exceptions,
+        }))))
+    }
+
+    /// The hyphenation opportunities for the given word as char indices.
+    pub fn hyphenate(&self, word: &str) -> Vec<usize> {
+        if let Some(breaks) = self
+            .0
+            .exceptions
+            .iter()
+            .find_map(|exception| exception_breaks(exception, word))
+        {
+            return breaks;
+        }
+
+        let chars: Vec<char> = std::iter::once('.')
+            .chain(word.chars().map(|c| c.to_lowercase().next().unwrap_or(c)))
+            .chain(std::iter::once('.'))
+            .collect();
+
+        // Find the maximum value between each pair of letters.
+        let mut points = vec![0; chars.len() + 1];
+        let mut key = EcoString::new();
+        for i in 0..chars.len() {
+            key.clear();
+            for &c in chars[i..].iter().take(self.0.longest) {
+                key.push(c);
+                if let Some(values) = self.0.patterns.get(&key) {
+                    for (k, &value) in values.iter().enumerate() {
+                        points[i + k] = points[i + k].max(value);
+                    }
+                }
+            }
+        }
+
+        // Odd values mark hyphenation opportunities. The value in front of
+        // a word's char is offset by one due to the leading dot.
+        let len = chars.len() - 2;
+        (1..len).filter(|&c| points[c + 1] % 2 == 1).collect()
+    }
+}
+
+cast_from_value! {
+    HyphenationPatterns,
+    v: EcoString => Self::parse(&v)?,
+}
+
+cast_to_value! {
+    v: HyphenationPatterns => v.0.source.clone().into()
+}
+
+/// Find the contents of a TeX block like `\patterns{...}`.
+fn block<'a>(text: &'a str, name: &str) -> StrResult<Option<&'a str>> {
+    let Some(start) = text.find(name) else { return Ok(None) };
+    let rest = text[start + name.len()..]
+        .trim_start()
+        .strip_prefix('{')
+        .ok_or("expected opening brace in hyphenation patterns")?;
+    let end = rest.find('}').ok_or("unclosed block in hyphenation patterns")?;
+    Ok(Some(&rest[..end]))
+}
+
+/// The hyphenation opportunities of a word as char indices if it matches the
+/// given exception.
+fn exception_breaks(exception: &str, word: &str) -> Option<Vec<usize>> {
+    let mut breaks = vec![];
+    let mut chars = word.chars();
+    let mut i = 0;
+    for c in exception.chars() {
+        if c == '-' {
+            breaks.push(i);
+            continue;
+        }
+
+        let other = chars.next()?;
+        if !c.to_lowercase().eq(other.to_lowercase()) {
+            return None;
+        }
+
+        i += 1;
+    }
+
+    chars.next().is_none().then_some(breaks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exception_breaks() {
+        assert_eq!(exception_breaks("data-base", "database"), Some(vec![4]));
+        assert_eq!(exception_breaks("data-base", "DataBase"), Some(vec![4]));
+        assert_eq!(exception_breaks("ab-ab-ab", "ababab"), Some(vec![2, 4]));
+        assert_eq!(exception_breaks("Typst", "typst"), Some(vec![]));
+        assert_eq!(exception_breaks("data-base", "databases"), None);
+        assert_eq!(exception_breaks("data-base", "data"), None);
+        assert_eq!(exception_breaks("data-base", "datapase"), None);
+    }
+
+    #[test]
+    fn test_hyphenate_with_patterns() {
+        let patterns = HyphenationPatterns::parse("a1b").unwrap();
+        assert_eq!(patterns.hyphenate("aab"), vec![2]);
+        assert_eq!(patterns.hyphenate("AAB"), vec![2]);
+        assert_eq!(patterns.hyphenate("abab"), vec![1, 3]);
+        assert_eq!(patterns.hyphenate("ba"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_hyphenate_with_exceptions() {
+        let source = "% Comment\n\\patterns{\na1b\n}\n\\hyphenation{\nab-ab-ab\n}\n";
+        let patterns = HyphenationPatterns::parse(source).unwrap();
+        assert_eq!(patterns.hyphenate("ababab"), vec![2, 4]);
+        assert_eq!(patterns.hyphenate("abab"), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_parse_patterns_errors() {
+        let error =
+            |source: &str| HyphenationPatterns::parse(source).unwrap_err().to_string();
+        assert_eq!(error(""), "hyphenation patterns must not be empty");
+        assert_eq!(error("\\patterns{"), "unclosed block in hyphenation patterns");
+        assert_eq!(
+            error("\\patterns a1b"),
+            "expected opening brace in hyphenation patterns",
+        );
+        assert_eq!(error("1 2"), "hyphenation pattern must contain letters");
+    }
+}
@@ -1,6 +1,7 @@
 //! Text handling.
 
 mod deco;
+mod hyphenate;
 mod misc;
 mod quotes;
 mod raw;
@@ -8,6 +9,7 @@ mod shaping;
 mod shift;
 
 pub use self::deco::*;
+pub use self::hyphenate::*;
 pub use self::misc::*;
 pub use self::quotes::*;
 pub use self::raw::*;
@@ -186,8 +188,19 @@ pub struct TextElem {
     #[resolve]
     pub baseline: Length,
 
-    /// Whether certain glyphs can hang over into the margin in justified text.
-    /// This can make justification visually more pleasing.
+    /// How far certain glyphs hang over into the margins.
+    ///
+    /// Letting punctuation like hyphens, periods and quotes protrude slightly
+    /// into the margin makes the paragraph's edges look straighter, which is
+    /// particularly pleasing in justified text.
+    ///
+    /// - `{true}`: Use the built-in amounts, which let dashes and punctuation
+    ///   hang into the end margin.
+    /// - `{false}`: Let no glyph hang into the margins.
+    /// - A dictionary: Override the amounts for specific characters on top of
+    ///   the built-in ones. Each key is a single character and each value
+    ///   either a ratio for the end margin or a dictionary with `start` and
+    ///   `end` ratios. The ratios are relative to the glyph's width.
     ///
     /// ```example
     /// #set par(justify: true)
@@ -205,9 +218,33 @@ pub struct TextElem {
     /// hyphen does not hang into the
     /// margin, making the paragraph's
     /// edge less clear.
+    ///
+    /// #set text(overhang: (
+    ///   "\"": (start: 50%, end: 50%),
+    ///   ",": 100%,
+    /// ))
+    /// "Quotes hang into the start
+    /// margin," she said, "while commas
+    /// hang all the way into the end."
     /// ```
-    #[default(true)]
-    pub overhang: bool,
+    #[default]
+    pub overhang: Overhang,
+
+    /// The maximum amount by which glyphs may be stretched or shrunk
+    /// horizontally to improve justification.
+    ///
+    /// With font expansion, justified lines don't need to rely on the spaces
+    /// between words alone to fill the line. Small values of up to a few
+    /// percent are typically imperceptible.
+    ///
+    /// ```example
+    /// #set par(justify: true)
+    /// #set text(expansion: 2%)
+    /// With a little bit of font
+    /// expansion, the spacing between
+    /// the words becomes more even.
+    /// ```
+    pub expansion: Ratio,
 
     /// The top end of the conceptual frame around the text used for layout and
     /// positioning. This affects the size of containers that hold text.
@@ -271,6 +308,8 @@ pub struct TextElem {
     /// - `{auto}`: Automatically infer the direction from the `lang` property.
     /// - `{ltr}`: Layout text from left to right.
     /// - `{rtl}`: Layout text from right to left.
+    /// - `{ttb}`: Layout text vertically from top to bottom. Lines are then
+    ///   stacked from right to left.
     ///
     /// When writing in right-to-left scripts like Arabic or Hebrew, you should
     /// set the [text language]($func/text.lang) or direction. While individual
@@ -290,6 +329,17 @@ pub struct TextElem {
     /// #set text(dir: rtl)
     /// هذا عربي.
     /// ```
+    ///
+    /// In vertical writing mode, Chinese and Japanese characters stand upright
+    /// and use their vertical alternates, while runs of other scripts are
+    /// rotated clockwise. Pages of a document set in vertical writing flow
+    /// from right to left.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #set text(lang: "ja", dir: ttb)
+    /// 吾輩は猫である。名前はまだ無い。
+    /// ```
     #[resolve]
     pub dir: TextDir,
 
@@ -313,6 +363,48 @@ pub struct TextElem {
     #[resolve]
     pub hyphenate: Hyphenate,
 
+    /// Words with custom hyphenation opportunities, marked by hyphens.
+    ///
+    /// These override the hyphenation patterns of the language. A word without
+    /// any hyphens is never hyphenated. The comparison with the words in the
+    /// text ignores case.
+    ///
+    /// ```example
+    /// #set page(width: 80pt)
+    /// #set par(justify: true)
+    /// #set text(hyphenation-exceptions: ("data-base", "Typst"))
+    /// A database for Typst documents.
+    /// ```
+    pub hyphenation_exceptions: HyphenationExceptions,
+
+    /// Hyphenation patterns to use instead of the built-in ones for the
+    /// language.
+    ///
+    /// The patterns are given in the format of TeX's pattern files, either
+    /// with `\patterns{...}` and optional `\hyphenation{...}` blocks or as a
+    /// plain list of patterns. This lets you hyphenate languages for which
+    /// Typst has no built-in patterns. Since the patterns apply to all text
+    /// they are set for, you should set them together with the [text
+    /// language]($func/text.lang).
+    ///
+    /// ```typ
+    /// #set text(
+    ///   lang: "eu",
+    ///   hyphenation-patterns: read("hyph-eu.tex"),
+    /// )
+    /// ```
+    pub hyphenation_patterns: Option<HyphenationPatterns>,
+
+    /// The minimum number of characters before the first hyphenation
+    /// opportunity of a word.
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub hyphenation_left_min: NonZeroUsize,
+
+    /// The minimum number of characters after the last hyphenation opportunity
+    /// of a word.
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub hyphenation_right_min: NonZeroUsize,
+
     /// Whether to apply kerning.
     ///
     /// When enabled, specific letter pairings move closer together or further
@@ -611,8 +703,8 @@ pub struct TextDir(pub Smart<Dir>);
 cast_from_value! {
     TextDir,
     v: Smart<Dir> => {
-        if v.map_or(false, |dir| dir.axis() == Axis::Y) {
-            Err("text direction must be horizontal")?;
+        if v == Smart::Custom(Dir::BTT) {
+            Err("text direction must be horizontal or top-to-bottom")?;
         }
         Self(v)
     },
@@ -622,17 +714,124 @@ cast_to_value! {
     v: TextDir => v.0.into()
 }
 
+impl TextDir {
+    /// Whether text is set in vertical writing mode in the given style chain.
+    pub fn is_vertical(styles: StyleChain) -> bool {
+        let dir = styles.get::<Self>(TextElem::func(), "dir", None, Self::default);
+        dir.0 == Smart::Custom(Dir::TTB)
+    }
+}
+
 impl Resolve for TextDir {
     type Output = Dir;
 
     fn resolve(self, styles: StyleChain) -> Self::Output {
         match self.0 {
             Smart::Auto => TextElem::lang_in(styles).dir(),
+            // In vertical writing mode, lines are laid out from left to right
+            // and rotated afterwards.
+            Smart::Custom(Dir::TTB) => Dir::LTR,
             Smart::Custom(dir) => dir,
         }
     }
 }
 
+/// How far characters hang into the start and end margins of a line.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Overhang {
+    /// Whether the built-in amounts apply.
+    pub builtin: bool,
+    /// Amounts for the start and end margin for specific characters,
+    /// overriding the built-in ones.
+    pub custom: Vec<(char, Ratio, Ratio)>,
+}
+
+impl Overhang {
+    /// How much the character hangs into the start and end margin, relative
+    /// to its width.
+    pub fn amounts(&self, c: char) -> (f64, f64) {
+        if let Some(&(_, start, end)) = self.custom.iter().find(|(k, ..)| *k == c) {
+            return (start.get(), end.get());
+        }
+
+        if !self.builtin {
+            return (0.0, 0.0);
+        }
+
+        // For more discussion, see:
+        // https://recoveringphysicist.com/21/
+        let end = match c {
+            // Dashes.
+            '–' | '—' => 0.2,
+            '-' => 0.55,
+
+            // Punctuation.
+            '.' | ',' => 0.8,
+            ':' | ';' => 0.3,
+
+            // Arabic and Ideographic
+            '\u{60C}' | '\u{6D4}' => 0.4,
+            '\u{3001}' | '\u{3002}' => 1.0,
+
+            _ => 0.0,
+        };
+
+        (0.0, end)
+    }
+}
+
+impl Default for Overhang {
+    fn default() -> Self {
+        Self { builtin: true, custom: vec![] }
+    }
+}
+
+cast_from_value! {
+    Overhang,
+    v: bool => Self { builtin: v, custom: vec![] },
+    dict: Dict => {
+        let mut custom = vec![];
+        for (key, value) in dict {
+            let mut chars = key.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err("overhang keys must be single characters".into());
+            };
+
+            let (start, end) = match value {
+                Value::Dict(mut amounts) => {
+                    let start = amounts.take("start").ok().map(Ratio::cast).transpose()?;
+                    let end = amounts.take("end").ok().map(Ratio::cast).transpose()?;
+                    amounts.finish(&["start", "end"])?;
+                    (start.unwrap_or_default(), end.unwrap_or_default())
+                }
+                v => (Ratio::zero(), v.cast()?),
+            };
+
+            custom.push((c, start, end));
+        }
+        Self { builtin: true, custom }
+    },
+}
+
+cast_to_value! {
+    v: Overhang => {
+        if v.custom.is_empty() {
+            return v.builtin.into();
+        }
+
+        let mut dict = Dict::new();
+        for (c, start, end) in v.custom {
+            let amounts = if start.is_zero() {
+                end.into()
+            } else {
+                Value::Dict(dict! { "start" => start, "end" => end })
+            };
+            dict.insert(c.into(), amounts);
+        }
+        dict.into()
+    }
+}
+
 /// Whether to hyphenate text.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Hyphenate(pub Smart<bool>);
@@ -1,11 +1,20 @@
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::sync::Arc;
+
 use once_cell::sync::Lazy;
 use syntect::highlighting as synt;
+use syntect::parsing::{SyntaxDefinition, SyntaxSet};
+use typst::eval::{locate, Scope};
 use typst::syntax::{self, LinkedNode};
+use typst::util::Buffer;
 
 use super::{
-    FontFamily, FontList, Hyphenate, LinebreakElem, SmartQuoteElem, TextElem, TextSize,
+    FontFamily, FontList, Hyphenate, LinebreakElem, Overhang, SmartQuoteElem, TextElem,
+    TextSize,
 };
-use crate::layout::BlockElem;
+use crate::layout::{BlockElem, GridElem, ParElem, Sizing, TrackSizings};
+use crate::meta::Numbering;
 use crate::prelude::*;
 
 /// Raw text with optional syntax highlighting.
@@ -36,6 +45,11 @@ use crate::prelude::*;
 /// Display: Raw Text / Code
 /// Category: text
 #[element(Synthesize, Show, Finalize)]
+#[scope(
+    let mut scope = Scope::new();
+    scope.define("line", RawLineElem::func());
+    scope
+)]
 pub struct RawElem {
     /// The raw text.
     ///
@@ -101,6 +115,113 @@ pub struct RawElem {
     /// ```
     /// ````
     pub lang: Option<EcoString>,
+
+    /// Additional syntax definitions to highlight with.
+    ///
+    /// Each path must point to a file in the
+    /// [`sublime-syntax`](https://www.sublimetext.com/docs/syntax.html) format.
+    /// The syntaxes defined in these files are available in addition to the
+    /// built-in ones and can be selected through their file extensions with the
+    /// `lang` argument.
+    ///
+    /// ```
+    /// #set raw(syntaxes: ("mylang.sublime-syntax",))
+    /// ```
+    #[parse(
+        args.named::<Spanned<Vec<RawPath>>>("syntaxes")?.map(|Spanned { v, span }| {
+            v.into_iter().map(|path| path.at(span)).collect()
+        })
+    )]
+    pub syntaxes: Vec<RawPath>,
+
+    /// The theme to highlight with.
+    ///
+    /// The path must point to a file in the
+    /// [`tmTheme`](https://www.sublimetext.com/docs/color_schemes_tmtheme.html)
+    /// format. When set to `{none}`, the built-in theme is used.
+    ///
+    /// ```
+    /// #set raw(theme: "solarized.tmTheme")
+    /// ```
+    #[parse(
+        args.named::<Spanned<Option<RawPath>>>("theme")?
+            .map(|Spanned { v, span }| v.map(|path| path.at(span)))
+    )]
+    pub theme: Option<RawPath>,
+
+    /// How to number the lines of a raw block.
+    ///
+    /// Line numbers are displayed in a column next to the code. They are only
+    /// shown for raw blocks, not for inline raw text.
+    ///
+    /// ````example
+    /// #set raw(numbering: "1")
+    ///
+    /// ```rust
+    /// fn main() {
+    ///     println!("Hello World!");
+    /// }
+    /// ```
+    /// ````
+    pub numbering: Option<Numbering>,
+
+    /// The number of the first line.
+    ///
+    /// ````example
+    /// #set raw(numbering: "1", start: 10)
+    ///
+    /// ```py
+    /// x = 1
+    /// y = x + 1
+    /// ```
+    /// ````
+    #[default(1)]
+    pub start: usize,
+
+    /// The numbers of the lines to highlight.
+    ///
+    /// Lines are identified by their numbers, i.e. with respect to the `start`
+    /// of the numbering. Ranges of lines can be highlighted with the
+    /// [`range`]($func/range) function. Highlighting only affects raw blocks.
+    ///
+    /// ````example
+    /// #set raw(highlight: (1, ..range(3, 5)))
+    ///
+    /// ```rust
+    /// let a = 1;
+    /// let b = 2;
+    /// let c = a + b;
+    /// let d = c * 2;
+    /// ```
+    /// ````
+    pub highlight: Vec<usize>,
+
+    /// The background fill of highlighted lines.
+    ///
+    /// ````example
+    /// #set raw(highlight: (2,), highlight-fill: yellow.lighten(60%))
+    ///
+    /// ```js
+    /// let x = 1;
+    /// let y = x * 2;
+    /// ```
+    /// ````
+    #[default(Color::Luma(LumaColor(230)).into())]
+    pub highlight_fill: Paint,
+
+    /// The number of spaces a tab expands to.
+    ///
+    /// Tabs are expanded to the next multiple of the tab size.
+    ///
+    /// ```example
+    /// #raw(
+    ///   "Year\tMonth\tDay\n2000\t2\t3\n2001\t12\t1",
+    ///   block: true,
+    ///   tab-size: 8,
+    /// )
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub tab_size: NonZeroUsize,
 }
 
 impl RawElem {
@@ -127,53 +248,139 @@ impl Synthesize for RawElem {
 }
 
 impl Show for RawElem {
-    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
-        let text = self.text();
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let text = self.text().replace("\r\n", "\n");
+        let text = expand_tabs(&text, self.tab_size(styles));
         let lang = self.lang(styles).as_ref().map(|s| s.to_lowercase());
-        let foreground = THEME
+
+        let paths = self.syntaxes(styles);
+        let syntaxes = if paths.is_empty() {
+            None
+        } else {
+            Some(load_syntaxes(vt.world, &paths)?)
+        };
+        let syntaxes = syntaxes.as_deref().unwrap_or(&SYNTAXES);
+
+        let theme = self.theme(styles).map(|path| load_theme(vt.world, &path));
+        let theme = theme.transpose()?;
+        let theme = theme.as_deref().unwrap_or(&THEME);
+
+        let foreground = theme
             .settings
             .foreground
             .map(to_typst)
             .map_or(Color::BLACK, Color::from);
 
-        let mut realized = if matches!(lang.as_deref(), Some("typ" | "typst" | "typc")) {
+        // The highlighted pieces of each line.
+        let mut bodies: Vec<Vec<Content>> = vec![vec![]];
+
+        if matches!(lang.as_deref(), Some("typ" | "typst" | "typc")) {
             let root = match lang.as_deref() {
                 Some("typc") => syntax::parse_code(&text),
                 _ => syntax::parse(&text),
             };
 
-            let mut seq = vec![];
-            let highlighter = synt::Highlighter::new(&THEME);
+            let highlighter = synt::Highlighter::new(theme);
             highlight_themed(
                 &LinkedNode::new(&root),
                 vec![],
                 &highlighter,
                 &mut |node, style| {
-                    seq.push(styled(&text[node.range()], foreground.into(), style));
+                    for (i, piece) in text[node.range()].split('\n').enumerate() {
+                        if i != 0 {
+                            bodies.push(vec![]);
+                        }
+
+                        if !piece.is_empty() {
+                            let piece = styled(piece, foreground.into(), style);
+                            bodies.last_mut().unwrap().push(piece);
+                        }
+                    }
                 },
             );
-
-            Content::sequence(seq)
         } else if let Some(syntax) =
-            lang.and_then(|token| SYNTAXES.find_syntax_by_token(&token))
+            lang.and_then(|token| syntaxes.find_syntax_by_token(&token))
         {
-            let mut seq = vec![];
-            let mut highlighter = syntect::easy::HighlightLines::new(syntax, &THEME);
-            for (i, line) in text.lines().enumerate() {
+            let mut highlighter = syntect::easy::HighlightLines::new(syntax, theme);
+            for (i, line) in text.split('\n').enumerate() {
                 if i != 0 {
-                    seq.push(LinebreakElem::new().pack());
+                    bodies.push(vec![]);
                 }
 
                 for (style, piece) in
-                    highlighter.highlight_line(line, &SYNTAXES).into_iter().flatten()
+                    highlighter.highlight_line(line, syntaxes).into_iter().flatten()
                 {
-                    seq.push(styled(piece, foreground.into(), style));
+                    let piece = styled(piece, foreground.into(), style);
+                    bodies.last_mut().unwrap().push(piece);
                 }
             }
+        } else {
+            bodies = text
+                .split('\n')
+                .map(|line| vec![TextElem::packed(line)])
+                .collect();
+        }
 
-            Content::sequence(seq)
+        let start = self.start(styles);
+        let count = bodies.len();
+        let lines = text.split('\n').zip(bodies).enumerate().map(|(i, (line, body))| {
+            RawLineElem::new(start + i, count, line.into(), Content::sequence(body))
+        });
+
+        let numbering = self.numbering(styles);
+        let highlight: HashSet<_> = self.highlight(styles).into_iter().collect();
+        let mut realized = if self.block(styles)
+            && (numbering.is_some() || !highlight.is_empty())
+        {
+            let leading = ParElem::leading_in(styles);
+            let fill = self.highlight_fill(styles);
+
+            let mut cells = vec![];
+            for line in lines {
+                let number = line.number();
+                if let Some(numbering) = &numbering {
+                    let display = numbering.apply_vt(vt, &[number])?.display();
+                    cells.push(display.aligned(Axes::with_x(Some(GenAlign::End))));
+                }
+
+                // Empty lines still need to take up space.
+                let empty = line.text().is_empty();
+                let mut body = line.pack();
+                if empty {
+                    body += TextElem::packed('\u{200B}');
+                }
+
+                let mut block = BlockElem::new()
+                    .with_width(Smart::Custom(Rel::one()))
+                    .with_body(Some(body));
+                if highlight.contains(&number) {
+                    block = block
+                        .with_fill(Some(fill.clone()))
+                        .with_outset(Sides::splat(Some((leading / 2.0).into())));
+                }
+
+                cells.push(block.pack());
+            }
+
+            let columns = match numbering {
+                Some(_) => vec![Sizing::Auto, Sizing::Fr(Fr::one())],
+                None => vec![Sizing::Fr(Fr::one())],
+            };
+
+            GridElem::new(cells)
+                .with_columns(TrackSizings(columns))
+                .with_column_gutter(TrackSizings(vec![Em::new(1.0).into()]))
+                .with_row_gutter(TrackSizings(vec![leading.into()]))
+                .pack()
         } else {
-            TextElem::packed(text)
+            let mut seq = vec![];
+            for (i, line) in lines.enumerate() {
+                if i != 0 {
+                    seq.push(LinebreakElem::new().pack());
+                }
+                seq.push(line.pack());
+            }
+            Content::sequence(seq)
         };
 
         if self.block(styles) {
@@ -187,7 +394,7 @@ impl Show for RawElem {
 impl Finalize for RawElem {
     fn finalize(&self, realized: Content, _: StyleChain) -> Content {
         let mut styles = Styles::new();
-        styles.set(TextElem::set_overhang(false));
+        styles.set(TextElem::set_overhang(Overhang { builtin: false, custom: vec![] }));
         styles.set(TextElem::set_hyphenate(Hyphenate(Smart::Custom(false))));
         styles.set(TextElem::set_size(TextSize(Em::new(0.8).into())));
         styles
@@ -197,6 +404,146 @@ impl Finalize for RawElem {
     }
 }
 
+/// A line of raw text.
+///
+/// Raw text is split into lines, which can be targeted by show rules. This is,
+/// for instance, useful to emphasize individual lines of an example.
+///
+/// ## Example
+/// ````example
+/// #show raw.line: it => {
+///   if it.number == 2 { strong(it.body) } else { it }
+/// }
+///
+/// ```py
+/// x = 1
+/// y = x + 1
+/// ```
+/// ````
+///
+/// Display: Raw Text Line
+/// Category: text
+#[element(Show)]
+pub struct RawLineElem {
+    /// The line's number.
+    #[required]
+    pub number: usize,
+
+    /// The total number of lines in the raw text.
+    #[required]
+    pub count: usize,
+
+    /// The line's raw text.
+    #[required]
+    pub text: EcoString,
+
+    /// The line's highlighted body.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for RawLineElem {
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body())
+    }
+}
+
+/// Expand tabs to spaces, aligning them to multiples of the tab size.
+fn expand_tabs(text: &str, size: NonZeroUsize) -> EcoString {
+    if !text.contains('\t') {
+        return text.into();
+    }
+
+    let mut expanded = EcoString::new();
+    let mut column = 0;
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                let spaces = size.get() - column % size.get();
+                for _ in 0..spaces {
+                    expanded.push(' ');
+                }
+                column += spaces;
+            }
+            '\n' => {
+                expanded.push(c);
+                column = 0;
+            }
+            _ => {
+                expanded.push(c);
+                column += 1;
+            }
+        }
+    }
+
+    expanded
+}
+
+/// A path to a syntax definition or theme file, together with the span it was
+/// written at.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct RawPath {
+    /// The user-entered path.
+    pub path: EcoString,
+    /// The span of the argument the path was given in.
+    pub span: Span,
+}
+
+impl RawPath {
+    /// Attach a span to the path.
+    fn at(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+
+    /// Load the file the path points to.
+    fn load(&self, world: Tracked<dyn World>) -> SourceResult<Buffer> {
+        let path = locate(world, self.span.source(), &self.path).at(self.span)?;
+        world.file(&path).at(self.span)
+    }
+}
+
+cast_from_value! {
+    RawPath,
+    path: EcoString => Self { path, span: Span::detached() },
+}
+
+cast_to_value! {
+    v: RawPath => v.path.into()
+}
+
+/// Load the built-in syntaxes extended by custom syntax definitions.
+#[comemo::memoize]
+fn load_syntaxes(
+    world: Tracked<dyn World>,
+    paths: &[RawPath],
+) -> SourceResult<Arc<SyntaxSet>> {
+    let mut builder = SYNTAXES.clone().into_builder();
+    for path in paths {
+        let buffer = path.load(world)?;
+        let text = std::str::from_utf8(&buffer)
+            .map_err(|_| "file is not valid utf-8")
+            .at(path.span)?;
+        let syntax = SyntaxDefinition::load_from_str(text, false, None)
+            .map_err(|err| eco_format!("failed to parse syntax ({err})"))
+            .at(path.span)?;
+        builder.add(syntax);
+    }
+    Ok(Arc::new(builder.build()))
+}
+
+/// Load a custom theme.
+#[comemo::memoize]
+fn load_theme(
+    world: Tracked<dyn World>,
+    path: &RawPath,
+) -> SourceResult<Arc<synt::Theme>> {
+    let buffer = path.load(world)?;
+    let theme = synt::ThemeSet::load_from_reader(&mut Cursor::new(buffer.as_slice()))
+        .map_err(|err| eco_format!("failed to parse theme ({err})"))
+        .at(path.span)?;
+    Ok(Arc::new(theme))
+}
+
 /// Highlight a syntax node in a theme by calling `f` with ranges and their
 /// styles.
 fn highlight_themed<F>(
@@ -255,8 +602,8 @@ fn to_syn(RgbaColor { r, g, b, a }: RgbaColor) -> synt::Color {
 }
 
 /// The syntect syntax definitions.
-static SYNTAXES: Lazy<syntect::parsing::SyntaxSet> =
-    Lazy::new(|| syntect::parsing::SyntaxSet::load_defaults_nonewlines());
+static SYNTAXES: Lazy<SyntaxSet> =
+    Lazy::new(|| SyntaxSet::load_defaults_nonewlines());
 
 /// The default theme used for syntax highlighting.
 pub static THEME: Lazy<synt::Theme> = Lazy::new(|| synt::Theme {
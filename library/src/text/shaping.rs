@@ -2,6 +2,7 @@ use std::ops::Range;
 use std::str::FromStr;
 
 use rustybuzz::{Feature, Tag, UnicodeBuffer};
+use ttf_parser::GlyphId;
 use typst::font::{Font, FontVariant};
 use typst::util::SliceExt;
 use unicode_script::{Script, UnicodeScript};
@@ -80,6 +81,21 @@ impl ShapedGlyph {
     pub fn is_justifiable(&self) -> bool {
         self.is_space() || is_spaceless(self.c.script())
     }
+
+    /// Whether the glyph stands upright in vertical writing mode.
+    ///
+    /// Chinese and Japanese characters keep their orientation while all other
+    /// glyphs are rotated along with the line.
+    pub fn is_upright(&self) -> bool {
+        is_upright(self.c)
+    }
+}
+
+/// Whether a character stands upright in vertical writing mode.
+fn is_upright(c: char) -> bool {
+    is_spaceless(c.script())
+        || c.script() == Script::Hangul
+        || matches!(c, '\u{3000}'..='\u{303F}' | '\u{FF00}'..='\u{FFEF}')
 }
 
 /// Does this script separate its words using spaces?
@@ -100,8 +116,10 @@ impl<'a> ShapedText<'a> {
     /// Build the shaped text's frame.
     ///
     /// The `justification` defines how much extra advance width each
-    /// [justifiable glyph](ShapedGlyph::is_justifiable) will get.
-    pub fn build(&self, vt: &Vt, justification: Abs) -> Frame {
+    /// [justifiable glyph](ShapedGlyph::is_justifiable) will get. The
+    /// `expansion` between `-1.0` and `1.0` defines how much of the maximum
+    /// [font expansion]($func/text.expansion) to apply to all other glyphs.
+    pub fn build(&self, vt: &Vt, justification: Abs, expansion: f64) -> Frame {
         let (top, bottom) = self.measure(vt);
         let size = Size::new(self.width, top + bottom);
 
@@ -113,26 +131,35 @@ impl<'a> ShapedText<'a> {
         let lang = TextElem::lang_in(self.styles);
         let decos = TextElem::deco_in(self.styles);
         let fill = TextElem::fill_in(self.styles);
+        let vertical = TextDir::is_vertical(self.styles);
+        let expansion = expansion * TextElem::expansion_in(self.styles).get().abs();
 
-        for ((font, y_offset), group) in
-            self.glyphs.as_ref().group_by_key(|g| (g.font.clone(), g.y_offset))
+        for ((font, y_offset, upright), group) in self
+            .glyphs
+            .as_ref()
+            .group_by_key(|g| (g.font.clone(), g.y_offset, vertical && g.is_upright()))
         {
             let pos = Point::new(offset, top + shift - y_offset.at(self.size));
             let glyphs = group
                 .iter()
-                .map(|glyph| Glyph {
-                    id: glyph.glyph_id,
-                    x_advance: glyph.x_advance
-                        + if glyph.is_justifiable() {
-                            frame.size_mut().x += justification;
-                            Em::from_length(justification, self.size)
-                        } else {
-                            Em::zero()
-                        },
-                    x_offset: glyph.x_offset,
-                    c: glyph.c,
-                    span: glyph.span,
-                    offset: glyph.offset,
+                .map(|glyph| {
+                    let mut x_advance = glyph.x_advance;
+                    if !glyph.is_space() {
+                        x_advance = x_advance * (1.0 + expansion);
+                        frame.size_mut().x += (x_advance - glyph.x_advance).at(self.size);
+                    }
+                    if glyph.is_justifiable() {
+                        frame.size_mut().x += justification;
+                        x_advance += Em::from_length(justification, self.size);
+                    }
+                    Glyph {
+                        id: glyph.glyph_id,
+                        x_advance,
+                        x_offset: glyph.x_offset * (1.0 + expansion),
+                        c: glyph.c,
+                        span: glyph.span,
+                        offset: glyph.offset,
+                    }
                 })
                 .collect();
 
@@ -141,6 +168,7 @@ impl<'a> ShapedText<'a> {
                 size: self.size,
                 lang,
                 fill: fill.clone(),
+                expansion: Ratio::new(expansion),
                 glyphs,
             };
 
@@ -152,7 +180,12 @@ impl<'a> ShapedText<'a> {
                 decorate(&mut frame, deco, &item, shift, pos, width);
             }
 
-            frame.insert(layer, pos, FrameItem::Text(item));
+            if upright {
+                insert_upright(&mut frame, layer, pos, item);
+            } else {
+                frame.insert(layer, pos, FrameItem::Text(item));
+            }
+
             offset += width;
         }
 
@@ -215,6 +248,18 @@ impl<'a> ShapedText<'a> {
             .at(self.size)
     }
 
+    /// How much the glyphs can be stretched or shrunk by font expansion.
+    pub fn expandability(&self) -> Abs {
+        let max = TextElem::expansion_in(self.styles).get().abs();
+        self.glyphs
+            .iter()
+            .filter(|g| !g.is_space())
+            .map(|g| g.x_advance)
+            .sum::<Em>()
+            .at(self.size)
+            * max
+    }
+
     /// Reshape a range of the shaped text, reusing information from this
     /// shaping process if possible.
     pub fn reshape(
@@ -353,6 +398,7 @@ struct ShapingContext<'a> {
     tags: Vec<rustybuzz::Feature>,
     fallback: bool,
     dir: Dir,
+    vertical: bool,
 }
 
 /// Shape text into [`ShapedText`].
@@ -377,6 +423,7 @@ pub fn shape<'a>(
         tags: tags(styles),
         fallback: TextElem::fallback_in(styles),
         dir,
+        vertical: TextDir::is_vertical(styles),
     };
 
     if !text.is_empty() {
@@ -463,15 +510,27 @@ fn shape_segment(
             // Add the glyph to the shaped output.
             // TODO: Don't ignore y_advance.
             let (span, offset) = ctx.spans.span_at(ctx.base + cluster);
+            let c = text[cluster..].chars().next().unwrap();
+
+            // In vertical writing mode, upright glyphs advance along the line
+            // by their vertical metrics. Fonts without them get a full em.
+            let mut x_advance = font.to_em(pos[i].x_advance);
+            if ctx.vertical && is_upright(c) {
+                x_advance = font
+                    .ttf()
+                    .glyph_ver_advance(GlyphId(info.glyph_id as u16))
+                    .map_or(Em::one(), |units| font.to_em(units));
+            }
+
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
                 glyph_id: info.glyph_id as u16,
-                x_advance: font.to_em(pos[i].x_advance),
+                x_advance,
                 x_offset: font.to_em(pos[i].x_offset),
                 y_offset: font.to_em(pos[i].y_offset),
                 cluster: base + cluster,
                 safe_to_break: !info.unsafe_to_break(),
-                c: text[cluster..].chars().next().unwrap(),
+                c,
                 span,
                 offset,
             });
@@ -620,6 +679,57 @@ pub fn families(styles: StyleChain) -> impl Iterator<Item = FontFamily> + Clone
         .chain(tail.iter().copied().map(FontFamily::new))
 }
 
+/// Warn about requested font families that don't exist. Without a warning,
+/// text set in them would silently fall back to other fonts.
+pub fn warn_unknown_families(vt: &mut Vt, styles: StyleChain, span: Span) {
+    let span = styles.span(TextElem::func(), "font").unwrap_or(span);
+    let variant = variant(styles);
+    for family in TextElem::font_in(styles) {
+        if vt.world.book().select(family.as_str(), variant).is_none() {
+            vt.tracer.warn(warning!(span, "unknown font family: {}", family.as_str()));
+        }
+    }
+}
+
+/// Insert the glyphs of a text item one by one, each rotated counter-clockwise
+/// into the center of its cell along the line.
+///
+/// In vertical writing mode, whole lines are rotated clockwise. This undoes
+/// the rotation for glyphs that should stand upright. A glyph's cell is as
+/// long as its vertical advance, while the glyph itself is centered by its
+/// horizontal advance.
+fn insert_upright(frame: &mut Frame, layer: usize, pos: Point, item: TextItem) {
+    let metrics = item.font.metrics();
+    let cy = -(metrics.ascender + metrics.descender).at(item.size) / 2.0;
+
+    let mut x = Abs::zero();
+    for (i, glyph) in item.glyphs.iter().enumerate() {
+        let width = glyph.x_advance.at(item.size);
+        let advance = item.font.advance(glyph.id).unwrap_or(glyph.x_advance);
+        let cx = advance.at(item.size) / 2.0;
+
+        let text = TextItem {
+            font: item.font.clone(),
+            size: item.size,
+            fill: item.fill.clone(),
+            lang: item.lang,
+            expansion: item.expansion,
+            glyphs: vec![*glyph],
+        };
+
+        let mut inner = Frame::new(Size::with_x(width));
+        inner.push(Point::zero(), FrameItem::Text(text));
+
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::translate(width / 2.0, cy)
+            .pre_concat(Transform::rotate(-Angle::deg(90.0)))
+            .pre_concat(Transform::translate(-cx, -cy));
+
+        frame.insert(layer + i, pos + Point::with_x(x), FrameItem::Group(group));
+        x += width;
+    }
+}
+
 /// Collect the tags of the OpenType features to apply.
 fn tags(styles: StyleChain) -> Vec<Feature> {
     let mut tags = vec![];
@@ -641,6 +751,10 @@ fn tags(styles: StyleChain) -> Vec<Feature> {
         feat(b"salt", 1);
     }
 
+    if TextDir::is_vertical(styles) {
+        feat(b"vert", 1);
+    }
+
     let storage;
     if let Some(set) = TextElem::stylistic_set_in(styles) {
         storage = [b's', b's', b'0' + set.get() / 10, b'0' + set.get() % 10];
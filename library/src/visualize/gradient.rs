@@ -0,0 +1,223 @@
+//! Color gradients.
+
+use typst::eval::{Module, Scope};
+
+use crate::prelude::*;
+
+/// A module with functions for creating gradients.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define("linear", linear);
+    scope.define("radial", radial);
+    scope.define("conic", conic);
+    Module::new("gradient").with_scope(scope)
+}
+
+/// Create a linear gradient.
+///
+/// The colors change along a straight line through the center of the painted
+/// shape. Gradients can be used wherever a color is accepted as a fill or
+/// stroke.
+///
+/// ## Example
+/// ```example
+/// #rect(
+///   width: 100%,
+///   fill: gradient.linear(red, blue),
+/// )
+/// #rect(
+///   width: 100%,
+///   fill: gradient.linear(
+///     (navy, 0%), (aqua, 30%), (white, 100%),
+///     angle: 90deg,
+///   ),
+/// )
+/// ```
+///
+/// Display: Linear Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn linear(
+    /// The color stops of the gradient.
+    ///
+    /// A stop is either just a color or an array of a color and its offset
+    /// along the gradient. Either all stops or none must have an offset. Stops
+    /// without offsets are spread evenly.
+    #[variadic]
+    stops: Vec<Spanned<Stop>>,
+    /// The direction of the gradient. At `{0deg}`, it goes from left to
+    /// right; larger angles turn it clockwise.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// What the gradient is laid out in.
+    ///
+    /// - `{"self"}`: The bounding box of the painted shape.
+    /// - `{"parent"}`: The bounding box of the container the shape is in.
+    ///
+    /// By default, shapes are painted relative to themselves and text is
+    /// painted relative to its parent, so that the gradient flows across a
+    /// whole paragraph.
+    #[named]
+    #[default]
+    relative: Smart<Relative>,
+) -> Value {
+    let stops = process_stops(args.span, stops)?;
+    Gradient::new(GradientKind::Linear { angle }, stops, relative).into()
+}
+
+/// Create a radial gradient.
+///
+/// The colors change in circles around a center point.
+///
+/// ## Example
+/// ```example
+/// #circle(
+///   radius: 30pt,
+///   fill: gradient.radial(white, eastern),
+/// )
+/// #rect(
+///   width: 100%,
+///   fill: gradient.radial(
+///     yellow, orange, maroon,
+///     center: (30%, 30%),
+///     radius: 80%,
+///   ),
+/// )
+/// ```
+///
+/// Display: Radial Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn radial(
+    /// The color stops of the gradient. See `gradient.linear` for details.
+    #[variadic]
+    stops: Vec<Spanned<Stop>>,
+    /// The center of the circles, relative to the bounding box.
+    #[named]
+    #[default(Center(Axes::splat(Ratio::new(0.5))))]
+    center: Center,
+    /// The radius of the last circle. At `{50%}`, the circle touches the
+    /// sides of a square bounding box.
+    #[named]
+    #[default(Ratio::new(0.5))]
+    radius: Ratio,
+    /// What the gradient is laid out in. See `gradient.linear` for details.
+    #[named]
+    #[default]
+    relative: Smart<Relative>,
+) -> Value {
+    let stops = process_stops(args.span, stops)?;
+    let kind = GradientKind::Radial { center: center.0, radius };
+    Gradient::new(kind, stops, relative).into()
+}
+
+/// Create a conic gradient.
+///
+/// The colors change around a center point, like on a color wheel.
+///
+/// ## Example
+/// ```example
+/// #square(
+///   size: 60pt,
+///   fill: gradient.conic(red, yellow, green, blue, red),
+/// )
+/// ```
+///
+/// Display: Conic Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn conic(
+    /// The color stops of the gradient. See `gradient.linear` for details.
+    #[variadic]
+    stops: Vec<Spanned<Stop>>,
+    /// The center of the gradient, relative to the bounding box.
+    #[named]
+    #[default(Center(Axes::splat(Ratio::new(0.5))))]
+    center: Center,
+    /// Where the first stop lies. At `{0deg}`, it points to the right; larger
+    /// angles turn it clockwise.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// What the gradient is laid out in. See `gradient.linear` for details.
+    #[named]
+    #[default]
+    relative: Smart<Relative>,
+) -> Value {
+    let stops = process_stops(args.span, stops)?;
+    let kind = GradientKind::Conic { center: center.0, angle };
+    Gradient::new(kind, stops, relative).into()
+}
+
+/// A color stop with an optional offset.
+pub struct Stop(Color, Option<Ratio>);
+
+cast_from_value! {
+    Stop,
+    color: Color => Self(color, None),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self(a.cast()?, Some(b.cast()?)),
+            _ => Err("a color stop must contain exactly a color and an offset")?,
+        }
+    },
+}
+
+/// The center of a gradient within its bounding box.
+pub struct Center(Axes<Ratio>);
+
+cast_from_value! {
+    Center,
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self(Axes::new(a.cast()?, b.cast()?)),
+            _ => Err("center must contain exactly two ratios")?,
+        }
+    },
+}
+
+/// Check the stops and spread those without offsets evenly.
+fn process_stops(
+    span: Span,
+    stops: Vec<Spanned<Stop>>,
+) -> SourceResult<Vec<(Color, Ratio)>> {
+    if stops.len() < 2 {
+        bail!(span, "a gradient must have at least two stops");
+    }
+
+    if stops.iter().all(|stop| stop.v.1.is_none()) {
+        let last = (stops.len() - 1) as f64;
+        return Ok(stops
+            .into_iter()
+            .enumerate()
+            .map(|(i, stop)| (stop.v.0, Ratio::new(i as f64 / last)))
+            .collect());
+    }
+
+    let mut prev = Ratio::zero();
+    let mut processed = vec![];
+    for Spanned { v: Stop(color, offset), span } in stops {
+        let Some(offset) = offset else {
+            bail!(span, "either all or no stops must have an offset");
+        };
+
+        if !(0.0..=1.0).contains(&offset.get()) {
+            bail!(span, "offset must be between 0% and 100%");
+        }
+
+        if offset < prev {
+            bail!(span, "offsets must be in ascending order");
+        }
+
+        prev = offset;
+        processed.push((color, offset));
+    }
+
+    Ok(processed)
+}
@@ -40,9 +40,16 @@ pub struct LineElem {
     ///   to `{1pt}`.
     /// - A stroke combined from color and thickness using the `+` operator as
     ///   in `{2pt + red}`.
+    /// - A [stroke dictionary]($type/stroke) that can additionally configure
+    ///   the line's cap and dash pattern.
     ///
     /// ```example
     /// #line(length: 100%, stroke: 2pt + red)
+    /// #line(length: 100%, stroke: (
+    ///   thickness: 2pt,
+    ///   cap: "round",
+    ///   dash: "dashed",
+    /// ))
     /// ```
     #[resolve]
     #[fold]
@@ -47,6 +47,8 @@ pub struct RectElem {
     ///   to `{1pt}`.
     /// - A stroke combined from color and thickness using the `+` operator as
     ///   in `{2pt + red}`.
+    /// - A [stroke dictionary]($type/stroke) with keys like `paint`, `dash`
+    ///   or `join`, as in `{(thickness: 2pt, dash: "dotted")}`.
     /// - A dictionary: With a dictionary, the stroke for each side can be set
     ///   individually. The dictionary can contain the following keys in order
     ///   of precedence:
@@ -15,6 +15,7 @@ struct Elem {
     ident: Ident,
     capable: Vec<Ident>,
     fields: Vec<Field>,
+    scope: Option<FieldParser>,
 }
 
 struct Field {
@@ -137,7 +138,10 @@ fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
         .into_iter()
         .collect();
 
-    let docs = documentation(&body.attrs);
+    let mut attrs = body.attrs.clone();
+    let scope = parse_attr(&mut attrs, "scope")?.flatten();
+
+    let docs = documentation(&attrs);
     let mut lines = docs.split('\n').collect();
     let category = meta_line(&mut lines, "Category")?.into();
     let display = meta_line(&mut lines, "Display")?.into();
@@ -152,9 +156,10 @@ fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
         ident: body.ident.clone(),
         capable,
         fields,
+        scope,
     };
 
-    validate_attrs(&body.attrs)?;
+    validate_attrs(&attrs)?;
     Ok(element)
 }
 
@@ -346,6 +351,10 @@ fn create_set_field_method(field: &Field) -> TokenStream {
 fn create_pack_impl(element: &Elem) -> TokenStream {
     let Elem { ident, name, display, category, docs, .. } = element;
     let vtable_func = create_vtable_func(element);
+    let scope = match &element.scope {
+        Some(FieldParser { prefix, expr }) => quote! { { #(#prefix)* #expr } },
+        None => quote! { ::typst::eval::Scope::new() },
+    };
     let infos = element
         .fields
         .iter()
@@ -377,6 +386,7 @@ fn create_pack_impl(element: &Elem) -> TokenStream {
                         params: ::std::vec![#(#infos),*],
                         returns: ::std::vec!["content"],
                         category: #category,
+                        scope: #scope,
                     }),
                 };
                 (&NATIVE).into()
@@ -129,6 +129,7 @@ fn create(func: &Func) -> TokenStream {
                     params: ::std::vec![#(#params),*],
                     returns: ::std::vec![#(#returns),*],
                     category: #category,
+                    scope: ::typst::eval::Scope::new(),
                 }),
             };
             &FUNC
@@ -42,20 +42,37 @@ macro_rules! __error {
     };
 }
 
+/// Construct a [`SourceError`] with [warning](Severity::Warning) severity.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __warning {
+    ($span:expr, $message:expr $(,)?) => {
+        $crate::diag::SourceError::warning($span, $message)
+    };
+
+    ($span:expr, $fmt:expr, $($arg:expr),+ $(,)?) => {
+        $crate::diag::warning!($span, $crate::diag::eco_format!($fmt, $($arg),+))
+    };
+}
+
 #[doc(inline)]
 pub use crate::__error as error;
+#[doc(inline)]
+pub use crate::__warning as warning;
 #[doc(hidden)]
 pub use ecow::eco_format;
 
 /// A result that can carry multiple source errors.
 pub type SourceResult<T> = Result<T, Box<Vec<SourceError>>>;
 
-/// An error in a source file.
+/// An error, warning or hint in a source file.
 ///
 /// The contained spans will only be detached if any of the input source files
 /// were detached.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct SourceError {
+    /// Whether this is an error, a warning or a hint.
+    pub severity: Severity,
     /// The span of the erroneous node in the source code.
     pub span: Span,
     /// The position in the node where the error should be annotated.
@@ -71,6 +88,7 @@ impl SourceError {
     #[track_caller]
     pub fn new(span: Span, message: impl Into<EcoString>) -> Self {
         Self {
+            severity: Severity::Error,
             span,
             pos: ErrorPos::Full,
             trace: vec![],
@@ -78,6 +96,25 @@ impl SourceError {
         }
     }
 
+    /// Create a new, bare warning.
+    ///
+    /// Warnings are reported through the [`Tracer`](crate::eval::Tracer) and
+    /// don't make compilation fail.
+    pub fn warning(span: Span, message: impl Into<EcoString>) -> Self {
+        Self::new(span, message).with_severity(Severity::Warning)
+    }
+
+    /// Create a new, bare hint.
+    pub fn hint(span: Span, message: impl Into<EcoString>) -> Self {
+        Self::new(span, message).with_severity(Severity::Hint)
+    }
+
+    /// Change the severity of the diagnostic.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
     /// Adjust the position in the node where the error should be annotated.
     pub fn with_pos(mut self, pos: ErrorPos) -> Self {
         self.pos = pos;
@@ -97,6 +134,17 @@ impl SourceError {
     }
 }
 
+/// How severe a [`SourceError`] is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    /// An error that makes compilation fail.
+    Error,
+    /// A likely mistake that doesn't prevent compilation.
+    Warning,
+    /// Additional information, typically accompanying a warning.
+    Hint,
+}
+
 /// A part of an error's [trace](SourceError::trace).
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Tracepoint {
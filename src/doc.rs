@@ -11,7 +11,7 @@ use crate::eval::{cast_from_value, cast_to_value, dict, Dict, Value};
 use crate::font::Font;
 use crate::geom::{
     self, rounded_rect, Abs, Align, Axes, Color, Corners, Dir, Em, Geometry, Length,
-    Numeric, Paint, Point, Rel, RgbaColor, Shape, Sides, Size, Stroke, Transform,
+    Numeric, Paint, Point, Ratio, Rel, RgbaColor, Shape, Sides, Size, Stroke, Transform,
 };
 use crate::image::Image;
 use crate::model::{Content, Location, MetaElem, StyleChain};
@@ -26,6 +26,9 @@ pub struct Document {
     pub title: Option<EcoString>,
     /// The document's author.
     pub author: Vec<EcoString>,
+    /// Whether the document is set in vertical writing mode, in which case its
+    /// pages flow from right to left.
+    pub vertical: bool,
 }
 
 /// A finished layout with items at fixed positions.
@@ -325,6 +328,14 @@ impl Frame {
         }
     }
 
+    /// Attribute the contents of the frame to a unit of the document's
+    /// logical structure.
+    pub fn tag(&mut self, tag: Tag) {
+        if !self.is_empty() {
+            self.group(|g| g.tag = Some(tag));
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -359,6 +370,7 @@ impl Frame {
                 Geometry::Line(Point::with_x(self.size.x)).stroked(Stroke {
                     paint: Color::RED.into(),
                     thickness: Abs::pt(1.0),
+                    ..Stroke::default()
                 }),
                 Span::detached(),
             ),
@@ -386,6 +398,7 @@ impl Frame {
                 Geometry::Line(Point::with_x(self.size.x)).stroked(Stroke {
                     paint: Color::GREEN.into(),
                     thickness: Abs::pt(1.0),
+                    ..Stroke::default()
                 }),
                 Span::detached(),
             ),
@@ -438,6 +451,9 @@ pub struct GroupItem {
     pub transform: Transform,
     /// Whether the frame should be a clipping boundary.
     pub clips: bool,
+    /// The unit of the document's logical structure the group's contents
+    /// belong to.
+    pub tag: Option<Tag>,
 }
 
 impl GroupItem {
@@ -447,6 +463,7 @@ impl GroupItem {
             frame,
             transform: Transform::identity(),
             clips: false,
+            tag: None,
         }
     }
 }
@@ -458,6 +475,43 @@ impl Debug for GroupItem {
     }
 }
 
+/// A unit of the document's logical structure.
+///
+/// Layout attaches tags to groups so that exporters know which content makes
+/// up paragraphs, lists and tables. Elements that are split across regions
+/// produce one tagged group per region, all but the first of which are marked
+/// as continuations.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Tag {
+    /// A paragraph. The flag is set for continuations.
+    Par(bool),
+    /// A list of any kind. The flag is set for continuations.
+    List(bool),
+    /// The label of the list item in the given row of a list.
+    ListLabel(usize),
+    /// The body of the list item in the given row of a list.
+    ListBody(usize),
+    /// A table. The flag is set for continuations.
+    Table(bool),
+    /// A table cell in the given section with the given column and row
+    /// within that section.
+    TableCell(TableSection, usize, usize),
+    /// Content that only repeats other content, like the header of a table
+    /// on a followup page.
+    Artifact,
+}
+
+/// A section of a table.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TableSection {
+    /// The rows that repeat at the top of each page.
+    Header,
+    /// The regular rows.
+    Body,
+    /// The rows that repeat at the bottom of each page.
+    Footer,
+}
+
 /// A run of shaped text.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct TextItem {
@@ -469,6 +523,10 @@ pub struct TextItem {
     pub fill: Paint,
     /// The natural language of the text.
     pub lang: Lang,
+    /// How much the glyphs are stretched (positive) or shrunk (negative)
+    /// horizontally through font expansion. Their advances already include
+    /// the expansion.
+    pub expansion: Ratio,
     /// The glyphs.
     pub glyphs: Vec<Glyph>,
 }
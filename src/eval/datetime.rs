@@ -0,0 +1,439 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter, Write};
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::StrResult;
+
+/// A calendar date, optionally with a time of day.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Datetime {
+    year: i32,
+    month: u8,
+    day: u8,
+    time: Option<(u8, u8, u8)>,
+}
+
+impl Datetime {
+    /// Create a date from a year, month and day.
+    ///
+    /// Returns `None` if the date does not exist.
+    pub fn from_ymd(year: i32, month: u8, day: u8) -> Option<Self> {
+        let valid =
+            (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day);
+        valid.then_some(Self { year, month, day, time: None })
+    }
+
+    /// Create a date with a time of day.
+    ///
+    /// Returns `None` if the date or the time does not exist.
+    pub fn from_ymd_hms(
+        year: i32,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Option<Self> {
+        let date = Self::from_ymd(year, month, day)?;
+        let valid = hour < 24 && minute < 60 && second < 60;
+        valid.then_some(Self { time: Some((hour, minute, second)), ..date })
+    }
+
+    /// The year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// The month, from 1 to 12.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// The day of the month, starting at 1.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// The hour, if this has a time of day.
+    pub fn hour(&self) -> Option<u8> {
+        self.time.map(|(hour, _, _)| hour)
+    }
+
+    /// The minute, if this has a time of day.
+    pub fn minute(&self) -> Option<u8> {
+        self.time.map(|(_, minute, _)| minute)
+    }
+
+    /// The second, if this has a time of day.
+    pub fn second(&self) -> Option<u8> {
+        self.time.map(|(_, _, second)| second)
+    }
+
+    /// The day of the week, from 1 (Monday) to 7 (Sunday).
+    pub fn weekday(&self) -> u8 {
+        // The 1st of January 1970 was a Thursday.
+        ((self.days() + 3).rem_euclid(7) + 1) as u8
+    }
+
+    /// The day of the year, starting at 1.
+    pub fn ordinal(&self) -> u16 {
+        (self.days() - days_from_civil(self.year, 1, 1) + 1) as u16
+    }
+
+    /// Add a duration to this date.
+    ///
+    /// The result only has a time of day if this date has one or if the
+    /// duration is not a whole number of days.
+    pub fn checked_add(self, duration: Duration) -> StrResult<Self> {
+        let overflow = || EcoString::from("datetime is out of range");
+        let timestamp = self.timestamp().checked_add(duration.0).ok_or_else(overflow)?;
+        let days = timestamp.div_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        let year = i32::try_from(year).map_err(|_| overflow())?;
+        let time = if self.time.is_some() || duration.0 % SECONDS_PER_DAY != 0 {
+            let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+            Some((
+                (seconds / 3600) as u8,
+                (seconds / 60 % 60) as u8,
+                (seconds % 60) as u8,
+            ))
+        } else {
+            None
+        };
+        Ok(Self { year, month, day, time })
+    }
+
+    /// The duration between two dates.
+    pub fn checked_sub(self, other: Self) -> StrResult<Duration> {
+        self.timestamp()
+            .checked_sub(other.timestamp())
+            .map(Duration)
+            .ok_or_else(|| "duration overflow".into())
+    }
+
+    /// Format the date with a pattern.
+    ///
+    /// The pattern consists of literal text and components in square brackets,
+    /// e.g. `[year]-[month]-[day]`. Each component can be followed by
+    /// `key:value` modifiers. Without a pattern, the date is formatted in ISO
+    /// 8601 style.
+    pub fn display(&self, pattern: Option<&str>) -> StrResult<EcoString> {
+        let pattern = pattern.unwrap_or(if self.time.is_some() {
+            "[year]-[month]-[day] [hour]:[minute]:[second]"
+        } else {
+            "[year]-[month]-[day]"
+        });
+
+        let mut out = EcoString::new();
+        let mut rest = pattern;
+        while let Some(start) = rest.find('[') {
+            out.push_str(&rest[..start]);
+            rest = &rest[start + 1..];
+
+            // Two opening brackets produce a literal one.
+            if let Some(tail) = rest.strip_prefix('[') {
+                out.push('[');
+                rest = tail;
+                continue;
+            }
+
+            let Some(end) = rest.find(']') else {
+                return Err("unclosed bracket in pattern".into());
+            };
+
+            self.component(&rest[..end], &mut out)?;
+            rest = &rest[end + 1..];
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Format a single pattern component like `month repr:long`.
+    fn component(&self, spec: &str, out: &mut EcoString) -> StrResult<()> {
+        let mut parts = spec.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+
+        let mut padding = Padding::Zero;
+        let mut repr = None;
+        for modifier in parts {
+            let Some((key, value)) = modifier.split_once(':') else {
+                return Err(eco_format!("invalid modifier: {modifier}"));
+            };
+            match (key, value) {
+                ("padding", "zero") => padding = Padding::Zero,
+                ("padding", "space") => padding = Padding::Space,
+                ("padding", "none") => padding = Padding::None,
+                ("repr", _) => repr = Some(value),
+                _ => return Err(eco_format!("invalid modifier: {modifier}")),
+            }
+        }
+
+        let time = |value: Option<u8>| {
+            value.ok_or_else(|| {
+                eco_format!("cannot display {name} of a date without time")
+            })
+        };
+
+        let (value, width) = match (name, repr) {
+            ("year", None) => (self.year as i64, 4),
+            ("month", None | Some("numerical")) => (self.month as i64, 2),
+            ("month", Some("long")) => return name_of(MONTHS, self.month, None, out),
+            ("month", Some("short")) => return name_of(MONTHS, self.month, Some(3), out),
+            ("day", None) => (self.day as i64, 2),
+            ("hour", None) => (time(self.hour())? as i64, 2),
+            ("minute", None) => (time(self.minute())? as i64, 2),
+            ("second", None) => (time(self.second())? as i64, 2),
+            ("ordinal", None) => (self.ordinal() as i64, 3),
+            ("weekday", Some("monday")) => (self.weekday() as i64, 1),
+            ("weekday", None | Some("long")) => {
+                return name_of(WEEKDAYS, self.weekday(), None, out);
+            }
+            ("weekday", Some("short")) => {
+                return name_of(WEEKDAYS, self.weekday(), Some(3), out);
+            }
+            (
+                "year" | "month" | "day" | "hour" | "minute" | "second" | "ordinal"
+                | "weekday",
+                Some(repr),
+            ) => return Err(eco_format!("invalid repr for {name}: {repr}")),
+            _ => return Err(eco_format!("unknown component: {name}")),
+        };
+
+        let digits = eco_format!("{}", value.unsigned_abs());
+        if value < 0 {
+            out.push('-');
+        }
+        let (fill, width) = match padding {
+            Padding::Zero => ('0', width),
+            Padding::Space => (' ', width),
+            Padding::None => (' ', 0),
+        };
+        for _ in digits.len()..width {
+            out.push(fill);
+        }
+        out.push_str(&digits);
+        Ok(())
+    }
+
+    /// The number of days since the 1st of January 1970.
+    fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    /// The number of seconds since midnight of the 1st of January 1970.
+    ///
+    /// Dates without a time of day count from midnight.
+    fn timestamp(&self) -> i64 {
+        let (hour, minute, second) = self.time.unwrap_or_default();
+        self.days() * SECONDS_PER_DAY
+            + hour as i64 * 3600
+            + minute as i64 * 60
+            + second as i64
+    }
+}
+
+impl Debug for Datetime {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "datetime(year: {}, month: {}, day: {}",
+            self.year, self.month, self.day
+        )?;
+        if let Some((hour, minute, second)) = self.time {
+            write!(f, ", hour: {hour}, minute: {minute}, second: {second}")?;
+        }
+        f.write_char(')')
+    }
+}
+
+impl PartialOrd for Datetime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // Dates are only comparable with dates and date-times only with
+        // date-times.
+        if self.time.is_some() != other.time.is_some() {
+            return None;
+        }
+        Some(self.timestamp().cmp(&other.timestamp()))
+    }
+}
+
+/// How to pad a numeric component.
+#[derive(Copy, Clone)]
+enum Padding {
+    Zero,
+    Space,
+    None,
+}
+
+/// The English names of the months.
+const MONTHS: &[&str] = &[
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// The English names of the weekdays, starting on Monday.
+const WEEKDAYS: &[&str] =
+    &["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// Write the one-based `index`th name, optionally abbreviated.
+fn name_of(
+    names: &[&str],
+    index: u8,
+    len: Option<usize>,
+    out: &mut EcoString,
+) -> StrResult<()> {
+    let name = names[index as usize - 1];
+    out.push_str(&name[..len.unwrap_or(name.len())]);
+    Ok(())
+}
+
+/// A signed length of time, with a precision of one second.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Duration(i64);
+
+impl Duration {
+    /// Create a duration from a number of seconds.
+    pub fn from_seconds(seconds: i64) -> Self {
+        Self(seconds)
+    }
+
+    /// The duration in seconds.
+    pub fn seconds(self) -> f64 {
+        self.0 as f64
+    }
+
+    /// The duration in minutes.
+    pub fn minutes(self) -> f64 {
+        self.seconds() / 60.0
+    }
+
+    /// The duration in hours.
+    pub fn hours(self) -> f64 {
+        self.seconds() / 3600.0
+    }
+
+    /// The duration in days.
+    pub fn days(self) -> f64 {
+        self.seconds() / SECONDS_PER_DAY as f64
+    }
+
+    /// The duration in weeks.
+    pub fn weeks(self) -> f64 {
+        self.days() / 7.0
+    }
+
+    /// Negate the duration, checking for overflow.
+    pub fn checked_neg(self) -> StrResult<Self> {
+        self.0.checked_neg().map(Self).ok_or_else(|| "duration overflow".into())
+    }
+
+    /// Add two durations, checking for overflow.
+    pub fn checked_add(self, other: Self) -> StrResult<Self> {
+        self.0
+            .checked_add(other.0)
+            .map(Self)
+            .ok_or_else(|| "duration overflow".into())
+    }
+
+    /// Subtract two durations, checking for overflow.
+    pub fn checked_sub(self, other: Self) -> StrResult<Self> {
+        self.0
+            .checked_sub(other.0)
+            .map(Self)
+            .ok_or_else(|| "duration overflow".into())
+    }
+
+    /// Multiply the duration with an integer, checking for overflow.
+    pub fn checked_mul(self, factor: i64) -> StrResult<Self> {
+        self.0
+            .checked_mul(factor)
+            .map(Self)
+            .ok_or_else(|| "duration overflow".into())
+    }
+}
+
+impl Debug for Duration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let total = self.0.unsigned_abs();
+        let parts = [
+            ("days", total / SECONDS_PER_DAY as u64),
+            ("hours", total / 3600 % 24),
+            ("minutes", total / 60 % 60),
+            ("seconds", total % 60),
+        ];
+
+        f.write_str("duration(")?;
+        let mut first = true;
+        for (name, value) in parts {
+            if value != 0 || (total == 0 && name == "seconds") {
+                if !first {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{name}: {sign}{value}")?;
+                first = false;
+            }
+        }
+        f.write_char(')')
+    }
+}
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Whether a year is a leap year in the proleptic Gregorian calendar.
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// The number of days in a month.
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days from the 1st of January 1970 to a date.
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let shifted = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted + 2) / 5 + day as i64 - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date that lies a number of days after the 1st of January 1970.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted + 2) / 5 + 1) as u8;
+    let month = if shifted < 10 { shifted + 3 } else { shifted - 9 } as u8;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
@@ -5,12 +5,13 @@ use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use comemo::{Prehashed, Track, Tracked, TrackedMut};
+use ecow::eco_format;
 use once_cell::sync::Lazy;
 
 use super::{
     cast_to_value, Args, CastInfo, Eval, Flow, Route, Scope, Scopes, Tracer, Value, Vm,
 };
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::model::{ElemFunc, Introspector, StabilityProvider, Vt};
 use crate::syntax::ast::{self, AstNode, Expr, Ident};
 use crate::syntax::{SourceId, Span, SyntaxNode};
@@ -59,6 +60,14 @@ impl Func {
         }
     }
 
+    /// Get a definition from the function's scope, like `table.cell`.
+    pub fn get(&self, field: &str) -> StrResult<&Value> {
+        let name = self.name().unwrap_or("anonymous");
+        self.info()
+            .and_then(|info| info.scope.get(field))
+            .ok_or_else(|| eco_format!("function `{name}` does not contain `{field}`"))
+    }
+
     /// The function's span.
     pub fn span(&self) -> Span {
         self.span
@@ -228,6 +237,8 @@ pub struct FuncInfo {
     pub returns: Vec<&'static str>,
     /// Which category the function is part of.
     pub category: &'static str,
+    /// Definitions that are accessible as fields of the function.
+    pub scope: Scope,
 }
 
 impl FuncInfo {
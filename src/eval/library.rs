@@ -34,6 +34,9 @@ pub struct LangItems {
     /// The root layout function.
     pub layout:
         fn(vt: &mut Vt, content: &Content, styles: StyleChain) -> SourceResult<Document>,
+    /// The root HTML export function.
+    pub html:
+        fn(vt: &mut Vt, content: &Content, styles: StyleChain) -> SourceResult<String>,
     /// Access the em size.
     pub em: fn(StyleChain) -> Abs,
     /// Access the text direction.
@@ -109,6 +112,7 @@ impl Debug for LangItems {
 impl Hash for LangItems {
     fn hash<H: Hasher>(&self, state: &mut H) {
         (self.layout as usize).hash(state);
+        (self.html as usize).hash(state);
         (self.em as usize).hash(state);
         (self.dir as usize).hash(state);
         self.space.hash(state);
@@ -26,6 +26,31 @@ pub fn call(
             _ => return missing(),
         },
 
+        Value::Datetime(datetime) => match method {
+            "display" => {
+                let pattern = args.eat::<EcoString>()?;
+                Value::Str(datetime.display(pattern.as_deref()).at(span)?.into())
+            }
+            "year" => Value::Int(datetime.year().into()),
+            "month" => Value::Int(datetime.month().into()),
+            "day" => Value::Int(datetime.day().into()),
+            "hour" => datetime.hour().map_or(Value::None, |v| Value::Int(v.into())),
+            "minute" => datetime.minute().map_or(Value::None, |v| Value::Int(v.into())),
+            "second" => datetime.second().map_or(Value::None, |v| Value::Int(v.into())),
+            "weekday" => Value::Int(datetime.weekday().into()),
+            "ordinal" => Value::Int(datetime.ordinal().into()),
+            _ => return missing(),
+        },
+
+        Value::Duration(duration) => match method {
+            "seconds" => Value::Float(duration.seconds()),
+            "minutes" => Value::Float(duration.minutes()),
+            "hours" => Value::Float(duration.hours()),
+            "days" => Value::Float(duration.days()),
+            "weeks" => Value::Float(duration.weeks()),
+            _ => return missing(),
+        },
+
         Value::Str(string) => match method {
             "len" => Value::Int(string.len()),
             "first" => Value::Str(string.first().at(span)?),
@@ -253,6 +278,24 @@ fn missing_method(type_name: &str, method: &str) -> String {
 pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
     match type_name {
         "color" => &[("lighten", true), ("darken", true), ("negate", false)],
+        "datetime" => &[
+            ("day", false),
+            ("display", true),
+            ("hour", false),
+            ("minute", false),
+            ("month", false),
+            ("ordinal", false),
+            ("second", false),
+            ("weekday", false),
+            ("year", false),
+        ],
+        "duration" => &[
+            ("days", false),
+            ("hours", false),
+            ("minutes", false),
+            ("seconds", false),
+            ("weeks", false),
+        ],
         "string" => &[
             ("len", false),
             ("at", true),
@@ -13,10 +13,12 @@ mod str;
 #[macro_use]
 mod value;
 mod args;
+mod datetime;
 mod func;
 mod methods;
 mod module;
 mod ops;
+mod package;
 mod scope;
 mod symbol;
 
@@ -26,10 +28,12 @@ pub use once_cell::sync::Lazy;
 pub use self::args::*;
 pub use self::array::*;
 pub use self::cast::*;
+pub use self::datetime::*;
 pub use self::dict::*;
 pub use self::func::*;
 pub use self::library::*;
 pub use self::module::*;
+pub use self::package::*;
 pub use self::scope::*;
 pub use self::str::*;
 pub use self::symbol::*;
@@ -46,7 +50,7 @@ use ecow::EcoVec;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::diag::{
-    bail, error, At, SourceError, SourceResult, StrResult, Trace, Tracepoint,
+    bail, error, warning, At, SourceError, SourceResult, StrResult, Trace, Tracepoint,
 };
 use crate::model::{
     Content, Introspector, Label, Recipe, Selector, StabilityProvider, Styles, Transform,
@@ -214,18 +218,44 @@ impl<'a> Vm<'a> {
     /// Resolve a user-entered path to be relative to the compilation
     /// environment's root.
     pub fn locate(&self, path: &str) -> StrResult<PathBuf> {
-        if !self.location.is_detached() {
-            if let Some(path) = path.strip_prefix('/') {
-                return Ok(self.world().root().join(path).normalize());
-            }
+        locate(self.world(), self.location, path)
+    }
+}
 
-            if let Some(dir) = self.world().source(self.location).path().parent() {
-                return Ok(dir.join(path).normalize());
+/// Resolve a user-entered path written in the given source file to be relative
+/// to the compilation environment's root.
+pub fn locate(world: Tracked<dyn World>, id: SourceId, path: &str) -> StrResult<PathBuf> {
+    if !id.is_detached() {
+        let current = world.source(id).path();
+
+        // Files in a package can only access files in the same package and
+        // resolve absolute paths relative to the package's directory.
+        if let Some(root) =
+            world.packages().and_then(|packages| package_root(packages, current))
+        {
+            let full = match path.strip_prefix('/') {
+                Some(path) => root.join(path),
+                None => current.parent().unwrap_or(&root).join(path),
+            };
+
+            let full = full.normalize();
+            if !full.starts_with(&root) {
+                return Err("cannot access files outside of the package".into());
             }
+
+            return Ok(full);
         }
 
-        Err("cannot access file system from here".into())
+        if let Some(path) = path.strip_prefix('/') {
+            return Ok(world.root().join(path).normalize());
+        }
+
+        if let Some(dir) = current.parent() {
+            return Ok(dir.join(path).normalize());
+        }
     }
+
+    Err("cannot access file system from here".into())
 }
 
 /// A control flow event that occurred during evaluation.
@@ -290,11 +320,13 @@ impl Route {
     }
 }
 
-/// Traces which values existed for the expression at a span.
+/// Traces which values existed for the expression at a span and collects
+/// the warnings that were emitted during compilation.
 #[derive(Default, Clone)]
 pub struct Tracer {
     span: Option<Span>,
     values: Vec<Value>,
+    warnings: Vec<SourceError>,
 }
 
 impl Tracer {
@@ -303,13 +335,18 @@ impl Tracer {
 
     /// Create a new tracer, possibly with a span under inspection.
     pub fn new(span: Option<Span>) -> Self {
-        Self { span, values: vec![] }
+        Self { span, values: vec![], warnings: vec![] }
     }
 
     /// Get the traced values.
     pub fn finish(self) -> Vec<Value> {
         self.values
     }
+
+    /// The warnings emitted so far.
+    pub fn warnings(&self) -> &[SourceError] {
+        &self.warnings
+    }
 }
 
 #[comemo::track]
@@ -329,6 +366,16 @@ impl Tracer {
             self.values.push(v);
         }
     }
+
+    /// Report a warning or hint.
+    ///
+    /// Since layout may run multiple times, the same warning can be emitted
+    /// more than once; duplicates are only reported once.
+    fn warn(&mut self, warning: SourceError) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
 }
 
 /// Evaluate an expression.
@@ -829,6 +876,20 @@ impl Eval for ast::ContentBlock {
     type Output = Content;
 
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        // A set rule at the end of a block has nothing to apply to.
+        let last = self
+            .body()
+            .exprs()
+            .filter(|expr| !matches!(expr, ast::Expr::Space(_) | ast::Expr::Parbreak(_)))
+            .last();
+        if let Some(ast::Expr::Set(set)) = last {
+            vm.vt.tracer.warn(warning!(set.span(), "set rule has no effect"));
+            vm.vt.tracer.warn(SourceError::hint(
+                set.span(),
+                "set rules only apply to content that follows them in the same block",
+            ));
+        }
+
         vm.scopes.enter();
         let content = self.body().eval(vm)?;
         vm.scopes.exit();
@@ -1460,9 +1521,20 @@ fn import(vm: &mut Vm, source: Value, span: Span) -> SourceResult<Module> {
         v => bail!(span, "expected path or module, found {}", v.type_name()),
     };
 
-    // Load the source file.
+    // Find the source file, either within a package or relative to the
+    // current location.
     let world = vm.world();
-    let full = vm.locate(&path).at(span)?;
+    let mut name = None;
+    let full = if path.starts_with('@') {
+        let spec = path.parse::<PackageSpec>().at(span)?;
+        let full = resolve_package(world, &spec).at(span)?;
+        name = Some(spec.name);
+        full
+    } else {
+        vm.locate(&path).at(span)?
+    };
+
+    // Load the source file.
     let id = world.resolve(&full).at(span)?;
 
     // Prevent cyclic importing.
@@ -1473,8 +1545,16 @@ fn import(vm: &mut Vm, source: Value, span: Span) -> SourceResult<Module> {
     // Evaluate the file.
     let source = world.source(id);
     let point = || Tracepoint::Import;
-    eval(world, vm.route, TrackedMut::reborrow_mut(&mut vm.vt.tracer), source)
-        .trace(world, point, span)
+    let module =
+        eval(world, vm.route, TrackedMut::reborrow_mut(&mut vm.vt.tracer), source)
+            .trace(world, point, span)?;
+
+    // A package's module is named after the package rather than its
+    // entrypoint.
+    Ok(match name {
+        Some(name) => module.with_name(name),
+        None => module,
+    })
 }
 
 impl Eval for ast::LoopBreak {
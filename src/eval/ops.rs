@@ -6,7 +6,9 @@ use ecow::eco_format;
 
 use super::{format_str, Regex, Value};
 use crate::diag::StrResult;
-use crate::geom::{Axes, Axis, GenAlign, Length, Numeric, PartialStroke, Rel, Smart};
+use crate::geom::{
+    Axes, Axis, GenAlign, Gradient, Length, Numeric, Paint, PartialStroke, Rel, Smart,
+};
 use Value::*;
 
 /// Bail with a type mismatch error.
@@ -46,6 +48,7 @@ pub fn pos(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(v),
         Relative(v) => Relative(v),
         Fraction(v) => Fraction(v),
+        Duration(v) => Duration(v),
         v => mismatch!("cannot apply '+' to {}", v),
     })
 }
@@ -60,6 +63,7 @@ pub fn neg(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(-v),
         Relative(v) => Relative(-v),
         Fraction(v) => Fraction(-v),
+        Duration(v) => Duration(v.checked_neg()?),
         v => mismatch!("cannot apply '-' to {}", v),
     })
 }
@@ -91,6 +95,11 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Fraction(a), Fraction(b)) => Fraction(a + b),
 
+        (Datetime(a), Duration(b)) | (Duration(b), Datetime(a)) => {
+            Datetime(a.checked_add(b)?)
+        }
+        (Duration(a), Duration(b)) => Duration(a.checked_add(b)?),
+
         (Symbol(a), Symbol(b)) => Str(format_str!("{a}{b}")),
         (Str(a), Str(b)) => Str(a + b),
         (Str(a), Symbol(b)) => Str(format_str!("{a}{b}")),
@@ -108,6 +117,18 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
             Value::dynamic(PartialStroke {
                 paint: Smart::Custom(color.into()),
                 thickness: Smart::Custom(thickness),
+                ..PartialStroke::default()
+            })
+        }
+
+        (Dyn(gradient), Length(thickness)) | (Length(thickness), Dyn(gradient))
+            if gradient.is::<Gradient>() =>
+        {
+            let gradient = gradient.downcast::<Gradient>().unwrap().clone();
+            Value::dynamic(PartialStroke {
+                paint: Smart::Custom(Paint::Gradient(gradient)),
+                thickness: Smart::Custom(thickness),
+                ..PartialStroke::default()
             })
         }
 
@@ -157,6 +178,10 @@ pub fn sub(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Fraction(a), Fraction(b)) => Fraction(a - b),
 
+        (Datetime(a), Duration(b)) => Datetime(a.checked_add(b.checked_neg()?)?),
+        (Datetime(a), Datetime(b)) => Duration(a.checked_sub(b)?),
+        (Duration(a), Duration(b)) => Duration(a.checked_sub(b)?),
+
         (a, b) => mismatch!("cannot subtract {1} from {0}", a, b),
     })
 }
@@ -194,6 +219,9 @@ pub fn mul(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Fraction(a), Float(b)) => Fraction(a * b),
         (Int(a), Fraction(b)) => Fraction(a as f64 * b),
 
+        (Duration(a), Int(b)) => Duration(a.checked_mul(b)?),
+        (Int(a), Duration(b)) => Duration(b.checked_mul(a)?),
+
         (Str(a), Int(b)) => Str(a.repeat(b)?),
         (Int(a), Str(b)) => Str(b.repeat(a)?),
         (Array(a), Int(b)) => Array(a.repeat(b)?),
@@ -337,6 +365,8 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (Relative(a), Relative(b)) => a == b,
         (Fraction(a), Fraction(b)) => a == b,
         (Color(a), Color(b)) => a == b,
+        (Datetime(a), Datetime(b)) => a == b,
+        (Duration(a), Duration(b)) => a == b,
         (Symbol(a), Symbol(b)) => a == b,
         (Str(a), Str(b)) => a == b,
         (Label(a), Label(b)) => a == b,
@@ -371,6 +401,8 @@ pub fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
         (Ratio(a), Ratio(b)) => a.partial_cmp(b),
         (Relative(a), Relative(b)) => a.partial_cmp(b),
         (Fraction(a), Fraction(b)) => a.partial_cmp(b),
+        (Datetime(a), Datetime(b)) => a.partial_cmp(b),
+        (Duration(a), Duration(b)) => a.partial_cmp(b),
         (Str(a), Str(b)) => a.partial_cmp(b),
 
         // Some technically different things should be comparable.
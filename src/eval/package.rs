@@ -0,0 +1,294 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+use comemo::Tracked;
+use ecow::{eco_format, EcoString};
+use serde::{Deserialize, Deserializer};
+use unscanny::Scanner;
+
+use crate::diag::{FileError, StrResult};
+use crate::syntax::is_ident;
+use crate::util::PathExt;
+use crate::World;
+
+/// Identifies a versioned package: `@namespace/name:1.2.0`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PackageSpec {
+    /// The namespace the package lives in.
+    pub namespace: EcoString,
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The package's version.
+    pub version: Version,
+}
+
+impl PackageSpec {
+    /// The directory of this package within a package directory.
+    pub fn dir(&self, packages: &Path) -> PathBuf {
+        packages
+            .join(self.namespace.as_str())
+            .join(self.name.as_str())
+            .join(self.version.to_string())
+    }
+}
+
+impl FromStr for PackageSpec {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut s = Scanner::new(s);
+        if !s.eat_if('@') {
+            return Err("package specification must start with '@'".into());
+        }
+
+        let namespace = s.eat_until('/');
+        if namespace.is_empty() {
+            return Err("package specification is missing namespace".into());
+        } else if !is_ident(namespace) {
+            return Err(eco_format!("`{namespace}` is not a valid package namespace"));
+        }
+
+        s.eat_if('/');
+
+        let name = s.eat_until(':');
+        if name.is_empty() {
+            return Err("package specification is missing name".into());
+        } else if !is_ident(name) {
+            return Err(eco_format!("`{name}` is not a valid package name"));
+        }
+
+        s.eat_if(':');
+
+        let version = s.after();
+        if version.is_empty() {
+            return Err("package specification is missing version".into());
+        }
+
+        Ok(Self {
+            namespace: namespace.into(),
+            name: name.into(),
+            version: version.parse()?,
+        })
+    }
+}
+
+impl Display for PackageSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@{}/{}:{}", self.namespace, self.name, self.version)
+    }
+}
+
+/// A version number: `major.minor.patch`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Version {
+    /// The major version.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch version.
+    pub patch: u32,
+}
+
+impl Version {
+    /// The version of this compiler.
+    pub fn compiler() -> Self {
+        Self {
+            major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+            patch: env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.');
+        let mut next = |kind| {
+            let part = parts
+                .next()
+                .filter(|part| !part.is_empty())
+                .ok_or_else(|| eco_format!("version number is missing {kind} version"))?;
+            part.parse::<u32>()
+                .map_err(|_| eco_format!("`{part}` is not a valid {kind} version"))
+        };
+
+        let major = next("major")?;
+        let minor = next("minor")?;
+        let patch = next("patch")?;
+        if let Some(rest) = parts.next() {
+            return Err(eco_format!(
+                "version number has unexpected fourth component: `{rest}`"
+            ));
+        }
+
+        Ok(Self { major, minor, patch })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = EcoString::deserialize(deserializer)?;
+        string.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The metadata of a package, read from the `typst.toml` file in its
+/// directory.
+///
+/// ```toml
+/// [package]
+/// name = "example"
+/// version = "0.1.0"
+/// entrypoint = "lib.typ"
+/// compiler = "0.1.0"
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+pub struct PackageManifest {
+    /// Details about the package itself.
+    pub package: PackageInfo,
+}
+
+/// The `[package]` table of a package manifest.
+///
+/// Keys other than the ones below are ignored.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+pub struct PackageInfo {
+    /// The package's name.
+    pub name: EcoString,
+    /// The package's version.
+    pub version: Version,
+    /// The path of the file to evaluate when the package is imported,
+    /// relative to the package's directory.
+    pub entrypoint: EcoString,
+    /// The minimum compiler version the package requires.
+    pub compiler: Option<Version>,
+}
+
+impl PackageManifest {
+    /// Parse a manifest from the contents of a `typst.toml` file.
+    pub fn parse(text: &str) -> StrResult<Self> {
+        toml::from_str(text).map_err(|err| err.message().into())
+    }
+
+    /// Ensure that this manifest describes the given package and that the
+    /// package can be used with this compiler.
+    pub fn validate(&self, spec: &PackageSpec) -> StrResult<()> {
+        let info = &self.package;
+        if info.name != spec.name {
+            return Err(eco_format!(
+                "package manifest contains mismatched name `{}`",
+                info.name
+            ));
+        }
+
+        if info.version != spec.version {
+            return Err(eco_format!(
+                "package manifest contains mismatched version {}",
+                info.version
+            ));
+        }
+
+        if let Some(required) = info.compiler {
+            let current = Version::compiler();
+            if current < required {
+                return Err(eco_format!(
+                    "package requires typst {required} or newer \
+                     (current version is {current})"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the entrypoint of a package in the world's package directory.
+pub(super) fn resolve_package(
+    world: Tracked<dyn World>,
+    spec: &PackageSpec,
+) -> StrResult<PathBuf> {
+    let Some(packages) = world.packages() else {
+        return Err("packages are not supported here".into());
+    };
+
+    let dir = spec.dir(packages).normalize();
+    let data = world.file(&dir.join("typst.toml")).map_err(|err| match err {
+        FileError::NotFound(_) => eco_format!("package not found: {spec}"),
+        err => eco_format!("failed to load manifest of {spec} ({err})"),
+    })?;
+
+    let text = std::str::from_utf8(&data)
+        .map_err(|_| eco_format!("manifest of {spec} is not valid utf-8"))?;
+    let manifest = PackageManifest::parse(text)
+        .map_err(|err| eco_format!("failed to parse manifest of {spec} ({err})"))?;
+    manifest.validate(spec)?;
+
+    let entrypoint = dir.join(manifest.package.entrypoint.as_str()).normalize();
+    if !entrypoint.starts_with(&dir) {
+        return Err(eco_format!("entrypoint of {spec} lies outside of the package"));
+    }
+
+    Ok(entrypoint)
+}
+
+/// The directory of the package a file belongs to, if any.
+///
+/// Within a package, this directory serves as the root for absolute paths.
+pub(super) fn package_root(packages: &Path, path: &Path) -> Option<PathBuf> {
+    let packages = packages.normalize();
+    let mut components = path.strip_prefix(&packages).ok()?.components();
+    let mut root = packages;
+    for _ in 0..3 {
+        match components.next()? {
+            Component::Normal(part) => root.push(part),
+            _ => return None,
+        }
+    }
+    Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = PackageManifest::parse(
+            r#"
+            # A package.
+            [package]
+            name = "example"
+            version = "0.1.0"
+            entrypoint = 'src/lib.typ'
+            authors = ["Jane", "John"]
+            license = { name = "MIT" }
+            "#,
+        )
+        .unwrap();
+
+        let info = manifest.package;
+        assert_eq!(info.name.as_str(), "example");
+        assert_eq!(info.version, Version { major: 0, minor: 1, patch: 0 });
+        assert_eq!(info.entrypoint.as_str(), "src/lib.typ");
+        assert_eq!(info.compiler, None);
+    }
+
+    #[test]
+    fn test_parse_manifest_errors() {
+        let missing = PackageManifest::parse("[package]\nname = \"example\"");
+        assert!(missing.unwrap_err().contains("version"));
+
+        let invalid = "[package]\nname = \"x\"\nversion = \"1.x.0\"\nentrypoint = \"a\"";
+        let invalid = PackageManifest::parse(invalid);
+        assert!(invalid.unwrap_err().contains("`x` is not a valid minor version"));
+    }
+}
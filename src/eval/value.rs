@@ -5,11 +5,12 @@ use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use ecow::eco_format;
+use serde::{Serialize, Serializer};
 use siphasher::sip128::{Hasher128, SipHasher};
 
 use super::{
-    cast_to_value, format_str, ops, Args, Array, Cast, CastInfo, Content, Dict, Func,
-    Label, Module, Str, Symbol,
+    cast_to_value, format_str, ops, Args, Array, Cast, CastInfo, Content, Datetime, Dict,
+    Duration, Func, Label, Module, Str, Symbol,
 };
 use crate::diag::StrResult;
 use crate::geom::{Abs, Angle, Color, Em, Fr, Length, Ratio, Rel};
@@ -42,6 +43,10 @@ pub enum Value {
     Fraction(Fr),
     /// A color value: `#f79143ff`.
     Color(Color),
+    /// A calendar date: `datetime(year: 2023, month: 4, day: 12)`.
+    Datetime(Datetime),
+    /// A length of time: `duration(days: 3)`.
+    Duration(Duration),
     /// A symbol: `arrow.l`.
     Symbol(Symbol),
     /// A string: `"string"`.
@@ -101,6 +106,8 @@ impl Value {
             Self::Relative(_) => Rel::<Length>::TYPE_NAME,
             Self::Fraction(_) => Fr::TYPE_NAME,
             Self::Color(_) => Color::TYPE_NAME,
+            Self::Datetime(_) => Datetime::TYPE_NAME,
+            Self::Duration(_) => Duration::TYPE_NAME,
             Self::Symbol(_) => Symbol::TYPE_NAME,
             Self::Str(_) => Str::TYPE_NAME,
             Self::Label(_) => Label::TYPE_NAME,
@@ -127,6 +134,7 @@ impl Value {
             Self::Dict(dict) => dict.at(field).cloned(),
             Self::Content(content) => content.at(field),
             Self::Module(module) => module.get(field).cloned(),
+            Self::Func(func) => func.get(field).cloned(),
             v => Err(eco_format!("cannot access fields on type {}", v.type_name())),
         }
     }
@@ -183,6 +191,8 @@ impl Debug for Value {
             Self::Relative(v) => Debug::fmt(v, f),
             Self::Fraction(v) => Debug::fmt(v, f),
             Self::Color(v) => Debug::fmt(v, f),
+            Self::Datetime(v) => Debug::fmt(v, f),
+            Self::Duration(v) => Debug::fmt(v, f),
             Self::Symbol(v) => Debug::fmt(v, f),
             Self::Str(v) => Debug::fmt(v, f),
             Self::Label(v) => Debug::fmt(v, f),
@@ -225,6 +235,8 @@ impl Hash for Value {
             Self::Relative(v) => v.hash(state),
             Self::Fraction(v) => v.hash(state),
             Self::Color(v) => v.hash(state),
+            Self::Datetime(v) => v.hash(state),
+            Self::Duration(v) => v.hash(state),
             Self::Symbol(v) => v.hash(state),
             Self::Str(v) => v.hash(state),
             Self::Label(v) => v.hash(state),
@@ -240,6 +252,26 @@ impl Hash for Value {
     }
 }
 
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::None => serializer.serialize_none(),
+            Self::Bool(v) => serializer.serialize_bool(*v),
+            Self::Int(v) => serializer.serialize_i64(*v),
+            Self::Float(v) => serializer.serialize_f64(*v),
+            Self::Symbol(v) => serializer.serialize_char(v.get()),
+            Self::Str(v) => serializer.serialize_str(v),
+            Self::Content(v) => v.serialize(serializer),
+            Self::Array(v) => serializer.collect_seq(v.iter()),
+            Self::Dict(v) => {
+                serializer.collect_map(v.iter().map(|(k, v)| (k.as_str(), v)))
+            }
+            // Other values are serialized as their code representation.
+            v => serializer.serialize_str(&eco_format!("{v:?}")),
+        }
+    }
+}
+
 /// A dynamic value.
 #[derive(Clone, Hash)]
 pub struct Dynamic(Arc<dyn Bounds>);
@@ -388,6 +420,8 @@ primitive! { Rel<Length>:  "relative length",
 }
 primitive! { Fr: "fraction", Fraction }
 primitive! { Color: "color", Color }
+primitive! { Datetime: "datetime", Datetime }
+primitive! { Duration: "duration", Duration }
 primitive! { Symbol: "symbol", Symbol }
 primitive! {
     Str: "string",
@@ -432,6 +466,14 @@ mod tests {
         test(Ratio::new(0.3) + Length::from(Abs::cm(2.0)), "30% + 56.69pt");
         test(Fr::one() * 7.55, "7.55fr");
         test(Color::Rgba(RgbaColor::new(1, 1, 1, 0xff)), "rgb(\"#010101\")");
+        test(
+            Datetime::from_ymd(2023, 4, 12).unwrap(),
+            "datetime(year: 2023, month: 4, day: 12)",
+        );
+        test(
+            Duration::from_seconds(90061),
+            "duration(days: 1, hours: 1, minutes: 1, seconds: 1)",
+        );
 
         // Collections.
         test("hello", r#""hello""#);
@@ -445,4 +487,36 @@ mod tests {
         test(dict!["one" => 1], "(one: 1)");
         test(dict!["two" => false, "one" => 1], "(one: 1, two: false)");
     }
+
+    #[track_caller]
+    fn test_json(value: impl Into<Value>, exp: &str) {
+        assert_eq!(serde_json::to_string(&value.into()).unwrap(), exp);
+    }
+
+    #[test]
+    fn test_value_serialize() {
+        // Primitives.
+        test_json(Value::None, "null");
+        test_json(true, "true");
+        test_json(12i64, "12");
+        test_json(3.5, "3.5");
+        test_json("hello", r#""hello""#);
+        test_json(Abs::pt(5.5), r#""5.5pt""#);
+        test_json(Label("intro".into()), r#""<intro>""#);
+
+        // Collections.
+        test_json(array![1, "a"], r#"[1,"a"]"#);
+        test_json(dict!["two" => false, "one" => 1], r#"{"one":1,"two":false}"#);
+
+        // Content.
+        let content = Content::empty().with_field("body", "Intro");
+        test_json(
+            content.clone(),
+            r#"{"func":"sequence","body":"Intro","children":[]}"#,
+        );
+        assert_eq!(
+            serde_yaml::to_string(&Value::Content(content)).unwrap(),
+            "---\nfunc: sequence\nbody: Intro\nchildren: []\n",
+        );
+    }
 }
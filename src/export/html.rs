@@ -0,0 +1,41 @@
+//! Exporting of content into semantic HTML.
+
+use comemo::Track;
+
+use crate::diag::SourceResult;
+use crate::doc::Document;
+use crate::eval::Tracer;
+use crate::model::{Content, Introspector, StabilityProvider, StyleChain, Vt};
+use crate::World;
+
+/// Export content into a standalone HTML document.
+///
+/// Unlike the other exporters, this works on the content tree rather than on
+/// the laid-out frames: Headings, paragraphs, lists, links, tables and figures
+/// map to their HTML counterparts. The typeset `document` is used to resolve
+/// introspections like counters and references. Content without an HTML
+/// equivalent (equations, shapes, images, ...) is laid out and embedded as
+/// inline SVG.
+///
+/// Warnings emitted along the way are collected in the `tracer`.
+///
+/// Returns the HTML source as a string.
+pub fn html(
+    world: &(dyn World + 'static),
+    tracer: &mut Tracer,
+    content: &Content,
+    document: &Document,
+) -> SourceResult<String> {
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+    let introspector = Introspector::new(&document.pages);
+    let mut provider = StabilityProvider::new();
+    let mut vt = Vt {
+        world: world.track(),
+        tracer: tracer.track_mut(),
+        provider: provider.track_mut(),
+        introspector: introspector.track(),
+    };
+
+    (library.items.html)(&mut vt, content, styles)
+}
@@ -5,7 +5,8 @@ use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
 use pdf_writer::{Filter, Finish, Name, Rect, Str};
 use ttf_parser::{name_id, GlyphId, Tag};
 
-use super::{deflate, EmExt, PdfContext, RefExt};
+use super::{deflate, EmExt, PdfContext, PdfStandard, RefExt};
+use crate::font::Font;
 use crate::util::SliceExt;
 
 /// Embed all used fonts into the PDF.
@@ -26,7 +27,15 @@ pub fn write_fonts(ctx: &mut PdfContext) {
             .find_name(name_id::POST_SCRIPT_NAME)
             .unwrap_or_else(|| "unknown".to_string());
 
-        let base_font = eco_format!("ABCDEF+{}", postscript_name);
+        // PDF/A requires fonts to be fully embedded, so we only subset them
+        // otherwise. Even then, the subsetter still reduces the font to the
+        // tables the PDF needs.
+        let subset = ctx.standard != PdfStandard::A2b;
+        let base_font = if subset {
+            eco_format!("ABCDEF+{}", postscript_name)
+        } else {
+            eco_format!("{}", postscript_name)
+        };
         let base_font = Name(base_font.as_bytes());
         let cmap_name = Name(b"Custom");
         let system_info = SystemInfo {
@@ -162,23 +171,133 @@ pub fn write_fonts(ctx: &mut PdfContext) {
             .filter(Filter::FlateDecode);
 
         // Subset and write the font's bytes.
-        let data = font.data();
-        let subsetted = {
-            let glyphs: Vec<_> = glyphs.iter().copied().collect();
-            let profile = subsetter::Profile::pdf(&glyphs);
-            subsetter::subset(data, font.index(), profile)
+        let glyphs: Vec<_> = if subset {
+            glyphs.iter().copied().collect()
+        } else {
+            (0..num_glyphs).collect()
         };
-
-        // Compress and write the font's bytes.
-        let data = subsetted.as_deref().unwrap_or(data);
-        let data = deflate(data);
+        let (data, stream_subtype) = font_file(font, &glyphs);
+        let data = deflate(&data);
         let mut stream = ctx.writer.stream(data_ref, &data);
         stream.filter(Filter::FlateDecode);
 
-        if subtype == CidFontType::Type0 {
-            stream.pair(Name(b"Subtype"), Name(b"CIDFontType0C"));
+        if let Some(stream_subtype) = stream_subtype {
+            stream.pair(Name(b"Subtype"), stream_subtype);
         }
 
         stream.finish();
     }
 }
+
+/// Extract the data to embed for the given glyphs of a font.
+///
+/// TrueType outlines are embedded as a font file of their own. CFF outlines
+/// are embedded as a bare CFF table, CFF2 outlines as an OpenType font. For
+/// those, the subtype of the font file stream is returned alongside the data.
+fn font_file(font: &Font, glyphs: &[u16]) -> (Vec<u8>, Option<Name<'static>>) {
+    let profile = subsetter::Profile::pdf(glyphs);
+    let subsetted = subsetter::subset(font.data(), font.index(), profile).ok();
+    let (data, index) = match &subsetted {
+        Some(data) => (data.as_slice(), 0),
+        None => (font.data().as_slice(), font.index()),
+    };
+
+    let Ok(face) = ttf_parser::RawFace::parse(data, index) else {
+        return (data.to_vec(), None);
+    };
+
+    if let Some(cff) = face.table(Tag::from_bytes(b"CFF ")) {
+        (cff.to_vec(), Some(Name(b"CIDFontType0C")))
+    } else if face.table(Tag::from_bytes(b"CFF2")).is_some() {
+        (data.to_vec(), Some(Name(b"OpenType")))
+    } else {
+        (data.to_vec(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Document, Frame, FrameItem, Glyph, Lang, TextItem};
+    use crate::export::{pdf, PdfStandard};
+    use crate::geom::{Abs, Color, Em, Point, Ratio, Size};
+    use crate::syntax::Span;
+    use crate::util::Buffer;
+
+    fn load(data: &'static [u8]) -> Font {
+        Font::new(Buffer::from_static(data), 0).unwrap()
+    }
+
+    fn cff() -> Font {
+        load(include_bytes!("../../../assets/fonts/NewCM10-Regular.otf"))
+    }
+
+    fn truetype() -> Font {
+        load(include_bytes!("../../../assets/fonts/PTSans-Regular.ttf"))
+    }
+
+    fn document(font: Font) -> Document {
+        let glyph = Glyph {
+            id: font.ttf().glyph_index('A').unwrap().0,
+            x_advance: Em::new(0.5),
+            x_offset: Em::zero(),
+            c: 'A',
+            span: Span::detached(),
+            offset: 0,
+        };
+
+        let text = TextItem {
+            font,
+            size: Abs::pt(10.0),
+            fill: Color::BLACK.into(),
+            lang: Lang::ENGLISH,
+            expansion: Ratio::zero(),
+            glyphs: vec![glyph],
+        };
+
+        let mut page = Frame::new(Size::new(Abs::pt(50.0), Abs::pt(50.0)));
+        page.push(Point::with_y(Abs::pt(20.0)), FrameItem::Text(text));
+        Document { pages: vec![page], ..Default::default() }
+    }
+
+    #[test]
+    fn test_font_file_cff() {
+        let font = cff();
+        let all: Vec<u16> = (0..font.ttf().number_of_glyphs()).collect();
+        let (data, subtype) = font_file(&font, &all);
+
+        // A bare CFF table starts with its major version instead of an
+        // OpenType header.
+        assert_eq!(subtype, Some(Name(b"CIDFontType0C")));
+        assert_eq!(data[0], 1);
+        assert_ne!(&data[..4], b"OTTO");
+    }
+
+    #[test]
+    fn test_font_file_truetype() {
+        let font = truetype();
+        let all: Vec<u16> = (0..font.ttf().number_of_glyphs()).collect();
+        let (data, subtype) = font_file(&font, &all);
+        assert_eq!(subtype, None);
+
+        // All glyphs are kept.
+        let face = ttf_parser::Face::parse(&data, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), font.ttf().number_of_glyphs());
+    }
+
+    #[test]
+    fn test_pdfa_fonts() {
+        let bytes = pdf(&document(cff()), PdfStandard::A2b);
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("/FontFile3"));
+        assert!(text.contains("/Subtype /CIDFontType0C"));
+        assert!(!text.contains("ABCDEF+"));
+
+        let bytes = pdf(&document(truetype()), PdfStandard::A2b);
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("/FontFile2"));
+        assert!(text.contains("/CIDToGIDMap /Identity"));
+        assert!(!text.contains("/FontFile3"));
+        assert!(!text.contains("ABCDEF+"));
+    }
+}
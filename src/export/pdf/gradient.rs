@@ -0,0 +1,361 @@
+use std::fmt::Write;
+
+use pdf_writer::{Content, Finish, Name, PdfWriter, Ref};
+
+use super::{PdfContext, RefExt};
+use crate::geom::{Color, Gradient, GradientKind, Size, Transform};
+
+/// A gradient as it is placed on a page.
+///
+/// For a soft mask, the transform is relative to the coordinate system that
+/// is active when the mask is applied instead.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfGradient {
+    /// The gradient itself.
+    pub gradient: Gradient,
+    /// Maps from the gradient's bounding box into the page's default
+    /// coordinate system.
+    pub transform: Transform,
+    /// The size of the gradient's bounding box.
+    pub size: Size,
+}
+
+/// Write all used gradients as shading patterns and the soft masks of
+/// translucent ones.
+pub fn write_gradients(ctx: &mut PdfContext) {
+    for PdfGradient { gradient, transform, size } in ctx.gradient_map.items() {
+        let pattern_ref = ctx.alloc.bump();
+        let shading_ref = ctx.alloc.bump();
+        ctx.gradient_refs.push(pattern_ref);
+
+        let Transform { sx, ky, kx, sy, tx, ty } = *transform;
+        let mut pattern = ctx.writer.indirect(pattern_ref).dict();
+        pattern.pair(Name(b"Type"), Name(b"Pattern"));
+        pattern.pair(Name(b"PatternType"), 2);
+        pattern.pair(Name(b"Shading"), shading_ref);
+        pattern.insert(Name(b"Matrix")).array().items([
+            sx.get() as f32,
+            ky.get() as f32,
+            kx.get() as f32,
+            sy.get() as f32,
+            tx.to_pt() as f32,
+            ty.to_pt() as f32,
+        ]);
+        pattern.finish();
+
+        let stops = normalized_stops(gradient, |color| {
+            let c = color.to_rgba();
+            vec![c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0]
+        });
+
+        let space = Name(b"DeviceRGB");
+        write_shading(ctx, shading_ref, gradient, *size, space, &stops);
+    }
+
+    for PdfGradient { gradient, transform, size } in ctx.mask_map.items() {
+        let state_ref = ctx.alloc.bump();
+        let group_ref = ctx.alloc.bump();
+        let shading_ref = ctx.alloc.bump();
+        ctx.mask_refs.push(state_ref);
+
+        // The soft mask is drawn in the coordinate system that is active when
+        // the graphics state is set. It covers the same area as the domain of
+        // conic gradients.
+        let (w, h) = (size.x.to_pt() as f32, size.y.to_pt() as f32);
+        let Transform { sx, ky, kx, sy, tx, ty } = *transform;
+        let mut content = Content::new();
+        content.transform([
+            sx.get() as f32,
+            ky.get() as f32,
+            kx.get() as f32,
+            sy.get() as f32,
+            tx.to_pt() as f32,
+            ty.to_pt() as f32,
+        ]);
+        content.shading(Name(b"Sh"));
+        let data = content.finish();
+
+        let (x, y) = (tx.to_pt() as f32, ty.to_pt() as f32);
+        let mut group = ctx.writer.stream(group_ref, &data);
+        group.pair(Name(b"Type"), Name(b"XObject"));
+        group.pair(Name(b"Subtype"), Name(b"Form"));
+        group
+            .insert(Name(b"BBox"))
+            .array()
+            .items([x - w, y - h, x + 2.0 * w, y + 2.0 * h]);
+        let mut attrs = group.insert(Name(b"Group")).dict();
+        attrs.pair(Name(b"S"), Name(b"Transparency"));
+        attrs.pair(Name(b"CS"), Name(b"DeviceGray"));
+        attrs.finish();
+        group
+            .insert(Name(b"Resources"))
+            .dict()
+            .insert(Name(b"Shading"))
+            .dict()
+            .pair(Name(b"Sh"), shading_ref);
+        group.finish();
+
+        let mut state = ctx.writer.indirect(state_ref).dict();
+        state.pair(Name(b"Type"), Name(b"ExtGState"));
+        let mut mask = state.insert(Name(b"SMask")).dict();
+        mask.pair(Name(b"Type"), Name(b"Mask"));
+        mask.pair(Name(b"S"), Name(b"Luminosity"));
+        mask.pair(Name(b"G"), group_ref);
+        mask.finish();
+        state.finish();
+
+        let stops = normalized_stops(gradient, |color| {
+            vec![color.to_rgba().a as f32 / 255.0]
+        });
+
+        let space = Name(b"DeviceGray");
+        write_shading(ctx, shading_ref, gradient, *size, space, &stops);
+    }
+}
+
+/// Write a shading of the gradient laid out in a box of the given size. The
+/// stops must have one component per channel of the color space.
+fn write_shading(
+    ctx: &mut PdfContext,
+    shading_ref: Ref,
+    gradient: &Gradient,
+    size: Size,
+    space: Name,
+    stops: &[(Vec<f32>, f32)],
+) {
+    let function_ref = ctx.alloc.bump();
+    let (w, h) = (size.x.to_pt() as f32, size.y.to_pt() as f32);
+    let mut shading = ctx.writer.indirect(shading_ref).dict();
+    shading.pair(Name(b"ColorSpace"), space);
+    shading.pair(Name(b"Function"), function_ref);
+
+    match gradient.kind() {
+        GradientKind::Linear { angle } => {
+            let (start, end) = Gradient::linear_axis(angle, size);
+            shading.pair(Name(b"ShadingType"), 2);
+            shading.insert(Name(b"Coords")).array().items([
+                start.x.to_pt() as f32,
+                start.y.to_pt() as f32,
+                end.x.to_pt() as f32,
+                end.y.to_pt() as f32,
+            ]);
+            shading.insert(Name(b"Extend")).array().items([true, true]);
+            shading.finish();
+            write_stitching_function(&mut ctx.writer, function_ref, stops);
+        }
+        GradientKind::Radial { center, radius } => {
+            let center = Gradient::center_in(center, size);
+            let (x, y) = (center.x.to_pt() as f32, center.y.to_pt() as f32);
+            let r = Gradient::radius_in(radius, size).to_pt() as f32;
+            shading.pair(Name(b"ShadingType"), 3);
+            shading.insert(Name(b"Coords")).array().items([x, y, 0.0, x, y, r]);
+            shading.insert(Name(b"Extend")).array().items([true, true]);
+            shading.finish();
+            write_stitching_function(&mut ctx.writer, function_ref, stops);
+        }
+        GradientKind::Conic { center, angle } => {
+            // The domain extends beyond the bounding box so that glyphs and
+            // strokes poking out of it are still painted.
+            let domain = [-w, 2.0 * w, -h, 2.0 * h];
+            let center = Gradient::center_in(center, size);
+            let (x, y) = (center.x.to_pt() as f32, center.y.to_pt() as f32);
+            let start = angle.to_deg().rem_euclid(360.0) as f32;
+            shading.pair(Name(b"ShadingType"), 1);
+            shading.insert(Name(b"Domain")).array().items(domain);
+            shading.finish();
+
+            let code = conic_function(stops, x, y, start);
+            let range = stops[0].0.iter().flat_map(|_| [0.0_f32, 1.0]);
+            let mut function = ctx.writer.stream(function_ref, code.as_bytes());
+            function.pair(Name(b"FunctionType"), 4);
+            function.insert(Name(b"Domain")).array().items(domain);
+            function.insert(Name(b"Range")).array().items(range);
+            function.finish();
+        }
+    }
+}
+
+/// The stops of a gradient with the components of their colors, extended so
+/// that they cover offsets from zero to one.
+fn normalized_stops(
+    gradient: &Gradient,
+    components: impl Fn(Color) -> Vec<f32>,
+) -> Vec<(Vec<f32>, f32)> {
+    let mut stops: Vec<_> = gradient
+        .stops()
+        .iter()
+        .map(|&(color, offset)| (components(color), offset.get() as f32))
+        .collect();
+
+    if let Some((first, offset)) = stops.first().cloned() {
+        if offset > 0.0 {
+            stops.insert(0, (first, 0.0));
+        }
+    }
+
+    if let Some((last, offset)) = stops.last().cloned() {
+        if offset < 1.0 {
+            stops.push((last, 1.0));
+        }
+    }
+
+    stops
+}
+
+/// The pairs of adjacent stops that span a non-empty range of offsets.
+///
+/// Two stops at the same offset form a hard stop. The empty segment between
+/// them is dropped, so that the color jumps at the offset. Since the stops are
+/// normalized, at least one segment remains.
+fn segments(stops: &[(Vec<f32>, f32)]) -> Vec<&[(Vec<f32>, f32)]> {
+    stops.windows(2).filter(|window| window[1].1 > window[0].1).collect()
+}
+
+/// Write a function that linearly interpolates between the stops.
+fn write_stitching_function(writer: &mut PdfWriter, id: Ref, stops: &[(Vec<f32>, f32)]) {
+    let segments = segments(stops);
+    let mut function = writer.indirect(id).dict();
+    function.pair(Name(b"FunctionType"), 3);
+    function.insert(Name(b"Domain")).array().items([0.0_f32, 1.0]);
+
+    let mut functions = function.insert(Name(b"Functions")).array();
+    for window in &segments {
+        let mut segment = functions.push().dict();
+        segment.pair(Name(b"FunctionType"), 2);
+        segment.insert(Name(b"Domain")).array().items([0.0_f32, 1.0]);
+        segment.insert(Name(b"C0")).array().items(window[0].0.iter().copied());
+        segment.insert(Name(b"C1")).array().items(window[1].0.iter().copied());
+        segment.pair(Name(b"N"), 1.0_f32);
+    }
+    functions.finish();
+
+    // The bounds are strictly increasing because empty segments are dropped.
+    function
+        .insert(Name(b"Bounds"))
+        .array()
+        .items(segments.iter().skip(1).map(|window| window[0].1));
+    function
+        .insert(Name(b"Encode"))
+        .array()
+        .items(segments.iter().flat_map(|_| [0.0_f32, 1.0]));
+    function.finish();
+}
+
+/// Create the PostScript code of a function that maps from a point to the
+/// color of a conic gradient centered at `(x, y)` whose first stop lies at
+/// `start` degrees.
+fn conic_function(stops: &[(Vec<f32>, f32)], x: f32, y: f32, start: f32) -> String {
+    // Compute the position along the gradient: The clockwise angle from the
+    // first stop as a fraction of the full circle.
+    let mut code = format!(
+        "{{ {y} sub exch {x} sub atan {start} sub dup 0 lt {{ 360 add }} if 360 div "
+    );
+
+    // Find the segment the position lies in and interpolate between its
+    // stops.
+    let segments = segments(stops);
+    for (i, window) in segments.iter().enumerate() {
+        let (c0, t0) = (&window[0].0, window[0].1);
+        let (c1, t1) = (&window[1].0, window[1].1);
+        let last = i + 1 == segments.len();
+        if !last {
+            write!(code, "dup {t1} le {{ ").unwrap();
+        }
+
+        // Keep the position on top of the stack until the last component is
+        // computed.
+        write!(code, "{t0} sub {} div ", t1 - t0).unwrap();
+        for (k, (a, b)) in c0.iter().zip(c1).enumerate() {
+            if k + 1 < c0.len() {
+                write!(code, "dup {} mul {a} add exch ", b - a).unwrap();
+            } else {
+                write!(code, "{} mul {a} add ", b - a).unwrap();
+            }
+        }
+
+        if !last {
+            code.push_str("} { ");
+        }
+    }
+
+    for _ in 1..segments.len() {
+        code.push_str("} ifelse ");
+    }
+
+    code.push('}');
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Document, Frame, FrameItem};
+    use crate::export::{pdf, PdfStandard};
+    use crate::geom::{Abs, Angle, Geometry, Paint, Point, Ratio, RgbaColor, Smart};
+    use crate::syntax::Span;
+
+    fn stops(offsets: &[f32]) -> Vec<(Vec<f32>, f32)> {
+        offsets.iter().enumerate().map(|(i, &t)| (vec![i as f32], t)).collect()
+    }
+
+    fn linear(stops: Vec<(Color, Ratio)>) -> Gradient {
+        let kind = GradientKind::Linear { angle: Angle::zero() };
+        Gradient::new(kind, stops, Smart::Auto)
+    }
+
+    fn export(gradient: Gradient) -> String {
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let mut page = Frame::new(size);
+        let shape = Geometry::Rect(size).filled(Paint::Gradient(gradient));
+        page.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        let document = Document { pages: vec![page], ..Default::default() };
+        let bytes = pdf(&document, PdfStandard::V1_7);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    #[test]
+    fn test_gradient_hard_stop_segments() {
+        let stops = stops(&[0.0, 0.5, 0.5, 1.0]);
+        let segments = segments(&stops);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0][1].1, 0.5);
+        assert_eq!(segments[1][0].1, 0.5);
+
+        let mut writer = PdfWriter::new();
+        write_stitching_function(&mut writer, Ref::new(1), &stops);
+        let text = String::from_utf8_lossy(&writer.finish()).into_owned();
+        assert!(text.contains("/Bounds [0.5]"));
+        assert!(text.contains("/Encode [0 1 0 1]"));
+    }
+
+    #[test]
+    fn test_gradient_conic_function_segments() {
+        let code = conic_function(&stops(&[0.0, 0.5, 0.5, 1.0]), 1.0, 2.0, 0.0);
+        assert!(code.starts_with("{ 2 sub exch 1 sub atan"));
+        assert_eq!(code.matches("ifelse").count(), 1);
+        assert!(code.ends_with('}'));
+    }
+
+    #[test]
+    fn test_gradient_pdf_shading() {
+        let text = export(linear(vec![
+            (Color::RED, Ratio::zero()),
+            (Color::BLUE, Ratio::one()),
+        ]));
+        assert!(text.contains("/PatternType 2"));
+        assert!(text.contains("/ShadingType 2"));
+        assert!(!text.contains("/SMask"));
+    }
+
+    #[test]
+    fn test_gradient_pdf_soft_mask() {
+        let transparent = RgbaColor::new(0x00, 0x74, 0xD9, 0x00);
+        let text = export(linear(vec![
+            (Color::BLUE, Ratio::zero()),
+            (transparent.into(), Ratio::one()),
+        ]));
+        assert!(text.contains("/SMask"));
+        assert!(text.contains("/S /Luminosity"));
+        assert!(text.contains("/ShadingType 2"));
+    }
+}
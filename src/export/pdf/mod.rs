@@ -1,39 +1,65 @@
 //! Exporting into PDF documents.
 
 mod font;
+mod gradient;
 mod image;
 mod outline;
 mod page;
+mod structure;
 
 use std::cmp::Eq;
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 
 use pdf_writer::types::Direction;
-use pdf_writer::{Finish, Name, PdfWriter, Ref, TextStr};
+use pdf_writer::{Filter, Finish, Name, PdfWriter, Ref, TextStr};
 use xmp_writer::{LangId, RenditionClass, XmpWriter};
 
+use self::gradient::PdfGradient;
 use self::outline::HeadingNode;
 use self::page::Page;
+use self::structure::StructBuilder;
 use crate::doc::{Document, Lang};
 use crate::font::Font;
 use crate::geom::{Abs, Dir, Em};
 use crate::image::Image;
 use crate::model::Introspector;
+use crate::util::hash128;
 
 /// Export a document into a PDF file.
 ///
+/// The PDF is tagged: Headings, paragraphs, lists, tables, figures, equations
+/// and links are written into a logical structure tree so that assistive
+/// technology can read the document in order.
+///
+/// With [`PdfStandard::A2b`], the file additionally conforms to PDF/A-2b for
+/// long-term archiving.
+///
 /// Returns the raw bytes making up the PDF file.
-pub fn pdf(document: &Document) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
+pub fn pdf(document: &Document, standard: PdfStandard) -> Vec<u8> {
+    let mut ctx = PdfContext::new(document, standard);
     page::construct_pages(&mut ctx, &document.pages);
     font::write_fonts(&mut ctx);
     image::write_images(&mut ctx);
+    gradient::write_gradients(&mut ctx);
     page::write_page_tree(&mut ctx);
     write_catalog(&mut ctx);
     ctx.writer.finish()
 }
 
+/// A standard the exported PDF conforms to.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PdfStandard {
+    /// Plain PDF 1.7.
+    #[default]
+    V1_7,
+    /// PDF/A-2b: Self-contained and device-independent for archiving.
+    A2b,
+}
+
+/// The ICC profile embedded as the output intent of PDF/A files.
+const SRGB_ICC: &[u8] = include_bytes!("icc/sRGB-v2.icc");
+
 /// Identifies the color space definitions.
 const SRGB: Name<'static> = Name(b"srgb");
 const D65_GRAY: Name<'static> = Name(b"d65gray");
@@ -41,6 +67,7 @@ const D65_GRAY: Name<'static> = Name(b"d65gray");
 /// Context for exporting a whole PDF document.
 pub struct PdfContext<'a> {
     document: &'a Document,
+    standard: PdfStandard,
     introspector: Introspector,
     writer: PdfWriter,
     pages: Vec<Page>,
@@ -49,20 +76,26 @@ pub struct PdfContext<'a> {
     page_tree_ref: Ref,
     font_refs: Vec<Ref>,
     image_refs: Vec<Ref>,
+    gradient_refs: Vec<Ref>,
+    mask_refs: Vec<Ref>,
     page_refs: Vec<Ref>,
     font_map: Remapper<Font>,
     image_map: Remapper<Image>,
+    gradient_map: Remapper<PdfGradient>,
+    mask_map: Remapper<PdfGradient>,
     glyph_sets: HashMap<Font, HashSet<u16>>,
     languages: HashMap<Lang, usize>,
     heading_tree: Vec<HeadingNode>,
+    structure: StructBuilder,
 }
 
 impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+    fn new(document: &'a Document, standard: PdfStandard) -> Self {
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
         Self {
             document,
+            standard,
             introspector: Introspector::new(&document.pages),
             writer: PdfWriter::new(),
             pages: vec![],
@@ -72,11 +105,16 @@ impl<'a> PdfContext<'a> {
             page_refs: vec![],
             font_refs: vec![],
             image_refs: vec![],
+            gradient_refs: vec![],
+            mask_refs: vec![],
             font_map: Remapper::new(),
             image_map: Remapper::new(),
+            gradient_map: Remapper::new(),
+            mask_map: Remapper::new(),
             glyph_sets: HashMap::new(),
             languages: HashMap::new(),
             heading_tree: vec![],
+            structure: StructBuilder::new(),
         }
     }
 }
@@ -112,7 +150,7 @@ fn write_catalog(ctx: &mut PdfContext) {
         .max_by_key(|(&lang, &count)| (count, lang))
         .map(|(&k, _)| k);
 
-    let dir = if lang.map(Lang::dir) == Some(Dir::RTL) {
+    let dir = if ctx.document.vertical || lang.map(Lang::dir) == Some(Dir::RTL) {
         Direction::R2L
     } else {
         Direction::L2R
@@ -127,9 +165,15 @@ fn write_catalog(ctx: &mut PdfContext) {
     }
 
     let authors = &ctx.document.author;
+    let joined = authors.join(", ");
     if !authors.is_empty() {
-        info.author(TextStr(&authors.join(", ")));
-        xmp.creator(authors.iter().map(|s| s.as_str()));
+        info.author(TextStr(&joined));
+        if ctx.standard == PdfStandard::A2b {
+            // PDF/A requires the metadata to match the info dictionary.
+            xmp.creator([joined.as_str()]);
+        } else {
+            xmp.creator(authors.iter().map(|s| s.as_str()));
+        }
     }
     info.creator(TextStr("Typst"));
     info.finish();
@@ -139,6 +183,10 @@ fn write_catalog(ctx: &mut PdfContext) {
     xmp.language(ctx.languages.keys().map(|lang| LangId(lang.as_str())));
     xmp.rendition_class(RenditionClass::Proof);
     xmp.pdf_version("1.7");
+    if ctx.standard == PdfStandard::A2b {
+        xmp.pdfa_part(2);
+        xmp.pdfa_conformance("B");
+    }
 
     let xmp_buf = xmp.finish(None);
     let meta_ref = ctx.alloc.bump();
@@ -147,11 +195,27 @@ fn write_catalog(ctx: &mut PdfContext) {
     meta_stream.pair(Name(b"Subtype"), Name(b"XML"));
     meta_stream.finish();
 
+    // Write the logical structure for accessibility.
+    let struct_tree_ref = structure::write_struct_tree(ctx);
+
+    // Write the color profile PDF/A output intents refer to.
+    let icc_ref = (ctx.standard == PdfStandard::A2b).then(|| {
+        let icc_ref = ctx.alloc.bump();
+        let data = deflate(SRGB_ICC);
+        let mut stream = ctx.writer.stream(icc_ref, &data);
+        stream.filter(Filter::FlateDecode);
+        stream.pair(Name(b"N"), 3);
+        stream.finish();
+        icc_ref
+    });
+
     // Write the document catalog.
     let mut catalog = ctx.writer.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
     catalog.viewer_preferences().direction(dir);
     catalog.pair(Name(b"Metadata"), meta_ref);
+    catalog.pair(Name(b"StructTreeRoot"), struct_tree_ref);
+    catalog.insert(Name(b"MarkInfo")).dict().pair(Name(b"Marked"), true);
 
     if let Some(outline_root_id) = outline_root_id {
         catalog.outlines(outline_root_id);
@@ -160,6 +224,24 @@ fn write_catalog(ctx: &mut PdfContext) {
     if let Some(lang) = lang {
         catalog.lang(TextStr(lang.as_str()));
     }
+
+    if let Some(icc_ref) = icc_ref {
+        let mut intents = catalog.insert(Name(b"OutputIntents")).array();
+        let mut intent = intents.push().dict();
+        intent.pair(Name(b"Type"), Name(b"OutputIntent"));
+        intent.pair(Name(b"S"), Name(b"GTS_PDFA1"));
+        intent.pair(Name(b"OutputConditionIdentifier"), TextStr("sRGB"));
+        intent.pair(Name(b"Info"), TextStr("sRGB IEC61966-2.1"));
+        intent.pair(Name(b"DestOutputProfile"), icc_ref);
+    }
+
+    catalog.finish();
+
+    // PDF/A requires a file identifier in the trailer.
+    if ctx.standard == PdfStandard::A2b {
+        let id = hash128(ctx.document).to_be_bytes().to_vec();
+        ctx.writer.set_file_id((id.clone(), id));
+    }
 }
 
 /// Compress data with the DEFLATE algorithm.
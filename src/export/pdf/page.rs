@@ -1,14 +1,18 @@
-use ecow::eco_format;
-use pdf_writer::types::{ActionType, AnnotationType, ColorSpaceOperand};
-use pdf_writer::writers::ColorSpace;
+use ecow::{eco_format, EcoString};
+use pdf_writer::types::{
+    ActionType, AnnotationFlags, AnnotationType, ColorSpaceOperand, LineCapStyle,
+    LineJoinStyle,
+};
+use pdf_writer::writers::{Annotation, ColorSpace};
 use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str};
 
-use super::{deflate, AbsExt, EmExt, PdfContext, RefExt, D65_GRAY, SRGB};
-use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
+use super::gradient::PdfGradient;
+use super::{deflate, AbsExt, EmExt, PdfContext, PdfStandard, RefExt, D65_GRAY, SRGB};
+use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, Tag, TextItem};
 use crate::font::Font;
 use crate::geom::{
-    self, Abs, Color, Em, Geometry, Numeric, Paint, Point, Ratio, Shape, Size, Stroke,
-    Transform,
+    self, Abs, Color, Em, Geometry, Gradient, LineCap, LineJoin, Numeric, Paint, Point,
+    Ratio, Relative, Shape, Size, Stroke, Transform,
 };
 use crate::image::Image;
 
@@ -24,6 +28,7 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
     let page_ref = ctx.alloc.bump();
     ctx.page_refs.push(page_ref);
     ctx.page_heights.push(frame.height().to_f32());
+    ctx.structure.start_page();
 
     let mut ctx = PageContext {
         parent: ctx,
@@ -33,12 +38,15 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
         saves: vec![],
         bottom: 0.0,
         links: vec![],
+        tag: 0,
+        artifact: false,
     };
 
     let size = frame.size();
 
     // Make the coordinate system start at the top-left.
     ctx.bottom = size.y.to_f32();
+    ctx.state.size = size;
     ctx.transform(Transform {
         sx: Ratio::one(),
         ky: Ratio::zero(),
@@ -63,8 +71,8 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
 
 /// Write the page tree.
 pub fn write_page_tree(ctx: &mut PdfContext) {
-    for page in std::mem::take(&mut ctx.pages).into_iter() {
-        write_page(ctx, page);
+    for (i, page) in std::mem::take(&mut ctx.pages).into_iter().enumerate() {
+        write_page(ctx, i, page);
     }
 
     let mut pages = ctx.writer.pages(ctx.page_tree_ref);
@@ -93,12 +101,28 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
     }
 
     images.finish();
+
+    let mut patterns = resources.insert(Name(b"Pattern")).dict();
+    for (gradient_ref, gr) in ctx.gradient_map.pdf_indices(&ctx.gradient_refs) {
+        let name = eco_format!("Gr{}", gr);
+        patterns.pair(Name(name.as_bytes()), gradient_ref);
+    }
+
+    patterns.finish();
+
+    let mut states = resources.insert(Name(b"ExtGState")).dict();
+    for (state_ref, gs) in ctx.mask_map.pdf_indices(&ctx.mask_refs) {
+        let name = eco_format!("Gs{}", gs);
+        states.pair(Name(name.as_bytes()), state_ref);
+    }
+
+    states.finish();
     resources.finish();
     pages.finish();
 }
 
 /// Write a page tree node.
-fn write_page(ctx: &mut PdfContext, page: Page) {
+fn write_page(ctx: &mut PdfContext, index: usize, page: Page) {
     let content_id = ctx.alloc.bump();
 
     let mut page_writer = ctx.writer.page(page.id);
@@ -108,12 +132,30 @@ fn write_page(ctx: &mut PdfContext, page: Page) {
     let h = page.size.y.to_f32();
     page_writer.media_box(Rect::new(0.0, 0.0, w, h));
     page_writer.contents(content_id);
+    page_writer.pair(Name(b"StructParents"), index as i32);
+
+    let annot_refs: Vec<Ref> = page.links.iter().map(|_| ctx.alloc.bump()).collect();
+    if !annot_refs.is_empty() {
+        page_writer
+            .insert(Name(b"Annots"))
+            .array()
+            .items(annot_refs.iter().copied());
+    }
+
+    page_writer.finish();
 
-    let mut annotations = page_writer.annotations();
-    for (dest, rect) in page.links {
-        let mut annotation = annotations.push();
+    for ((dest, rect, node), annot_ref) in page.links.into_iter().zip(annot_refs) {
+        // Links are part of the structure tree through an object reference
+        // from their link element and the annotation's parent tree key.
+        let key = node.map(|node| ctx.structure.annotate(node, page.id, annot_ref));
+
+        let mut annotation = ctx.writer.indirect(annot_ref).start::<Annotation>();
         annotation.subtype(AnnotationType::Link).rect(rect);
         annotation.border(0.0, 0.0, 0.0, None);
+        annotation.flags(AnnotationFlags::PRINT);
+        if let Some(key) = key {
+            annotation.pair(Name(b"StructParent"), key);
+        }
 
         let pos = match dest {
             Destination::Url(uri) => {
@@ -139,9 +181,6 @@ fn write_page(ctx: &mut PdfContext, page: Page) {
         }
     }
 
-    annotations.finish();
-    page_writer.finish();
-
     let data = page.content.finish();
     let data = deflate(&data);
     ctx.writer.stream(content_id, &data).filter(Filter::FlateDecode);
@@ -155,8 +194,9 @@ pub struct Page {
     pub size: Size,
     /// The page's content stream.
     pub content: Content,
-    /// Links in the PDF coordinate system.
-    pub links: Vec<(Destination, Rect)>,
+    /// Links in the PDF coordinate system and the structure nodes they belong
+    /// to.
+    pub links: Vec<(Destination, Rect, Option<usize>)>,
 }
 
 /// An exporter for the contents of a single PDF page.
@@ -167,14 +207,21 @@ struct PageContext<'a, 'b> {
     state: State,
     saves: Vec<State>,
     bottom: f32,
-    links: Vec<(Destination, Rect)>,
+    links: Vec<(Destination, Rect, Option<usize>)>,
+    /// The structure node that content is currently attributed to.
+    tag: usize,
+    /// Whether the current content only repeats other content.
+    artifact: bool,
 }
 
 /// A simulated graphics state used to deduplicate graphics state changes and
-/// keep track of the current transformation matrix for link annotations.
+/// keep track of the current transformation matrix for link annotations and
+/// gradients.
 #[derive(Debug, Default, Clone)]
 struct State {
     transform: Transform,
+    /// The size of the innermost frame, which parent-relative gradients fill.
+    size: Size,
     font: Option<(Font, Abs)>,
     fill: Option<Paint>,
     fill_space: Option<Name<'static>>,
@@ -215,11 +262,24 @@ impl PageContext<'_, '_> {
         }
     }
 
-    fn set_fill(&mut self, fill: &Paint) {
+    /// Set the fill paint. The position and size describe the bounding box of
+    /// what is painted, gradients may be laid out relative to it.
+    fn set_fill(&mut self, fill: &Paint, on_text: bool, pos: Point, size: Size) {
+        let color = match fill {
+            Paint::Solid(color) => *color,
+            Paint::Gradient(gradient) => {
+                let name = self.gradient(gradient, on_text, pos, size);
+                self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+                self.content.set_fill_pattern(None, Name(name.as_bytes()));
+                self.state.fill = None;
+                self.state.fill_space = None;
+                return;
+            }
+        };
+
         if self.state.fill.as_ref() != Some(fill) {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = fill;
-            match color {
+            match self.conform(color) {
                 Color::Luma(c) => {
                     self.set_fill_color_space(D65_GRAY);
                     self.content.set_fill_gray(f(c.0));
@@ -237,6 +297,72 @@ impl PageContext<'_, '_> {
         }
     }
 
+    /// Register a gradient placed within the given bounding box and return
+    /// its resource name.
+    fn gradient(
+        &mut self,
+        gradient: &Gradient,
+        on_text: bool,
+        pos: Point,
+        size: Size,
+    ) -> EcoString {
+        let (pos, size) = match gradient.relative_for(on_text) {
+            Relative::Self_ => (pos, size),
+            Relative::Parent => (Point::zero(), self.state.size),
+        };
+
+        let translation = Transform::translate(pos.x, pos.y);
+        let placed = PdfGradient {
+            gradient: gradient.clone(),
+            transform: self.state.transform.pre_concat(translation),
+            size,
+        };
+
+        self.parent.gradient_map.insert(placed.clone());
+        eco_format!("Gr{}", self.parent.gradient_map.map(placed))
+    }
+
+    /// Register the soft mask for a paint with translucent gradient stops and
+    /// return its graphics state's resource name.
+    fn soft_mask(
+        &mut self,
+        paint: &Paint,
+        on_text: bool,
+        pos: Point,
+        size: Size,
+    ) -> Option<EcoString> {
+        let Paint::Gradient(gradient) = paint else { return None };
+        if gradient.stops().iter().all(|(color, _)| color.to_rgba().a == u8::MAX) {
+            return None;
+        }
+
+        let (pos, size) = match gradient.relative_for(on_text) {
+            Relative::Self_ => (pos, size),
+            Relative::Parent => (Point::zero(), self.state.size),
+        };
+
+        let placed = PdfGradient {
+            gradient: gradient.clone(),
+            transform: Transform::translate(pos.x, pos.y),
+            size,
+        };
+
+        self.parent.mask_map.insert(placed.clone());
+        Some(eco_format!("Gs{}", self.parent.mask_map.map(placed)))
+    }
+
+    /// Replace colors that the PDF standard doesn't allow.
+    fn conform(&self, color: Color) -> Color {
+        match color {
+            // PDF/A forbids device-dependent CMYK colors with our sRGB output
+            // intent.
+            Color::Cmyk(cmyk) if self.parent.standard == PdfStandard::A2b => {
+                Color::Rgba(cmyk.to_rgba())
+            }
+            color => color,
+        }
+    }
+
     fn set_fill_color_space(&mut self, space: Name<'static>) {
         if self.state.fill_space != Some(space) {
             self.content.set_fill_color_space(ColorSpaceOperand::Named(space));
@@ -248,11 +374,25 @@ impl PageContext<'_, '_> {
         self.state.fill_space = None;
     }
 
-    fn set_stroke(&mut self, stroke: &Stroke) {
+    /// Set the stroke. The position and size describe the bounding box of
+    /// what is stroked, gradients may be laid out relative to it.
+    fn set_stroke(&mut self, stroke: &Stroke, pos: Point, size: Size) {
+        let color = match &stroke.paint {
+            Paint::Solid(color) => *color,
+            Paint::Gradient(gradient) => {
+                let name = self.gradient(gradient, false, pos, size);
+                self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+                self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+                self.set_line_style(stroke);
+                self.state.stroke = None;
+                self.state.stroke_space = None;
+                return;
+            }
+        };
+
         if self.state.stroke.as_ref() != Some(stroke) {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = stroke.paint;
-            match color {
+            match self.conform(color) {
                 Color::Luma(c) => {
                     self.set_stroke_color_space(D65_GRAY);
                     self.content.set_stroke_gray(f(c.0));
@@ -267,11 +407,35 @@ impl PageContext<'_, '_> {
                 }
             }
 
-            self.content.set_line_width(stroke.thickness.to_f32());
+            self.set_line_style(stroke);
             self.state.stroke = Some(stroke.clone());
         }
     }
 
+    /// Write everything about a stroke except for its paint.
+    fn set_line_style(&mut self, stroke: &Stroke) {
+        self.content.set_line_width(stroke.thickness.to_f32());
+        self.content.set_line_cap(match stroke.line_cap {
+            LineCap::Butt => LineCapStyle::ButtCap,
+            LineCap::Round => LineCapStyle::RoundCap,
+            LineCap::Square => LineCapStyle::ProjectingSquareCap,
+        });
+        self.content.set_line_join(match stroke.line_join {
+            LineJoin::Miter => LineJoinStyle::MiterJoin,
+            LineJoin::Round => LineJoinStyle::RoundJoin,
+            LineJoin::Bevel => LineJoinStyle::BevelJoin,
+        });
+        self.content.set_miter_limit(stroke.miter_limit.0 as f32);
+
+        match &stroke.dash_pattern {
+            Some(pattern) => self.content.set_dash_pattern(
+                pattern.array.iter().map(|l| l.to_f32()),
+                pattern.phase.to_f32(),
+            ),
+            None => self.content.set_dash_pattern([], 0.0),
+        };
+    }
+
     fn set_stroke_color_space(&mut self, space: Name<'static>) {
         if self.state.stroke_space != Some(space) {
             self.content.set_stroke_color_space(ColorSpaceOperand::Named(space));
@@ -282,25 +446,94 @@ impl PageContext<'_, '_> {
     fn reset_stroke_color_space(&mut self) {
         self.state.stroke_space = None;
     }
+
+    /// Start a marked-content sequence for content of the current structure
+    /// node. Decorative graphics are marked as artifacts instead.
+    fn begin_tagged(&mut self, graphics: bool) {
+        let role = self.parent.structure.nodes[self.tag].role;
+        if self.artifact || (graphics && !role.is_graphical()) {
+            self.content.begin_marked_content(Name(b"Artifact"));
+        } else {
+            let mcid = self.parent.structure.mark(self.tag, self.page_ref);
+            self.content
+                .begin_marked_content_with_properties(role.name())
+                .properties()
+                .identify(mcid);
+        }
+    }
+
+    /// End the current marked-content sequence.
+    fn end_tagged(&mut self) {
+        self.content.end_marked_content();
+    }
 }
 
 /// Encode a frame into the content stream.
 fn write_frame(ctx: &mut PageContext, frame: &Frame) {
+    // Metadata is prepended to the content it belongs to and spans that
+    // content's area. The structure node it enters thus applies to the items
+    // that follow it within that area.
+    let outer = ctx.tag;
+    let mut scopes: Vec<(usize, Point, Size)> = vec![];
+
     for &(pos, ref item) in frame.items() {
         let x = pos.x.to_f32();
         let y = pos.y.to_f32();
+
+        // The position of a text run is on its baseline, so we probe within
+        // the glyphs instead.
+        let probe = match item {
+            FrameItem::Text(text) => pos - Point::with_y(text.size / 2.0),
+            _ => pos,
+        };
+
+        while let Some(&(_, origin, size)) = scopes.last() {
+            if within(probe, origin, size) {
+                break;
+            }
+            scopes.pop();
+        }
+        ctx.tag = scopes.last().map_or(outer, |&(node, ..)| node);
+
         match item {
             FrameItem::Group(group) => write_group(ctx, pos, group),
-            FrameItem::Text(text) => write_text(ctx, x, y, text),
-            FrameItem::Shape(shape, _) => write_shape(ctx, x, y, shape),
-            FrameItem::Image(image, size, _) => write_image(ctx, x, y, image, *size),
-            FrameItem::Meta(meta, size) => match meta {
-                Meta::Link(dest) => write_link(ctx, pos, dest, *size),
-                Meta::Elem(_) => {}
-                Meta::Hide => {}
-            },
+            FrameItem::Text(text) => {
+                ctx.begin_tagged(false);
+                write_text(ctx, x, y, text);
+                ctx.end_tagged();
+            }
+            FrameItem::Shape(shape, _) => {
+                ctx.begin_tagged(true);
+                write_shape(ctx, x, y, shape);
+                ctx.end_tagged();
+            }
+            FrameItem::Image(image, size, _) => {
+                ctx.begin_tagged(false);
+                write_image(ctx, x, y, image, *size);
+                ctx.end_tagged();
+            }
+            FrameItem::Meta(meta, size) => {
+                if !ctx.artifact {
+                    ctx.tag = ctx.parent.structure.enter(meta, ctx.tag);
+                    scopes.push((ctx.tag, pos, *size));
+                }
+                if let Meta::Link(dest) = meta {
+                    write_link(ctx, pos, dest, *size);
+                }
+            }
         }
     }
+
+    ctx.tag = outer;
+}
+
+/// Whether a position lies in the area starting at the origin.
+fn within(pos: Point, origin: Point, size: Size) -> bool {
+    let end = origin + size.to_point();
+    pos.x.fits(origin.x)
+        && pos.y.fits(origin.y)
+        && !pos.x.fits(end.x)
+        && !pos.y.fits(end.y)
 }
 
 /// Encode a group into the content stream.
@@ -309,6 +542,7 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
 
     ctx.save_state();
     ctx.transform(translation.pre_concat(group.transform));
+    ctx.state.size = group.frame.size();
 
     if group.clips {
         let size = group.frame.size();
@@ -322,7 +556,19 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
         ctx.content.end_path();
     }
 
+    // Tagged groups determine the structure node of their contents.
+    let (tag, artifact) = (ctx.tag, ctx.artifact);
+    match group.tag {
+        Some(Tag::Artifact) => ctx.artifact = true,
+        Some(tag) if !ctx.artifact => {
+            ctx.tag = ctx.parent.structure.enter_tag(tag, ctx.tag);
+        }
+        _ => {}
+    }
+
     write_frame(ctx, &group.frame);
+    ctx.tag = tag;
+    ctx.artifact = artifact;
     ctx.restore_state();
 }
 
@@ -335,12 +581,22 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
         .or_default()
         .extend(text.glyphs.iter().map(|g| g.id));
 
-    ctx.set_fill(&text.fill);
+    let pos = Point::new(Abs::pt(x as f64), Abs::pt(y as f64) - text.size);
+    let size = Size::new(text.width(), text.size);
+    let mask = ctx.soft_mask(&text.fill, true, pos, size);
+    if let Some(mask) = &mask {
+        ctx.save_state();
+        ctx.content.set_parameters(Name(mask.as_bytes()));
+    }
+
+    ctx.set_fill(&text.fill, true, pos, size);
     ctx.set_font(&text.font, text.size);
     ctx.content.begin_text();
 
-    // Positiosn the text.
-    ctx.content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, y]);
+    // Position the text. Font expansion scales the glyphs and their advances
+    // horizontally, so the adjustments below are made in unscaled units.
+    let expansion = 1.0 + text.expansion.get();
+    ctx.content.set_text_matrix([expansion as f32, 0.0, 0.0, -1.0, x, y]);
 
     let mut positioned = ctx.content.show_positioned();
     let mut items = positioned.items();
@@ -349,7 +605,9 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
 
     // Write the glyphs with kerning adjustments.
     for glyph in &text.glyphs {
-        adjustment += glyph.x_offset;
+        let x_advance = glyph.x_advance / expansion;
+        let x_offset = glyph.x_offset / expansion;
+        adjustment += x_offset;
 
         if !adjustment.is_zero() {
             if !encoded.is_empty() {
@@ -365,10 +623,10 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
         encoded.push((glyph.id & 0xff) as u8);
 
         if let Some(advance) = text.font.advance(glyph.id) {
-            adjustment += glyph.x_advance - advance;
+            adjustment += x_advance - advance;
         }
 
-        adjustment -= glyph.x_offset;
+        adjustment -= x_offset;
     }
 
     if !encoded.is_empty() {
@@ -378,6 +636,10 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
     items.finish();
     positioned.finish();
     ctx.content.end_text();
+
+    if mask.is_some() {
+        ctx.restore_state();
+    }
 }
 
 /// Encode a geometrical shape into the content stream.
@@ -386,12 +648,39 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
         return;
     }
 
+    let pos = Point::new(Abs::pt(x as f64), Abs::pt(y as f64));
+    let size = shape.geometry.bbox_size();
+
+    // A graphics state can only have one soft mask, so a translucent gradient
+    // requires the fill and the stroke to be painted separately.
+    let fill_mask = shape
+        .fill
+        .as_ref()
+        .and_then(|fill| ctx.soft_mask(fill, false, pos, size));
+    let stroke_mask = shape
+        .stroke
+        .as_ref()
+        .and_then(|stroke| ctx.soft_mask(&stroke.paint, false, pos, size));
+
+    let masked = fill_mask.is_some() || stroke_mask.is_some();
+    if masked && shape.fill.is_some() && shape.stroke.is_some() {
+        write_shape(ctx, x, y, &Shape { stroke: None, ..shape.clone() });
+        write_shape(ctx, x, y, &Shape { fill: None, ..shape.clone() });
+        return;
+    }
+
+    let mask = fill_mask.or(stroke_mask);
+    if let Some(mask) = &mask {
+        ctx.save_state();
+        ctx.content.set_parameters(Name(mask.as_bytes()));
+    }
+
     if let Some(fill) = &shape.fill {
-        ctx.set_fill(fill);
+        ctx.set_fill(fill, false, pos, size);
     }
 
     if let Some(stroke) = &shape.stroke {
-        ctx.set_stroke(stroke);
+        ctx.set_stroke(stroke, pos, size);
     }
 
     match shape.geometry {
@@ -419,6 +708,10 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
         (None, Some(_)) => ctx.content.stroke(),
         (Some(_), Some(_)) => ctx.content.fill_nonzero_and_stroke(),
     };
+
+    if mask.is_some() {
+        ctx.restore_state();
+    }
 }
 
 /// Encode a bezier path into the content stream.
@@ -483,5 +776,7 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
     let y2 = min_y.to_f32();
     let rect = Rect::new(x1, y1, x2, y2);
 
-    ctx.links.push((dest.clone(), rect));
+    // The link's structure node was just entered by its metadata.
+    let node = (!ctx.artifact).then_some(ctx.tag);
+    ctx.links.push((dest.clone(), rect, node));
 }
@@ -0,0 +1,548 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use pdf_writer::{Finish, Name, Ref, TextStr};
+
+use super::{PdfContext, RefExt};
+use crate::doc::{Destination, Meta, TableSection, Tag};
+use crate::eval::Value;
+use crate::model::{Content, Location};
+
+/// A node in the document's logical structure tree.
+#[derive(Debug, Clone)]
+pub struct StructNode {
+    /// The structure type, e.g. `P` or `H1`.
+    pub role: Role,
+    /// The parent node's index. `None` for the document root.
+    pub parent: Option<usize>,
+    /// The node's children in reading order.
+    pub kids: Vec<StructKid>,
+    /// Alternate text for figures.
+    pub alt: Option<EcoString>,
+    /// Identifies the node among its siblings if it was created for a keyed
+    /// tag, e.g. a table row.
+    key: Option<Key>,
+}
+
+/// Identifies a list item, table section, table row or table cell among its
+/// siblings. Keyed siblings are kept in key order.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+enum Key {
+    Section(usize),
+    Row(usize),
+    Column(usize),
+    Label,
+    Body,
+}
+
+/// A child of a structure node.
+#[derive(Debug, Copy, Clone)]
+pub enum StructKid {
+    /// Another structure node, by index.
+    Node(usize),
+    /// A marked-content sequence on a page.
+    Content { page: Ref, mcid: i32 },
+    /// A link annotation on a page.
+    Annotation { page: Ref, annot: Ref },
+}
+
+/// The structure types we emit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Role {
+    Document,
+    P,
+    H(usize),
+    L,
+    LI,
+    Lbl,
+    LBody,
+    Table,
+    THead,
+    TBody,
+    TFoot,
+    TR,
+    TH,
+    TD,
+    Figure,
+    Formula,
+    Link,
+}
+
+impl Role {
+    /// The standard structure type name.
+    pub fn name(self) -> Name<'static> {
+        Name(match self {
+            Self::Document => b"Document",
+            Self::P => b"P",
+            Self::H(1) => b"H1",
+            Self::H(2) => b"H2",
+            Self::H(3) => b"H3",
+            Self::H(4) => b"H4",
+            Self::H(5) => b"H5",
+            Self::H(_) => b"H6",
+            Self::L => b"L",
+            Self::LI => b"LI",
+            Self::Lbl => b"Lbl",
+            Self::LBody => b"LBody",
+            Self::Table => b"Table",
+            Self::THead => b"THead",
+            Self::TBody => b"TBody",
+            Self::TFoot => b"TFoot",
+            Self::TR => b"TR",
+            Self::TH => b"TH",
+            Self::TD => b"TD",
+            Self::Figure => b"Figure",
+            Self::Formula => b"Formula",
+            Self::Link => b"Link",
+        })
+    }
+
+    /// Whether graphics within this node are real content rather than
+    /// decoration.
+    pub fn is_graphical(self) -> bool {
+        matches!(self, Self::Figure | Self::Formula)
+    }
+
+    /// Determine the role of an element from its kind.
+    ///
+    /// Paragraphs, lists and tables are not located elements. Their nodes
+    /// are derived from the tags on the groups layout produces for them.
+    fn of(elem: &Content) -> Option<Self> {
+        Some(match elem.func().name() {
+            "heading" => {
+                let level = elem.cast_field::<NonZeroUsize>("level");
+                Self::H(level.map_or(1, NonZeroUsize::get))
+            }
+            "figure" => Self::Figure,
+            "equation" => Self::Formula,
+            _ => return None,
+        })
+    }
+}
+
+/// Builds the structure tree while the pages are constructed.
+pub struct StructBuilder {
+    /// All nodes, the document root is at index zero.
+    pub nodes: Vec<StructNode>,
+    /// Maps from element locations to node indices.
+    located: Vec<(Location, usize)>,
+    /// The most recent link node and its destination, so that consecutive
+    /// pieces of the same link end up in one node.
+    last_link: Option<(Destination, usize)>,
+    /// Maps from a parent node and a key to the keyed child node.
+    keyed: HashMap<(usize, Key), usize>,
+    /// For each page, the node each marked-content identifier belongs to.
+    pub parents: Vec<Vec<usize>>,
+    /// For each link annotation, the node it belongs to. These come after
+    /// the pages in the parent tree.
+    pub annotations: Vec<usize>,
+}
+
+impl StructBuilder {
+    /// Create a new builder with just a document root.
+    pub fn new() -> Self {
+        let root = StructNode {
+            role: Role::Document,
+            parent: None,
+            kids: vec![],
+            alt: None,
+            key: None,
+        };
+
+        Self {
+            nodes: vec![root],
+            located: vec![],
+            last_link: None,
+            keyed: HashMap::new(),
+            parents: vec![],
+            annotations: vec![],
+        }
+    }
+
+    /// Start tagging a new page.
+    pub fn start_page(&mut self) {
+        self.parents.push(vec![]);
+        self.last_link = None;
+    }
+
+    /// Enter the node for a piece of metadata within the given parent node.
+    ///
+    /// Returns the parent itself if the metadata has no structural meaning.
+    pub fn enter(&mut self, meta: &Meta, parent: usize) -> usize {
+        match meta {
+            Meta::Elem(elem) => {
+                let Some(role) = Role::of(elem) else { return parent };
+                let Some(location) = elem.location() else { return parent };
+
+                if let Some(&(_, index)) =
+                    self.located.iter().find(|(prev, _)| *prev == location)
+                {
+                    return index;
+                }
+
+                let alt = (role == Role::Figure)
+                    .then(|| elem.cast_field::<Content>("caption"))
+                    .flatten()
+                    .map(|caption| plain_text(&caption))
+                    .filter(|alt| !alt.is_empty());
+
+                let index = self.push(role, parent, alt);
+                self.located.push((location, index));
+                index
+            }
+            Meta::Link(dest) => {
+                if let Some((prev, index)) = &self.last_link {
+                    if prev == dest && self.nodes[*index].parent == Some(parent) {
+                        return *index;
+                    }
+                }
+
+                let index = self.push(Role::Link, parent, None);
+                self.last_link = Some((dest.clone(), index));
+                index
+            }
+            Meta::Hide => parent,
+        }
+    }
+
+    /// Enter the node for a tagged group within the given parent node.
+    ///
+    /// Continued paragraphs, lists and tables and further pieces of list
+    /// items and table cells end up in the node of their first piece.
+    pub fn enter_tag(&mut self, tag: Tag, parent: usize) -> usize {
+        match tag {
+            Tag::Par(continued) => {
+                // Headings and links only contain inline content.
+                if matches!(self.nodes[parent].role, Role::H(_) | Role::Link) {
+                    return parent;
+                }
+                let parent = self.item_body(parent, continued);
+                self.open(Role::P, parent, continued)
+            }
+            Tag::List(continued) => {
+                let parent = self.item_body(parent, continued);
+                self.open(Role::L, parent, continued)
+            }
+            Tag::ListLabel(row) => {
+                let item = self.keyed(Role::LI, parent, Key::Row(row));
+                self.keyed(Role::Lbl, item, Key::Label)
+            }
+            Tag::ListBody(row) => {
+                let item = self.keyed(Role::LI, parent, Key::Row(row));
+                self.keyed(Role::LBody, item, Key::Body)
+            }
+            Tag::Table(continued) => {
+                let parent = self.item_body(parent, continued);
+                self.open(Role::Table, parent, continued)
+            }
+            Tag::TableCell(section, x, y) => {
+                let (role, cell) = match section {
+                    TableSection::Header => (Role::THead, Role::TH),
+                    TableSection::Body => (Role::TBody, Role::TD),
+                    TableSection::Footer => (Role::TFoot, Role::TD),
+                };
+                let section = self.keyed(role, parent, Key::Section(section as usize));
+                let row = self.keyed(Role::TR, section, Key::Row(y));
+                self.keyed(cell, row, Key::Column(x))
+            }
+            Tag::Artifact => parent,
+        }
+    }
+
+    /// The node that content within a list must go into: Lists only contain
+    /// list items, so other content is wrapped in one.
+    fn item_body(&mut self, parent: usize, continued: bool) -> usize {
+        if self.nodes[parent].role != Role::L {
+            return parent;
+        }
+        let item = self.open(Role::LI, parent, continued);
+        self.open(Role::LBody, item, continued)
+    }
+
+    /// Reuse the last child node with the given role for continued content or
+    /// start a new one.
+    fn open(&mut self, role: Role, parent: usize, continued: bool) -> usize {
+        if continued {
+            let last = self.nodes[parent].kids.iter().rev().find_map(|kid| match *kid {
+                StructKid::Node(i) if self.nodes[i].role == role => Some(i),
+                _ => None,
+            });
+            if let Some(index) = last {
+                return index;
+            }
+        }
+        self.push(role, parent, None)
+    }
+
+    /// Find or create the child node with the given key.
+    fn keyed(&mut self, role: Role, parent: usize, key: Key) -> usize {
+        if let Some(&index) = self.keyed.get(&(parent, key)) {
+            return index;
+        }
+
+        let index = self.nodes.len();
+        let node = StructNode {
+            role,
+            parent: Some(parent),
+            kids: vec![],
+            alt: None,
+            key: Some(key),
+        };
+
+        // Layout doesn't necessarily produce rows and cells in order, e.g.
+        // cells spanning multiple rows come last.
+        let kids = &self.nodes[parent].kids;
+        let at = kids
+            .iter()
+            .position(|kid| match *kid {
+                StructKid::Node(i) => self.nodes[i].key.map_or(false, |k| k > key),
+                _ => false,
+            })
+            .unwrap_or(kids.len());
+
+        self.nodes.push(node);
+        self.nodes[parent].kids.insert(at, StructKid::Node(index));
+        self.keyed.insert((parent, key), index);
+        index
+    }
+
+    /// Allocate the next marked-content identifier on the current page for
+    /// content that belongs to the given node.
+    pub fn mark(&mut self, node: usize, page: Ref) -> i32 {
+        let parents = self.parents.last_mut().expect("no page started");
+        let mcid = parents.len() as i32;
+        parents.push(node);
+        self.nodes[node].kids.push(StructKid::Content { page, mcid });
+        mcid
+    }
+
+    /// Attribute a link annotation on a page to the given node and return the
+    /// annotation's key in the parent tree.
+    pub fn annotate(&mut self, node: usize, page: Ref, annot: Ref) -> i32 {
+        let key = self.parents.len() + self.annotations.len();
+        self.annotations.push(node);
+        self.nodes[node].kids.push(StructKid::Annotation { page, annot });
+        key as i32
+    }
+
+    /// Add a new node as the last child of its parent.
+    fn push(&mut self, role: Role, parent: usize, alt: Option<EcoString>) -> usize {
+        let index = self.nodes.len();
+        let node = StructNode {
+            role,
+            parent: Some(parent),
+            kids: vec![],
+            alt,
+            key: None,
+        };
+        self.nodes.push(node);
+        self.nodes[parent].kids.push(StructKid::Node(index));
+        index
+    }
+}
+
+/// Write the structure tree and return the reference of its root.
+pub fn write_struct_tree(ctx: &mut PdfContext) -> Ref {
+    let tree_ref = ctx.alloc.bump();
+    let parent_tree_ref = ctx.alloc.bump();
+    let len = ctx.structure.nodes.len();
+    let node_refs: Vec<Ref> = (0..len).map(|_| ctx.alloc.bump()).collect();
+
+    for (node, &id) in ctx.structure.nodes.iter().zip(&node_refs) {
+        let mut elem = ctx.writer.indirect(id).dict();
+        elem.pair(Name(b"Type"), Name(b"StructElem"));
+        elem.pair(Name(b"S"), node.role.name());
+        elem.pair(Name(b"P"), node.parent.map_or(tree_ref, |i| node_refs[i]));
+        if let Some(alt) = &node.alt {
+            elem.pair(Name(b"Alt"), TextStr(alt));
+        }
+
+        let mut kids = elem.insert(Name(b"K")).array();
+        for kid in &node.kids {
+            match *kid {
+                StructKid::Node(i) => {
+                    kids.item(node_refs[i]);
+                }
+                StructKid::Content { page, mcid } => {
+                    let mut marked = kids.push().dict();
+                    marked.pair(Name(b"Type"), Name(b"MCR"));
+                    marked.pair(Name(b"Pg"), page);
+                    marked.pair(Name(b"MCID"), mcid);
+                }
+                StructKid::Annotation { page, annot } => {
+                    let mut object = kids.push().dict();
+                    object.pair(Name(b"Type"), Name(b"OBJR"));
+                    object.pair(Name(b"Pg"), page);
+                    object.pair(Name(b"Obj"), annot);
+                }
+            }
+        }
+    }
+
+    // The parent tree maps from the marked-content identifiers on each page to
+    // the structure elements they belong to and from each link annotation to
+    // its link element.
+    let mut parent_tree = ctx.writer.indirect(parent_tree_ref).dict();
+    let mut nums = parent_tree.insert(Name(b"Nums")).array();
+    for (i, parents) in ctx.structure.parents.iter().enumerate() {
+        nums.item(i as i32);
+        nums.push().array().items(parents.iter().map(|&node| node_refs[node]));
+    }
+    let pages = ctx.structure.parents.len();
+    for (i, &node) in ctx.structure.annotations.iter().enumerate() {
+        nums.item((pages + i) as i32);
+        nums.item(node_refs[node]);
+    }
+    nums.finish();
+    parent_tree.finish();
+
+    let mut tree = ctx.writer.indirect(tree_ref).dict();
+    tree.pair(Name(b"Type"), Name(b"StructTreeRoot"));
+    tree.pair(Name(b"K"), node_refs[0]);
+    tree.pair(Name(b"ParentTree"), parent_tree_ref);
+    let next = pages + ctx.structure.annotations.len();
+    tree.pair(Name(b"ParentTreeNextKey"), next as i32);
+    tree.finish();
+
+    tree_ref
+}
+
+/// Extract the plain text of content, e.g. for alternate descriptions.
+fn plain_text(content: &Content) -> EcoString {
+    fn walk(content: &Content, text: &mut EcoString) {
+        if let Some(piece) = item!(text_str)(content) {
+            text.push_str(&piece);
+        } else if content.func().name() == "space" {
+            text.push(' ');
+        } else {
+            for (_, value) in content.fields_ref() {
+                walk_value(value, text);
+            }
+        }
+    }
+
+    fn walk_value(value: &Value, text: &mut EcoString) {
+        match value {
+            Value::Content(content) => walk(content, text),
+            Value::Array(array) => {
+                for value in array.iter() {
+                    walk_value(value, text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut text = EcoString::new();
+    walk(content, &mut text);
+    text.trim().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Document, Frame, FrameItem};
+    use crate::export::{pdf, PdfStandard};
+    use crate::geom::{Abs, Point, Size};
+
+    fn roles(builder: &StructBuilder, node: usize) -> Vec<Role> {
+        builder.nodes[node]
+            .kids
+            .iter()
+            .filter_map(|kid| match *kid {
+                StructKid::Node(i) => Some(builder.nodes[i].role),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn kid(builder: &StructBuilder, node: usize, i: usize) -> usize {
+        match builder.nodes[node].kids[i] {
+            StructKid::Node(i) => i,
+            _ => panic!("expected node"),
+        }
+    }
+
+    #[test]
+    fn test_struct_list_items() {
+        let mut builder = StructBuilder::new();
+        let list = builder.enter_tag(Tag::List(false), 0);
+        let body = builder.enter_tag(Tag::ListBody(1), list);
+        builder.enter_tag(Tag::ListLabel(0), list);
+        builder.enter_tag(Tag::Par(false), body);
+
+        // A list that continues on the next page ends up in the same nodes.
+        let next = builder.enter_tag(Tag::List(true), 0);
+        assert_eq!(next, list);
+        assert_eq!(builder.enter_tag(Tag::ListBody(1), next), body);
+
+        assert_eq!(roles(&builder, 0), [Role::L]);
+        assert_eq!(roles(&builder, list), [Role::LI, Role::LI]);
+        assert_eq!(roles(&builder, kid(&builder, list, 0)), [Role::Lbl]);
+        assert_eq!(roles(&builder, kid(&builder, list, 1)), [Role::LBody]);
+        assert_eq!(roles(&builder, body), [Role::P]);
+    }
+
+    #[test]
+    fn test_struct_paragraphs_in_list() {
+        let mut builder = StructBuilder::new();
+        let list = builder.enter_tag(Tag::List(false), 0);
+        let first = builder.enter_tag(Tag::Par(false), list);
+        assert_eq!(builder.enter_tag(Tag::Par(true), list), first);
+        builder.enter_tag(Tag::Par(false), list);
+
+        // Content directly within a list is wrapped into list items.
+        assert_eq!(roles(&builder, list), [Role::LI, Role::LI]);
+        let item = kid(&builder, list, 0);
+        assert_eq!(roles(&builder, item), [Role::LBody]);
+        assert_eq!(roles(&builder, kid(&builder, item, 0)), [Role::P]);
+    }
+
+    #[test]
+    fn test_struct_table_cells() {
+        let mut builder = StructBuilder::new();
+        let table = builder.enter_tag(Tag::Table(false), 0);
+        let b = TableSection::Body;
+        let second = builder.enter_tag(Tag::TableCell(b, 1, 0), table);
+        let first = builder.enter_tag(Tag::TableCell(b, 0, 0), table);
+        builder.enter_tag(Tag::TableCell(TableSection::Footer, 0, 0), table);
+        builder.enter_tag(Tag::TableCell(TableSection::Header, 0, 0), table);
+
+        // The table continues with another row on the next page.
+        let next = builder.enter_tag(Tag::Table(true), 0);
+        assert_eq!(next, table);
+        builder.enter_tag(Tag::TableCell(b, 0, 1), next);
+
+        assert_eq!(roles(&builder, table), [Role::THead, Role::TBody, Role::TFoot]);
+        let head = kid(&builder, table, 0);
+        assert_eq!(roles(&builder, kid(&builder, head, 0)), [Role::TH]);
+        let body = kid(&builder, table, 1);
+        assert_eq!(roles(&builder, body), [Role::TR, Role::TR]);
+        let row = kid(&builder, body, 0);
+        assert_eq!(roles(&builder, row), [Role::TD, Role::TD]);
+        assert_eq!(kid(&builder, row, 0), first);
+        assert_eq!(kid(&builder, row, 1), second);
+    }
+
+    #[test]
+    fn test_struct_link_annotation() {
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let dest = Destination::Url("https://typst.app".into());
+        let mut par = Frame::new(size);
+        par.push(Point::zero(), FrameItem::Meta(Meta::Link(dest), size));
+        par.tag(Tag::Par(false));
+
+        let mut page = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(100.0)));
+        page.push_frame(Point::zero(), par);
+
+        let document = Document { pages: vec![page], ..Default::default() };
+        let bytes = pdf(&document, PdfStandard::V1_7);
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("/S /P"));
+        assert!(text.contains("/S /Link"));
+        assert!(text.contains("/Type /OBJR"));
+        assert!(text.contains("/StructParent 1"));
+        assert!(text.contains("/ParentTreeNextKey 2"));
+    }
+}
@@ -11,7 +11,8 @@ use usvg::{FitTo, NodeExt};
 
 use crate::doc::{Frame, FrameItem, GroupItem, Meta, TextItem};
 use crate::geom::{
-    self, Abs, Color, Geometry, Paint, PathItem, Shape, Size, Stroke, Transform,
+    self, Abs, Color, Geometry, Gradient, GradientKind, LineCap, LineJoin, Paint,
+    PathItem, Point, Relative, Shape, Size, Stroke, Transform,
 };
 use crate::image::{DecodedImage, Image};
 
@@ -50,10 +51,10 @@ fn render_frame(
                 render_group(canvas, ts, mask, group);
             }
             FrameItem::Text(text) => {
-                render_text(canvas, ts, mask, text);
+                render_text(canvas, ts, mask, text, *pos, frame.size());
             }
             FrameItem::Shape(shape, _) => {
-                render_shape(canvas, ts, mask, shape);
+                render_shape(canvas, ts, mask, shape, *pos, frame.size());
             }
             FrameItem::Image(image, size, _) => {
                 render_image(canvas, ts, mask, image, *size);
@@ -109,22 +110,44 @@ fn render_group(
     render_frame(canvas, ts, mask, &group.frame);
 }
 
-/// Render a text run into the canvas.
+/// Render a text run at the given position within a frame of the given size
+/// into the canvas.
 fn render_text(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::ClipMask>,
     text: &TextItem,
+    pos: Point,
+    parent: Size,
 ) {
+    // Glyphs are painted with a gradient as paths in the coordinate system of
+    // the whole run, so that the gradient flows across the glyphs.
+    let mut storage = None;
+    let mut gradient = None;
+    if let Paint::Gradient(_) = text.fill {
+        let own = (
+            sk::Transform::from_translate(0.0, -text.size.to_f32()),
+            Size::new(text.width(), text.size),
+        );
+        let (bbox, size) = paint_bbox(&text.fill, true, own, pos, parent);
+        gradient = Some(to_sk_paint(&text.fill, bbox, size, density(ts), &mut storage));
+    }
+
     let mut x = 0.0;
+    let expansion = 1.0 + text.expansion.get() as f32;
     for glyph in &text.glyphs {
         let id = GlyphId(glyph.id);
         let offset = x + glyph.x_offset.at(text.size).to_f32();
-        let ts = ts.pre_translate(offset, 0.0);
+        let glyph_ts = ts.pre_translate(offset, 0.0).pre_scale(expansion, 1.0);
 
-        render_svg_glyph(canvas, ts, mask, text, id)
-            .or_else(|| render_bitmap_glyph(canvas, ts, mask, text, id))
-            .or_else(|| render_outline_glyph(canvas, ts, mask, text, id));
+        render_svg_glyph(canvas, glyph_ts, mask, text, id)
+            .or_else(|| render_bitmap_glyph(canvas, glyph_ts, mask, text, id))
+            .or_else(|| match &gradient {
+                Some(paint) => {
+                    render_gradient_glyph(canvas, ts, mask, text, id, offset, paint)
+                }
+                None => render_outline_glyph(canvas, glyph_ts, mask, text, id),
+            });
 
         x += glyph.x_advance.at(text.size).to_f32();
     }
@@ -234,6 +257,31 @@ fn render_bitmap_glyph(
     render_image(canvas, ts, mask, &image, Size::new(w, h))
 }
 
+/// Render an outline glyph with a gradient fill into the canvas.
+///
+/// Unlike for the other glyph kinds, the transform is the one of the whole
+/// text run and the glyph is placed at the given offset within it.
+fn render_gradient_glyph(
+    canvas: &mut sk::Pixmap,
+    ts: sk::Transform,
+    mask: Option<&sk::ClipMask>,
+    text: &TextItem,
+    id: GlyphId,
+    offset: f32,
+    paint: &sk::Paint,
+) -> Option<()> {
+    let mut builder = WrappedPathBuilder(sk::PathBuilder::new());
+    text.font.ttf().outline_glyph(id, &mut builder)?;
+
+    // Flip vertically because font design coordinate system is Y-up.
+    let scale = text.size.to_f32() / text.font.units_per_em() as f32;
+    let expansion = 1.0 + text.expansion.get() as f32;
+    let glyph_ts = sk::Transform::from_translate(offset, 0.0)
+        .pre_scale(scale * expansion, -scale);
+    let path = builder.0.finish()?.transform(glyph_ts)?;
+    canvas.fill_path(&path, paint, sk::FillRule::default(), ts, mask)
+}
+
 /// Render an outline glyph into the canvas. This is the "normal" case.
 fn render_outline_glyph(
     canvas: &mut sk::Pixmap,
@@ -277,8 +325,7 @@ fn render_outline_glyph(
         let mw = bitmap.width;
         let mh = bitmap.height;
 
-        let Paint::Solid(color) = text.fill;
-        let c = color.to_rgba();
+        let c = text.fill.approx_color().to_rgba();
 
         // Pad the pixmap with 1 pixel in each dimension so that we do
         // not get any problem with floating point errors along ther border
@@ -308,8 +355,7 @@ fn render_outline_glyph(
          let bottom = top + mh;
 
          // Premultiply the text color.
-        let Paint::Solid(color) = text.fill;
-        let c = color.to_rgba();
+        let c = text.fill.approx_color().to_rgba();
         let color = sk::ColorU8::from_rgba(c.r, c.g, c.b, 255).premultiply().get();
 
         // Blend the glyph bitmap with the existing pixels on the canvas.
@@ -337,12 +383,15 @@ fn render_outline_glyph(
     }
 }
 
-/// Render a geometrical shape into the canvas.
+/// Render a geometrical shape at the given position within a frame of the
+/// given size into the canvas.
 fn render_shape(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::ClipMask>,
     shape: &Shape,
+    pos: Point,
+    parent: Size,
 ) -> Option<()> {
     let path = match shape.geometry {
         Geometry::Line(target) => {
@@ -359,8 +408,12 @@ fn render_shape(
         Geometry::Path(ref path) => convert_path(path)?,
     };
 
+    let own = (sk::Transform::identity(), shape.geometry.bbox_size());
+
     if let Some(fill) = &shape.fill {
-        let mut paint: sk::Paint = fill.into();
+        let mut storage = None;
+        let (bbox, size) = paint_bbox(fill, false, own, pos, parent);
+        let mut paint = to_sk_paint(fill, bbox, size, density(ts), &mut storage);
         if matches!(shape.geometry, Geometry::Rect(_)) {
             paint.anti_alias = false;
         }
@@ -369,9 +422,39 @@ fn render_shape(
         canvas.fill_path(&path, &paint, rule, ts, mask);
     }
 
-    if let Some(Stroke { paint, thickness }) = &shape.stroke {
-        let paint = paint.into();
-        let stroke = sk::Stroke { width: thickness.to_f32(), ..Default::default() };
+    if let Some(Stroke {
+        paint,
+        thickness,
+        line_cap,
+        line_join,
+        dash_pattern,
+        miter_limit,
+    }) = &shape.stroke
+    {
+        let dash = dash_pattern.as_ref().and_then(|pattern| {
+            // tiny-skia only allows dash patterns with an even number of
+            // elements, while pdf allows any number.
+            let len = if pattern.array.len() % 2 == 1 { 2 } else { 1 };
+            let array = pattern
+                .array
+                .iter()
+                .map(|l| l.to_f32())
+                .cycle()
+                .take(len * pattern.array.len())
+                .collect();
+            sk::StrokeDash::new(array, pattern.phase.to_f32())
+        });
+
+        let mut storage = None;
+        let (bbox, size) = paint_bbox(paint, false, own, pos, parent);
+        let paint = to_sk_paint(paint, bbox, size, density(ts), &mut storage);
+        let stroke = sk::Stroke {
+            width: thickness.to_f32(),
+            line_cap: (*line_cap).into(),
+            line_join: (*line_join).into(),
+            dash,
+            miter_limit: miter_limit.0 as f32,
+        };
         canvas.stroke_path(&path, &paint, &stroke, ts, mask);
     }
 
@@ -488,13 +571,129 @@ impl From<Transform> for sk::Transform {
 impl From<&Paint> for sk::Paint<'static> {
     fn from(paint: &Paint) -> Self {
         let mut sk_paint = sk::Paint::default();
-        let Paint::Solid(color) = *paint;
-        sk_paint.set_color(color.into());
+        sk_paint.set_color(paint.approx_color().into());
         sk_paint.anti_alias = true;
         sk_paint
     }
 }
 
+/// Determine the bounding box a paint is laid out in.
+///
+/// The box is given as a transform from its coordinate system into the one of
+/// the painted item and its size. `own` is the item's own box and `pos` and
+/// `parent` are the item's position in its parent frame and that frame's size.
+fn paint_bbox(
+    paint: &Paint,
+    on_text: bool,
+    own: (sk::Transform, Size),
+    pos: Point,
+    parent: Size,
+) -> (sk::Transform, Size) {
+    match paint {
+        Paint::Gradient(gradient)
+            if gradient.relative_for(on_text) == Relative::Parent =>
+        {
+            let x = pos.x.to_f32();
+            let y = pos.y.to_f32();
+            (sk::Transform::from_translate(-x, -y), parent)
+        }
+        _ => own,
+    }
+}
+
+/// Convert a paint into a tiny-skia paint.
+///
+/// Gradients are laid out in a bounding box of the given size, whose
+/// coordinate system `bbox` maps into the one of the painted item. Conic
+/// gradients are prerendered into `storage` with the given number of pixels
+/// per point.
+fn to_sk_paint<'a>(
+    paint: &Paint,
+    bbox: sk::Transform,
+    size: Size,
+    density: f32,
+    storage: &'a mut Option<sk::Pixmap>,
+) -> sk::Paint<'a> {
+    let mut sk_paint = sk::Paint::default();
+    sk_paint.anti_alias = true;
+    let shader = match paint {
+        Paint::Solid(_) => None,
+        Paint::Gradient(gradient) => to_sk_shader(gradient, bbox, size, density, storage),
+    };
+
+    match shader {
+        Some(shader) => sk_paint.shader = shader,
+        None => sk_paint.set_color(paint.approx_color().into()),
+    }
+
+    sk_paint
+}
+
+/// Create a tiny-skia shader for a gradient.
+fn to_sk_shader<'a>(
+    gradient: &Gradient,
+    bbox: sk::Transform,
+    size: Size,
+    density: f32,
+    storage: &'a mut Option<sk::Pixmap>,
+) -> Option<sk::Shader<'a>> {
+    let point = |p: Point| sk::Point::from_xy(p.x.to_f32(), p.y.to_f32());
+    let stops = gradient
+        .stops()
+        .iter()
+        .map(|&(color, offset)| sk::GradientStop::new(offset.get() as f32, color.into()))
+        .collect();
+
+    match gradient.kind() {
+        GradientKind::Linear { angle } => {
+            let (start, end) = Gradient::linear_axis(angle, size);
+            let mode = sk::SpreadMode::Pad;
+            sk::LinearGradient::new(point(start), point(end), stops, mode, bbox)
+        }
+        GradientKind::Radial { center, radius } => {
+            let center = point(Gradient::center_in(center, size));
+            let radius = Gradient::radius_in(radius, size).to_f32();
+            let mode = sk::SpreadMode::Pad;
+            sk::RadialGradient::new(center, center, radius, stops, mode, bbox)
+        }
+        GradientKind::Conic { center, angle } => {
+            // Tiny-skia has no conic gradients, so we render one into a
+            // pixmap.
+            let w = (density * size.x.to_f32()).ceil().max(1.0) as u32;
+            let h = (density * size.y.to_f32()).ceil().max(1.0) as u32;
+            let center = Gradient::center_in(center, size);
+            let cx = density * center.x.to_f32();
+            let cy = density * center.y.to_f32();
+
+            let mut pixmap = sk::Pixmap::new(w, h)?;
+            for y in 0..h {
+                for x in 0..w {
+                    let dx = x as f32 + 0.5 - cx;
+                    let dy = y as f32 + 0.5 - cy;
+                    let theta = (dy.atan2(dx) as f64).to_degrees() - angle.to_deg();
+                    let c = gradient.sample(theta.rem_euclid(360.0) / 360.0);
+                    pixmap.pixels_mut()[(y * w + x) as usize] =
+                        sk::ColorU8::from_rgba(c.r, c.g, c.b, c.a).premultiply();
+                }
+            }
+
+            let pixmap = storage.insert(pixmap);
+            Some(sk::Pattern::new(
+                pixmap.as_ref(),
+                sk::SpreadMode::Pad,
+                sk::FilterQuality::Bilinear,
+                1.0,
+                bbox.pre_scale(1.0 / density, 1.0 / density),
+            ))
+        }
+    }
+}
+
+/// The number of pixels per point a transform results in.
+fn density(ts: sk::Transform) -> f32 {
+    ts.sx.hypot(ts.ky).max(ts.kx.hypot(ts.sy))
+}
+
 impl From<Color> for sk::Color {
     fn from(color: Color) -> Self {
         let c = color.to_rgba();
@@ -502,6 +701,26 @@ impl From<Color> for sk::Color {
     }
 }
 
+impl From<LineCap> for sk::LineCap {
+    fn from(line_cap: LineCap) -> Self {
+        match line_cap {
+            LineCap::Butt => sk::LineCap::Butt,
+            LineCap::Round => sk::LineCap::Round,
+            LineCap::Square => sk::LineCap::Square,
+        }
+    }
+}
+
+impl From<LineJoin> for sk::LineJoin {
+    fn from(line_join: LineJoin) -> Self {
+        match line_join {
+            LineJoin::Miter => sk::LineJoin::Miter,
+            LineJoin::Round => sk::LineJoin::Round,
+            LineJoin::Bevel => sk::LineJoin::Bevel,
+        }
+    }
+}
+
 /// Allows to build tiny-skia paths from glyph outlines.
 struct WrappedPathBuilder(sk::PathBuilder);
 
@@ -555,3 +774,82 @@ fn alpha_mul(color: u32, scale: u32) -> u32 {
     let ag = ((color >> 8) & mask) * scale;
     (rb & mask) | (ag & !mask)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::{Angle, DashPattern, Ratio, RgbaColor, Smart};
+    use crate::syntax::Span;
+
+    /// Render a rectangle filled with a horizontal linear gradient at one pixel
+    /// per point on a white background.
+    fn render_linear(stops: Vec<(Color, Ratio)>) -> sk::Pixmap {
+        let kind = GradientKind::Linear { angle: Angle::zero() };
+        let paint = Paint::Gradient(Gradient::new(kind, stops, Smart::Auto));
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let mut frame = Frame::new(size);
+        let shape = Geometry::Rect(size).filled(paint);
+        frame.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        render(&frame, 1.0, Color::WHITE)
+    }
+
+    #[track_caller]
+    fn assert_pixel(pixmap: &sk::Pixmap, x: u32, expected: Color) {
+        let actual = pixmap.pixel(x, 5).unwrap().demultiply();
+        let actual = [actual.red(), actual.green(), actual.blue(), actual.alpha()];
+        let RgbaColor { r, g, b, a } = expected.to_rgba();
+        let close = actual.iter().zip([r, g, b, a]).all(|(&u, v)| u.abs_diff(v) <= 2);
+        assert!(close, "pixel {x} is {actual:?}, expected {:?}", [r, g, b, a]);
+    }
+
+    #[test]
+    fn test_render_gradient_hard_stops() {
+        let pixmap = render_linear(vec![
+            (Color::RED, Ratio::zero()),
+            (Color::RED, Ratio::new(0.5)),
+            (Color::BLUE, Ratio::new(0.5)),
+            (Color::BLUE, Ratio::one()),
+        ]);
+        assert_pixel(&pixmap, 0, Color::RED);
+        assert_pixel(&pixmap, 8, Color::RED);
+        assert_pixel(&pixmap, 11, Color::BLUE);
+        assert_pixel(&pixmap, 19, Color::BLUE);
+    }
+
+    #[test]
+    fn test_render_gradient_translucent_stops() {
+        let transparent = RgbaColor::new(0x00, 0x74, 0xD9, 0x00);
+        let pixmap = render_linear(vec![
+            (Color::BLUE, Ratio::zero()),
+            (Color::BLUE, Ratio::new(0.25)),
+            (transparent.into(), Ratio::new(0.75)),
+            (transparent.into(), Ratio::one()),
+        ]);
+        assert_pixel(&pixmap, 0, Color::BLUE);
+        assert_pixel(&pixmap, 19, Color::WHITE);
+    }
+
+    #[test]
+    fn test_render_dashed_stroke() {
+        let stroke = Stroke {
+            thickness: Abs::pt(2.0),
+            dash_pattern: Some(DashPattern {
+                array: vec![Abs::pt(3.0), Abs::pt(3.0)],
+                phase: Abs::zero(),
+            }),
+            ..Default::default()
+        };
+
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let mut frame = Frame::new(size);
+        let shape = Geometry::Line(Point::with_x(size.x)).stroked(stroke);
+        let pos = Point::with_y(Abs::pt(5.0));
+        frame.push(pos, FrameItem::Shape(shape, Span::detached()));
+
+        let pixmap = render(&frame, 1.0, Color::WHITE);
+        assert_pixel(&pixmap, 1, Color::BLACK);
+        assert_pixel(&pixmap, 4, Color::WHITE);
+        assert_pixel(&pixmap, 7, Color::BLACK);
+        assert_pixel(&pixmap, 10, Color::WHITE);
+    }
+}
@@ -0,0 +1,446 @@
+//! Exporting into SVG images.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter, Write};
+
+use ecow::{eco_format, EcoString};
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::font::Font;
+use crate::geom::{
+    self, Abs, Color, Geometry, LineCap, LineJoin, Paint, PathItem, Shape, Size,
+    Transform,
+};
+use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+use crate::util::Escaped;
+
+/// Export a frame into an SVG image.
+///
+/// Returns the SVG markup as a string. Internal links are dropped because their
+/// targets are not part of the image.
+///
+/// Gradients are not drawn natively: Every gradient fill or stroke, including
+/// conic ones, is approximated by the single color halfway through it.
+pub fn svg(frame: &Frame) -> String {
+    let mut renderer = SvgRenderer::new();
+    renderer.render_frame(frame);
+    renderer.finish(frame.size())
+}
+
+/// Renders frames into SVG markup.
+struct SvgRenderer {
+    /// The markup of the visible content.
+    body: String,
+    /// The markup of definitions (glyph outlines and clip paths) that are
+    /// referenced from the body.
+    defs: String,
+    /// Maps from glyphs to the ids of their outline definitions. If a glyph
+    /// has no outline, it maps to `None`.
+    glyphs: HashMap<(Font, u16), Option<usize>>,
+    /// The number of clip paths defined so far.
+    clips: usize,
+}
+
+impl SvgRenderer {
+    /// Create a new, empty renderer.
+    fn new() -> Self {
+        Self {
+            body: String::new(),
+            defs: String::new(),
+            glyphs: HashMap::new(),
+            clips: 0,
+        }
+    }
+
+    /// Assemble the final SVG document.
+    fn finish(self, size: Size) -> String {
+        let mut xml = String::new();
+        let w = Num(size.x.to_pt());
+        let h = Num(size.y.to_pt());
+        write!(
+            xml,
+            r#"<svg class="typst-doc" viewBox="0 0 {w} {h}" width="{w}pt" height="{h}pt" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">"#,
+        )
+        .unwrap();
+        if !self.defs.is_empty() {
+            xml.push_str("<defs>");
+            xml.push_str(&self.defs);
+            xml.push_str("</defs>");
+        }
+        xml.push_str(&self.body);
+        xml.push_str("</svg>");
+        xml
+    }
+
+    /// Render a frame.
+    fn render_frame(&mut self, frame: &Frame) {
+        for (pos, item) in frame.items() {
+            let x = Num(pos.x.to_pt());
+            let y = Num(pos.y.to_pt());
+            match item {
+                FrameItem::Group(group) => {
+                    self.render_group(Transform::translate(pos.x, pos.y), group);
+                }
+                FrameItem::Text(text) => {
+                    write!(self.body, r#"<g transform="translate({x} {y})">"#).unwrap();
+                    self.render_text(text);
+                    self.body.push_str("</g>");
+                }
+                FrameItem::Shape(shape, _) => {
+                    write!(self.body, r#"<g transform="translate({x} {y})">"#).unwrap();
+                    self.render_shape(shape);
+                    self.body.push_str("</g>");
+                }
+                FrameItem::Image(image, size, _) => {
+                    write!(self.body, r#"<g transform="translate({x} {y})">"#).unwrap();
+                    self.render_image(image, *size);
+                    self.body.push_str("</g>");
+                }
+                FrameItem::Meta(meta, size) => match meta {
+                    Meta::Link(dest) => {
+                        write!(self.body, r#"<g transform="translate({x} {y})">"#)
+                            .unwrap();
+                        self.render_link(dest, *size);
+                        self.body.push_str("</g>");
+                    }
+                    Meta::Elem(_) => {}
+                    Meta::Hide => {}
+                },
+            }
+        }
+    }
+
+    /// Render a group with optional transform and clipping.
+    fn render_group(&mut self, ts: Transform, group: &GroupItem) {
+        let ts = ts.pre_concat(group.transform);
+        write!(self.body, r#"<g transform="{}""#, Matrix(ts)).unwrap();
+
+        if group.clips {
+            let id = self.clips;
+            self.clips += 1;
+            let size = group.frame.size();
+            write!(
+                self.defs,
+                r#"<clipPath id="c{id}"><rect width="{}" height="{}"/></clipPath>"#,
+                Num(size.x.to_pt()),
+                Num(size.y.to_pt()),
+            )
+            .unwrap();
+            write!(self.body, r#" clip-path="url(#c{id})""#).unwrap();
+        }
+
+        self.body.push('>');
+        self.render_frame(&group.frame);
+        self.body.push_str("</g>");
+    }
+
+    /// Render a text run.
+    fn render_text(&mut self, text: &TextItem) {
+        let scale = text.size.to_pt() / text.font.units_per_em();
+        let expansion = 1.0 + text.expansion.get();
+        write!(self.body, r#"<g class="typst-text" {}>"#, FillAttrs(&text.fill))
+            .unwrap();
+
+        let mut x = Abs::zero();
+        for glyph in &text.glyphs {
+            let offset = x + glyph.x_offset.at(text.size);
+            if let Some(id) = self.glyph(&text.font, glyph.id) {
+                write!(
+                    self.body,
+                    r##"<use xlink:href="#g{id}" transform="translate({} 0) scale({} {})"/>"##,
+                    Num(offset.to_pt()),
+                    Num(scale * expansion),
+                    Num(-scale),
+                )
+                .unwrap();
+            } else {
+                self.render_bitmap_glyph(text, offset, glyph.id);
+            }
+            x += glyph.x_advance.at(text.size);
+        }
+
+        self.body.push_str("</g>");
+    }
+
+    /// Retrieve the id of a glyph's outline definition, defining it if
+    /// necessary.
+    fn glyph(&mut self, font: &Font, id: u16) -> Option<usize> {
+        let next = self.glyphs.len();
+        let defs = &mut self.defs;
+        *self.glyphs.entry((font.clone(), id)).or_insert_with(|| {
+            let mut builder = SvgPathBuilder(String::new());
+            font.ttf().outline_glyph(GlyphId(id), &mut builder)?;
+            write!(defs, r#"<path id="g{next}" d="{}"/>"#, builder.0.trim_end()).unwrap();
+            Some(next)
+        })
+    }
+
+    /// Render a bitmap glyph as an embedded image.
+    fn render_bitmap_glyph(&mut self, text: &TextItem, x: Abs, id: u16) -> Option<()> {
+        let raster = text.font.ttf().glyph_raster_image(GlyphId(id), u16::MAX)?;
+        let image = Image::new(raster.data.into(), raster.format.into()).ok()?;
+
+        // Position the bitmap the same way as the raster exporter does.
+        let size = text.size.to_pt();
+        let h = text.size;
+        let w = (image.width() as f64 / image.height() as f64) * h;
+        let dx = (raster.x as f64) / (image.width() as f64) * size;
+        let dy = (raster.y as f64) / (image.height() as f64) * size;
+        write!(
+            self.body,
+            r#"<g transform="translate({} {})">"#,
+            Num(x.to_pt() + dx),
+            Num(-size - dy),
+        )
+        .unwrap();
+        self.render_image(&image, Size::new(w, h));
+        self.body.push_str("</g>");
+        Some(())
+    }
+
+    /// Render a geometrical shape.
+    fn render_shape(&mut self, shape: &Shape) {
+        let data = match shape.geometry {
+            Geometry::Line(target) => {
+                eco_format!("M0 0 L{} {}", Num(target.x.to_pt()), Num(target.y.to_pt()))
+            }
+            Geometry::Rect(size) => convert_path(&geom::Path::rect(size)),
+            Geometry::Path(ref path) => convert_path(path),
+        };
+
+        write!(self.body, r#"<path d="{data}""#).unwrap();
+
+        match &shape.fill {
+            Some(fill) => write!(self.body, " {}", FillAttrs(fill)).unwrap(),
+            None => self.body.push_str(r#" fill="none""#),
+        }
+
+        if let Some(stroke) = &shape.stroke {
+            let color = stroke.paint.approx_color();
+            write!(
+                self.body,
+                r#" stroke="{}" stroke-width="{}""#,
+                Hex(color),
+                Num(stroke.thickness.to_pt()),
+            )
+            .unwrap();
+            let alpha = color.to_rgba().a;
+            if alpha != u8::MAX {
+                write!(self.body, r#" stroke-opacity="{}""#, Num(alpha as f64 / 255.0))
+                    .unwrap();
+            }
+
+            let cap = match stroke.line_cap {
+                LineCap::Butt => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
+            };
+            let join = match stroke.line_join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            };
+            write!(self.body, r#" stroke-linecap="{cap}" stroke-linejoin="{join}""#)
+                .unwrap();
+            write!(self.body, r#" stroke-miterlimit="{}""#, Num(stroke.miter_limit.0))
+                .unwrap();
+
+            if let Some(pattern) = &stroke.dash_pattern {
+                let array: Vec<_> =
+                    pattern.array.iter().map(|l| Num(l.to_pt()).to_string()).collect();
+                write!(
+                    self.body,
+                    r#" stroke-dasharray="{}" stroke-dashoffset="{}""#,
+                    array.join(" "),
+                    Num(pattern.phase.to_pt()),
+                )
+                .unwrap();
+            }
+        }
+
+        self.body.push_str("/>");
+    }
+
+    /// Render a raster or vector image as a data URL.
+    fn render_image(&mut self, image: &Image, size: Size) {
+        let mime = match image.format() {
+            ImageFormat::Raster(RasterFormat::Png) => "image/png",
+            ImageFormat::Raster(RasterFormat::Jpg) => "image/jpeg",
+            ImageFormat::Raster(RasterFormat::Gif) => "image/gif",
+            ImageFormat::Vector(VectorFormat::Svg) => "image/svg+xml",
+        };
+
+        write!(
+            self.body,
+            r#"<image width="{}" height="{}" preserveAspectRatio="none" xlink:href="data:{mime};base64,{}"/>"#,
+            Num(size.x.to_pt()),
+            Num(size.y.to_pt()),
+            base64::encode(image.data()),
+        )
+        .unwrap();
+    }
+
+    /// Render a link as a transparent, clickable area.
+    fn render_link(&mut self, dest: &Destination, size: Size) {
+        // Internal links are dropped because their targets are on other
+        // pages, which are not part of the image.
+        let Destination::Url(href) = dest else { return };
+
+        write!(
+            self.body,
+            r#"<a xlink:href="{}"><rect width="{}" height="{}" fill="transparent"/></a>"#,
+            Escaped(href),
+            Num(size.x.to_pt()),
+            Num(size.y.to_pt()),
+        )
+        .unwrap();
+    }
+}
+
+/// Convert a Typst path into SVG path data.
+fn convert_path(path: &geom::Path) -> EcoString {
+    let mut data = EcoString::new();
+    for elem in &path.0 {
+        match elem {
+            PathItem::MoveTo(p) => {
+                write!(data, "M{} {} ", Num(p.x.to_pt()), Num(p.y.to_pt()))
+            }
+            PathItem::LineTo(p) => {
+                write!(data, "L{} {} ", Num(p.x.to_pt()), Num(p.y.to_pt()))
+            }
+            PathItem::CubicTo(p1, p2, p3) => write!(
+                data,
+                "C{} {} {} {} {} {} ",
+                Num(p1.x.to_pt()),
+                Num(p1.y.to_pt()),
+                Num(p2.x.to_pt()),
+                Num(p2.y.to_pt()),
+                Num(p3.x.to_pt()),
+                Num(p3.y.to_pt()),
+            ),
+            PathItem::ClosePath => write!(data, "Z "),
+        }
+        .unwrap();
+    }
+    data.trim_end().into()
+}
+
+/// Allows to build SVG path data from glyph outlines.
+struct SvgPathBuilder(String);
+
+impl OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "M{x} {y} ").unwrap();
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "L{x} {y} ").unwrap();
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        write!(self.0, "Q{x1} {y1} {x} {y} ").unwrap();
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        write!(self.0, "C{x1} {y1} {x2} {y2} {x} {y} ").unwrap();
+    }
+
+    fn close(&mut self) {
+        self.0.push_str("Z ");
+    }
+}
+
+/// Displays a number with limited precision to keep the markup small.
+struct Num(f64);
+
+impl Display for Num {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let rounded = (self.0 * 1000.0).round() / 1000.0;
+        if rounded == 0.0 {
+            f.write_str("0")
+        } else {
+            write!(f, "{rounded}")
+        }
+    }
+}
+
+/// Displays a transform as an SVG matrix.
+struct Matrix(Transform);
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Transform { sx, ky, kx, sy, tx, ty } = self.0;
+        write!(
+            f,
+            "matrix({} {} {} {} {} {})",
+            Num(sx.get()),
+            Num(ky.get()),
+            Num(kx.get()),
+            Num(sy.get()),
+            Num(tx.to_pt()),
+            Num(ty.to_pt()),
+        )
+    }
+}
+
+/// Displays a color as a hex string without alpha.
+struct Hex(Color);
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let c = self.0.to_rgba();
+        write!(f, "#{:02x}{:02x}{:02x}", c.r, c.g, c.b)
+    }
+}
+
+/// Displays the fill attributes for a paint.
+///
+/// Gradients are approximated by a single color.
+struct FillAttrs<'a>(&'a Paint);
+
+impl Display for FillAttrs<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let color = self.0.approx_color();
+        write!(f, r#"fill="{}""#, Hex(color))?;
+        let alpha = color.to_rgba().a;
+        if alpha != u8::MAX {
+            write!(f, r#" fill-opacity="{}""#, Num(alpha as f64 / 255.0))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::doc::Position;
+    use crate::geom::Point;
+
+    fn linked(dest: Destination) -> Frame {
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+        let mut frame = Frame::new(size);
+        frame.push(Point::zero(), FrameItem::Meta(Meta::Link(dest), size));
+        frame
+    }
+
+    #[test]
+    fn test_svg_external_link() {
+        let frame = linked(Destination::Url("https://typst.app".into()));
+        let svg = svg(&frame);
+        assert!(svg.starts_with(r#"<svg class="typst-doc" viewBox="0 0 20 10""#));
+        assert!(svg.contains(r#"<a xlink:href="https://typst.app">"#));
+        assert!(svg.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_svg_internal_link() {
+        let frame = linked(Destination::Position(Position {
+            page: NonZeroUsize::new(2).unwrap(),
+            point: Point::zero(),
+        }));
+        assert!(!svg(&frame).contains("<a "));
+    }
+}
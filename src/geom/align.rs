@@ -143,6 +143,24 @@ cast_to_value! {
     }
 }
 
+/// An alignment that is restricted to the vertical axis when cast.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct VerticalAlign(pub GenAlign);
+
+cast_from_value! {
+    VerticalAlign,
+    align: GenAlign => {
+        if align.axis() != Axis::Y {
+            Err("alignment must be vertical")?;
+        }
+        Self(align)
+    },
+}
+
+cast_to_value! {
+    v: VerticalAlign => v.0.into()
+}
+
 impl From<Axes<GenAlign>> for Axes<Option<GenAlign>> {
     fn from(axes: Axes<GenAlign>) -> Self {
         axes.map(Some)
@@ -0,0 +1,191 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use super::*;
+
+/// A color gradient.
+///
+/// Gradients are laid out within a bounding box: Either the one of the shape
+/// they paint or the one of its parent container. Positions within the box
+/// are given as ratios so that the same gradient can be used for boxes of any
+/// size.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Gradient(Arc<Repr>);
+
+/// The internal representation of a [`Gradient`].
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct Repr {
+    kind: GradientKind,
+    stops: Vec<(Color, Ratio)>,
+    relative: Smart<Relative>,
+}
+
+/// The shape of a gradient.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GradientKind {
+    /// Colors change along a straight line through the center of the box.
+    ///
+    /// An angle of zero goes from left to right, larger angles turn the line
+    /// clockwise.
+    Linear { angle: Angle },
+    /// Colors change outwards from a center point.
+    ///
+    /// A radius of `50%` reaches the sides of a square box.
+    Radial { center: Axes<Ratio>, radius: Ratio },
+    /// Colors change around a center point.
+    ///
+    /// The angle is where the first stop lies. Like for linear gradients, an
+    /// angle of zero points to the right.
+    Conic { center: Axes<Ratio>, angle: Angle },
+}
+
+/// What a gradient's bounding box is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Relative {
+    /// The bounding box of the painted shape itself.
+    Self_,
+    /// The bounding box of the container the shape is in.
+    Parent,
+}
+
+impl Gradient {
+    /// Create a new gradient.
+    ///
+    /// The stops must be sorted by their offsets.
+    pub fn new(
+        kind: GradientKind,
+        stops: Vec<(Color, Ratio)>,
+        relative: Smart<Relative>,
+    ) -> Self {
+        Self(Arc::new(Repr { kind, stops, relative }))
+    }
+
+    /// The shape of the gradient.
+    pub fn kind(&self) -> GradientKind {
+        self.0.kind
+    }
+
+    /// The color stops with their offsets.
+    pub fn stops(&self) -> &[(Color, Ratio)] {
+        &self.0.stops
+    }
+
+    /// The bounding box the gradient is laid out in, as configured.
+    pub fn relative(&self) -> Smart<Relative> {
+        self.0.relative
+    }
+
+    /// The bounding box the gradient is laid out in.
+    ///
+    /// Unless configured otherwise, text is painted relative to its parent so
+    /// that a gradient flows across all glyphs instead of restarting at each
+    /// text run.
+    pub fn relative_for(&self, on_text: bool) -> Relative {
+        self.0
+            .relative
+            .unwrap_or(if on_text { Relative::Parent } else { Relative::Self_ })
+    }
+
+    /// The color at a position between zero and one.
+    pub fn sample(&self, t: f64) -> RgbaColor {
+        let stops = self.stops();
+        let t = t.clamp(0.0, 1.0);
+        let Some(next) = stops.iter().position(|(_, offset)| offset.get() >= t) else {
+            return stops.last().map_or(RgbaColor::new(0, 0, 0, 0), |s| s.0.to_rgba());
+        };
+
+        if next == 0 {
+            return stops[0].0.to_rgba();
+        }
+
+        let (c0, o0) = stops[next - 1];
+        let (c1, o1) = stops[next];
+        let range = o1.get() - o0.get();
+        let f = if range > 0.0 { (t - o0.get()) / range } else { 1.0 };
+        let (a, b) = (c0.to_rgba(), c1.to_rgba());
+        let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * f).round() as u8;
+        RgbaColor::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b), mix(a.a, b.a))
+    }
+
+    /// The start and end point of a linear gradient's axis within a box of
+    /// the given size.
+    ///
+    /// The axis runs through the center of the box and is just long enough
+    /// that the lines orthogonal to it through the start and end point touch
+    /// the box's corners.
+    pub fn linear_axis(angle: Angle, size: Size) -> (Point, Point) {
+        let (w, h) = (size.x.to_raw(), size.y.to_raw());
+        let (cos, sin) = (angle.cos(), angle.sin());
+        let half = (w * cos.abs() + h * sin.abs()) / 2.0;
+        let dx = Abs::raw(cos * half);
+        let dy = Abs::raw(sin * half);
+        let center = size.to_point() / 2.0;
+        (center - Point::new(dx, dy), center + Point::new(dx, dy))
+    }
+
+    /// The center point within a box of the given size.
+    pub fn center_in(center: Axes<Ratio>, size: Size) -> Point {
+        Point::new(center.x.of(size.x), center.y.of(size.y))
+    }
+
+    /// The absolute radius of a radial gradient within a box of the given
+    /// size.
+    pub fn radius_in(radius: Ratio, size: Size) -> Abs {
+        let diagonal = size.x.to_raw().hypot(size.y.to_raw());
+        Abs::raw(radius.get() * diagonal / std::f64::consts::SQRT_2)
+    }
+}
+
+impl Debug for Gradient {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let stops = self
+            .stops()
+            .iter()
+            .map(|(color, offset)| format!("({color:?}, {offset:?})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.kind() {
+            GradientKind::Linear { angle } => {
+                write!(f, "gradient.linear({stops}, angle: {angle:?}")?
+            }
+            GradientKind::Radial { center, radius } => write!(
+                f,
+                "gradient.radial({stops}, center: ({:?}, {:?}), radius: {radius:?}",
+                center.x, center.y,
+            )?,
+            GradientKind::Conic { center, angle } => write!(
+                f,
+                "gradient.conic({stops}, center: ({:?}, {:?}), angle: {angle:?}",
+                center.x, center.y,
+            )?,
+        }
+
+        match self.relative() {
+            Smart::Auto => {}
+            Smart::Custom(Relative::Self_) => f.write_str(", relative: \"self\"")?,
+            Smart::Custom(Relative::Parent) => f.write_str(", relative: \"parent\"")?,
+        }
+
+        f.write_char(')')
+    }
+}
+
+cast_from_value! {
+    Gradient: "gradient",
+}
+
+cast_from_value! {
+    Relative,
+    /// Relative to the bounding box of the painted shape.
+    "self" => Self::Self_,
+    /// Relative to the bounding box of the parent container.
+    "parent" => Self::Parent,
+}
+
+cast_to_value! {
+    v: Relative => Value::from(match v {
+        Relative::Self_ => "self",
+        Relative::Parent => "parent",
+    })
+}
@@ -7,6 +7,21 @@ use super::*;
 pub enum Paint {
     /// A solid color.
     Solid(Color),
+    /// A color gradient.
+    Gradient(Gradient),
+}
+
+impl Paint {
+    /// A single color that approximates this paint.
+    ///
+    /// Used where a gradient cannot be drawn natively. For a gradient, this is
+    /// the color halfway through it.
+    pub fn approx_color(&self) -> Color {
+        match self {
+            Self::Solid(color) => *color,
+            Self::Gradient(gradient) => gradient.sample(0.5).into(),
+        }
+    }
 }
 
 impl<T: Into<Color>> From<T> for Paint {
@@ -19,6 +34,7 @@ impl Debug for Paint {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::Solid(color) => color.fmt(f),
+            Self::Gradient(gradient) => gradient.fmt(f),
         }
     }
 }
@@ -26,10 +42,14 @@ impl Debug for Paint {
 cast_from_value! {
     Paint,
     color: Color => Self::Solid(color),
+    gradient: Gradient => Self::Gradient(gradient),
 }
 
 cast_to_value! {
-    Paint::Solid(color): Paint => Value::Color(color)
+    v: Paint => match v {
+        Paint::Solid(color) => Value::Color(color),
+        Paint::Gradient(gradient) => gradient.into(),
+    }
 }
 
 /// A color in a dynamic format.
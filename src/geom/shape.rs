@@ -32,4 +32,27 @@ impl Geometry {
     pub fn stroked(self, stroke: Stroke) -> Shape {
         Shape { geometry: self, fill: None, stroke: Some(stroke) }
     }
+
+    /// The size of the box spanned by the origin and the geometry's extent.
+    pub fn bbox_size(&self) -> Size {
+        match self {
+            Self::Line(target) => Size::new(target.x.abs(), target.y.abs()),
+            Self::Rect(size) => *size,
+            Self::Path(path) => {
+                let mut size = Size::zero();
+                for item in &path.0 {
+                    let points = match item {
+                        PathItem::MoveTo(p) | PathItem::LineTo(p) => vec![p],
+                        PathItem::CubicTo(p1, p2, p3) => vec![p1, p2, p3],
+                        PathItem::ClosePath => vec![],
+                    };
+                    for point in points {
+                        size.x.set_max(point.x);
+                        size.y.set_max(point.y);
+                    }
+                }
+                size
+            }
+        }
+    }
 }
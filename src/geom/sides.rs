@@ -183,11 +183,19 @@ where
     T: Default + Cast + Clone,
 {
     fn is(value: &Value) -> bool {
-        matches!(value, Value::Dict(_)) || T::is(value)
+        matches!(value, Value::Dict(dict) if is_sides_dict(dict)) || T::is(value)
     }
 
     fn cast(mut value: Value) -> StrResult<Self> {
         if let Value::Dict(dict) = &mut value {
+            if !is_sides_dict(dict) {
+                return if T::is(&value) {
+                    Ok(Self::splat(Some(T::cast(value)?)))
+                } else {
+                    <Self as Cast>::error(value)
+                };
+            }
+
             let mut take = |key| dict.take(key).ok().map(T::cast).transpose();
 
             let rest = take("rest")?;
@@ -200,7 +208,7 @@ where
                 bottom: take("bottom")?.or_else(|| y.clone()),
             };
 
-            dict.finish(&["left", "top", "right", "bottom", "x", "y", "rest"])?;
+            dict.finish(&SIDES_KEYS)?;
 
             Ok(sides)
         } else if T::is(&value) {
@@ -215,6 +223,15 @@ where
     }
 }
 
+/// The keys of a dictionary that specifies sides.
+const SIDES_KEYS: [&str; 7] = ["left", "top", "right", "bottom", "x", "y", "rest"];
+
+/// Whether a dictionary specifies sides rather than a value for all sides,
+/// like a stroke with dash pattern.
+fn is_sides_dict(dict: &Dict) -> bool {
+    dict.is_empty() || dict.iter().any(|(key, _)| SIDES_KEYS.contains(&key.as_str()))
+}
+
 impl<T> From<Sides<Option<T>>> for Value
 where
     T: PartialEq + Into<Value>,
@@ -17,7 +17,8 @@
 //!   per page with items at fixed positions.
 //! - **Exporting:**
 //!   These frames can finally be exported into an output format (currently
-//!   supported are [PDF] and [raster images]).
+//!   supported are [PDF], [raster images], and [SVG]). Alternatively, the
+//!   content can be exported into semantic [HTML].
 //!
 //! [tokens]: syntax::SyntaxKind
 //! [parsed]: syntax::parse
@@ -31,6 +32,8 @@
 //! [frame]: doc::Frame
 //! [PDF]: export::pdf
 //! [raster images]: export::render
+//! [SVG]: export::svg
+//! [HTML]: export::html
 
 #![recursion_limit = "1000"]
 
@@ -57,16 +60,21 @@ use comemo::{Prehashed, Track};
 
 use crate::diag::{FileResult, SourceResult};
 use crate::doc::Document;
-use crate::eval::{Library, Route, Tracer};
+use crate::eval::{Datetime, Library, Route, Tracer};
 use crate::font::{Font, FontBook};
 use crate::syntax::{Source, SourceId};
 use crate::util::Buffer;
 
 /// Compile a source file into a fully layouted document.
-pub fn compile(world: &(dyn World + 'static)) -> SourceResult<Document> {
+///
+/// Warnings emitted along the way are collected in the `tracer`, also if
+/// compilation fails.
+pub fn compile(
+    world: &(dyn World + 'static),
+    tracer: &mut Tracer,
+) -> SourceResult<Document> {
     // Evaluate the source file into a module.
     let route = Route::default();
-    let mut tracer = Tracer::default();
     let module =
         eval::eval(world.track(), route.track(), tracer.track_mut(), world.main())?;
 
@@ -74,6 +82,27 @@ pub fn compile(world: &(dyn World + 'static)) -> SourceResult<Document> {
     model::typeset(world.track(), tracer.track_mut(), &module.content())
 }
 
+/// Compile a source file into a semantic HTML document.
+///
+/// The document is typeset first so that counters, references and other
+/// introspections resolve to the same values as in the paged output.
+pub fn compile_html(
+    world: &(dyn World + 'static),
+    tracer: &mut Tracer,
+) -> SourceResult<String> {
+    // Evaluate the source file into a module.
+    let route = Route::default();
+    let module =
+        eval::eval(world.track(), route.track(), tracer.track_mut(), world.main())?;
+
+    // Typeset the module's contents to resolve introspections.
+    let content = module.content();
+    let document = model::typeset(world.track(), tracer.track_mut(), &content)?;
+
+    // Export the content tree.
+    export::html(world, tracer, &content, &document)
+}
+
 /// The environment in which typesetting occurs.
 #[comemo::track]
 pub trait World {
@@ -104,4 +133,27 @@ pub trait World {
 
     /// Try to access a file at a path.
     fn file(&self, path: &Path) -> FileResult<Buffer>;
+
+    /// The directory in which packages are stored.
+    ///
+    /// An import of `"@namespace/name:1.2.0"` resolves to the package in
+    /// the `namespace/name/1.2.0` subdirectory. Returns `None` if packages
+    /// are not supported, which is the default.
+    fn packages(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Get the current date.
+    ///
+    /// If no offset is specified, the local date should be chosen. Otherwise,
+    /// the UTC date should be chosen with the corresponding offset in hours.
+    ///
+    /// The date should stay the same throughout a compilation so that the
+    /// output is reproducible. If this function returns `None`, Typst's
+    /// `today` function will produce an error. By default, the current date is
+    /// unavailable.
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        let _ = offset;
+        None
+    }
 }
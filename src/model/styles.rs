@@ -467,6 +467,15 @@ impl<'a> StyleChain<'a> {
         next(self.properties::<T>(func, name, inherent), self, &default)
     }
 
+    /// The span of the set rule the first value for the given property in the
+    /// chain stems from.
+    pub fn span(self, func: ElemFunc, name: &str) -> Option<Span> {
+        self.entries()
+            .filter_map(Style::property)
+            .find(|property| property.is(func, name))
+            .and_then(|property| property.span)
+    }
+
     /// Iterate over all style recipes in the chain.
     pub fn recipes(self) -> impl Iterator<Item = &'a Recipe> {
         self.entries().filter_map(Style::recipe)
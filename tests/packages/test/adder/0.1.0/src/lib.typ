@@ -0,0 +1,5 @@
+// Absolute paths resolve relative to the package's directory.
+#import "/src/util.typ": double
+
+#let add(x, y) = x + y
+#let greeting = read("/greeting.txt").trim()
@@ -10,15 +10,17 @@ use comemo::{Prehashed, Track};
 use elsa::FrozenVec;
 use once_cell::unsync::OnceCell;
 use tiny_skia as sk;
-use typst::diag::{bail, FileError, FileResult};
+use typst::diag::{bail, FileError, FileResult, Severity};
 use typst::doc::{Document, Frame, FrameItem, Meta};
-use typst::eval::{func, Library, Value};
+use typst::eval::{func, Datetime, Library, Value};
+use typst::export::PdfStandard;
 use typst::font::{Font, FontBook};
-use typst::geom::{Abs, Color, RgbaColor, Sides, Smart};
+use typst::geom::{Abs, Color, RgbaColor, Smart};
+use typst::model::{Content, StyleChain};
 use typst::syntax::{Source, SourceId, Span, SyntaxNode};
 use typst::util::{Buffer, PathExt};
 use typst::World;
-use typst_library::layout::PageElem;
+use typst_library::layout::{Margin, PageElem};
 use typst_library::text::{TextElem, TextSize};
 use unscanny::Scanner;
 use walkdir::WalkDir;
@@ -29,6 +31,7 @@ const PNG_DIR: &str = "png";
 const PDF_DIR: &str = "pdf";
 const FONT_DIR: &str = "../assets/fonts";
 const FILE_DIR: &str = "../assets/files";
+const PACKAGE_DIR: &str = "packages";
 
 fn main() {
     let args = Args::new(env::args().skip(1));
@@ -172,7 +175,18 @@ fn library() -> Library {
         Value::None
     }
 
-    let mut lib = typst_library::build();
+    /// Display: HTML
+    /// Category: test
+    /// Returns: string
+    #[func]
+    fn html(body: Content) -> Value {
+        let styles = StyleChain::new(&vm.world().library().styles);
+        Value::Str(typst_library::html::html(&mut vm.vt, &body, styles)?.into())
+    }
+
+    // Provide an input like `--input key=value` would.
+    let inputs = [("key".into(), Value::Str("value".into()))].into_iter().collect();
+    let mut lib = typst_library::build_with_inputs(inputs);
 
     // Set page width to 120pt with 10pt margins, so that the inner page is
     // exactly 100pt wide. Page height is unbounded and font size is 10pt so
@@ -180,7 +194,7 @@ fn library() -> Library {
     lib.styles
         .set(PageElem::set_width(Smart::Custom(Abs::pt(120.0).into())));
     lib.styles.set(PageElem::set_height(Smart::Auto));
-    lib.styles.set(PageElem::set_margin(Sides::splat(Some(Smart::Custom(
+    lib.styles.set(PageElem::set_margin(Margin::splat(Some(Smart::Custom(
         Abs::pt(10.0).into(),
     )))));
     lib.styles.set(TextElem::set_size(TextSize(Abs::pt(10.0).into())));
@@ -188,6 +202,7 @@ fn library() -> Library {
     // Hook up helpers into the global scope.
     lib.global.scope_mut().define("test", test);
     lib.global.scope_mut().define("print", print);
+    lib.global.scope_mut().define("html", html);
     lib.global
         .scope_mut()
         .define("conifer", RgbaColor::new(0x9f, 0xEB, 0x52, 0xFF));
@@ -283,6 +298,15 @@ impl World for TestWorld {
             .get_or_init(|| read(path).map(Buffer::from))
             .clone()
     }
+
+    fn packages(&self) -> Option<&Path> {
+        Some(Path::new(PACKAGE_DIR))
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        // Pinned so that the reference images stay stable.
+        Datetime::from_ymd(1970, 1, 1)
+    }
 }
 
 impl TestWorld {
@@ -378,7 +402,7 @@ fn test(
     let document = Document { pages: frames, ..Default::default() };
     if compare_ever {
         if let Some(pdf_path) = pdf_path {
-            let pdf_data = typst::export::pdf(&document);
+            let pdf_data = typst::export::pdf(&document, PdfStandard::default());
             fs::create_dir_all(&pdf_path.parent().unwrap()).unwrap();
             fs::write(pdf_path, pdf_data).unwrap();
         }
@@ -453,10 +477,12 @@ fn test_part(
         println!("Model:\n{:#?}\n", module.content());
     }
 
-    let (mut frames, errors) = match typst::compile(world) {
+    let mut tracer = typst::eval::Tracer::default();
+    let (mut frames, mut errors) = match typst::compile(world, &mut tracer) {
         Ok(document) => (document.pages, vec![]),
         Err(errors) => (vec![], *errors),
     };
+    errors.extend(tracer.warnings().iter().cloned());
 
     // Don't retain frames if we don't wanna compare with reference images.
     if !compare_ref {
@@ -468,7 +494,9 @@ fn test_part(
     let mut errors: Vec<_> = errors
         .into_iter()
         .filter(|error| error.span.source() == id)
-        .map(|error| (error.range(world), error.message.replace('\\', "/")))
+        .map(|error| {
+            (error.range(world), error.severity, error.message.replace('\\', "/"))
+        })
         .collect();
 
     errors.sort_by_key(|error| error.0.start);
@@ -497,7 +525,10 @@ fn test_part(
     (ok, compare_ref, frames)
 }
 
-fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>) {
+/// An expected or emitted diagnostic.
+type Annotation = (Range<usize>, Severity, String);
+
+fn parse_metadata(source: &Source) -> (Option<bool>, Vec<Annotation>) {
     let mut compare_ref = None;
     let mut errors = vec![];
 
@@ -526,24 +557,38 @@ fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>
             source.line_column_to_byte(line, column).unwrap()
         };
 
-        let Some(rest) = line.strip_prefix("// Error: ") else { continue };
+        let (severity, rest) = if let Some(rest) = line.strip_prefix("// Error: ") {
+            (Severity::Error, rest)
+        } else if let Some(rest) = line.strip_prefix("// Warning: ") {
+            (Severity::Warning, rest)
+        } else if let Some(rest) = line.strip_prefix("// Hint: ") {
+            (Severity::Hint, rest)
+        } else {
+            continue;
+        };
+
         let mut s = Scanner::new(rest);
         let start = pos(&mut s);
         let end = if s.eat_if('-') { pos(&mut s) } else { start };
         let range = start..end;
 
-        errors.push((range, s.after().trim().to_string()));
+        errors.push((range, severity, s.after().trim().to_string()));
     }
 
     (compare_ref, errors)
 }
 
-fn print_error(source: &Source, line: usize, (range, message): &(Range<usize>, String)) {
+fn print_error(source: &Source, line: usize, (range, severity, message): &Annotation) {
     let start_line = 1 + line + source.byte_to_line(range.start).unwrap();
     let start_col = 1 + source.byte_to_column(range.start).unwrap();
     let end_line = 1 + line + source.byte_to_line(range.end).unwrap();
     let end_col = 1 + source.byte_to_column(range.end).unwrap();
-    println!("Error: {start_line}:{start_col}-{end_line}:{end_col}: {message}");
+    let kind = match severity {
+        Severity::Error => "Error",
+        Severity::Warning => "Warning",
+        Severity::Hint => "Hint",
+    };
+    println!("{kind}: {start_line}:{start_col}-{end_line}:{end_col}: {message}");
 }
 
 /// Pseudorandomly edit the source file and test whether a reparse produces the
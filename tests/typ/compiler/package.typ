@@ -0,0 +1,33 @@
+// Test package imports.
+// Ref: false
+
+---
+#import "@test/adder:0.1.0": add, double, greeting
+#test(add(2, 3), 5)
+#test(double(4), 8)
+#test(greeting, "Hello from a package!")
+
+---
+// The module is named after the package.
+#import "@test/adder:0.1.0"
+#test(adder.add(1, 1), 2)
+
+---
+// Error: 9-30 package not found: @test/missing:0.1.0
+#import "@test/missing:0.1.0"
+
+---
+// Error: 9-28 package not found: @test/adder:0.2.0
+#import "@test/adder:0.2.0"
+
+---
+// Error: 9-31 package manifest contains mismatched version 1.0.1
+#import "@test/mismatch:1.0.0"
+
+---
+// Error: 9-22 package specification is missing version
+#import "@test/adder"
+
+---
+// Error: 9-28 `x` is not a valid minor version
+#import "@test/adder:1.x.0"
@@ -64,3 +64,13 @@ Hello *#x*
 ---
 // Error: 12-26 set is only allowed directly in code and content blocks
 #{ let x = set text(blue) }
+
+---
+// Test set rule without effect.
+// Ref: false
+#[
+  Hello
+  // Warning: 4-17 set rule has no effect
+  // Hint: 4-17 set rules only apply to content that follows them in the same block
+  #set text(red)
+]
@@ -0,0 +1,95 @@
+// Test datetimes and durations.
+// Ref: false
+
+---
+// Test construction and accessors.
+#let date = datetime(year: 2023, month: 4, day: 12)
+#test(type(date), "datetime")
+#test(date.year(), 2023)
+#test(date.month(), 4)
+#test(date.day(), 12)
+#test(date.hour(), none)
+#test(date.weekday(), 3)
+#test(date.ordinal(), 102)
+#test(repr(date), "datetime(year: 2023, month: 4, day: 12)")
+
+#let time = datetime(year: 2000, month: 2, day: 29, hour: 13, minute: 5)
+#test(time.hour(), 13)
+#test(time.minute(), 5)
+#test(time.second(), 0)
+#test(time.ordinal(), 60)
+
+---
+// Test formatting.
+#let date = datetime(year: 2023, month: 4, day: 2)
+#test(date.display(), "2023-04-02")
+#test(date.display("[day].[month].[year]"), "02.04.2023")
+#test(date.display("[month repr:long] [day padding:none], [year]"), "April 2, 2023")
+#test(date.display("[weekday repr:short] [month repr:short] [day padding:space]"), "Sun Apr  2")
+#test(date.display("[weekday repr:monday] [ordinal] [[x]"), "7 092 [x]")
+
+#let time = datetime(year: 2023, month: 4, day: 2, hour: 9, minute: 30, second: 5)
+#test(time.display(), "2023-04-02 09:30:05")
+#test(time.display("[hour padding:none]h[minute]"), "9h30")
+
+---
+// Test arithmetic and comparison.
+#let date = datetime(year: 2023, month: 12, day: 30)
+#test(date + duration(days: 3), datetime(year: 2024, month: 1, day: 2))
+#test(duration(weeks: 1) + date, datetime(year: 2024, month: 1, day: 6))
+#test(date - duration(days: 365), datetime(year: 2022, month: 12, day: 30))
+#test(date + duration(hours: 36), datetime(year: 2023, month: 12, day: 31, hour: 12))
+#test((datetime(year: 2024, month: 3, day: 1) - datetime(year: 2024, month: 2, day: 1)).days(), 29.0)
+#test(datetime(year: 2020, month: 1, day: 1) < date, true)
+#test(date == datetime(year: 2023, month: 12, day: 30), true)
+
+#test(duration(minutes: 90).hours(), 1.5)
+#test(duration(hours: 1) * 3, duration(hours: 3))
+#test(-duration(seconds: 5) + duration(seconds: 10), duration(seconds: 5))
+#test(duration(days: 1) > duration(hours: 23), true)
+#test(repr(duration(hours: 25, seconds: -1)), "duration(days: 1, minutes: 59, seconds: 59)")
+
+---
+// Test the pinned date of the test environment.
+#test(today(), datetime(year: 1970, month: 1, day: 1))
+#test(today(offset: 2).display(), "1970-01-01")
+
+---
+// Error: 10-41 date does not exist
+#datetime(year: 2023, month: 2, day: 29)
+
+---
+// Error: 30-32 month must be between 1 and 12
+#datetime(year: 2023, month: 13, day: 1)
+
+---
+// Error: 10-32 missing argument: day
+#datetime(year: 2023, month: 1)
+
+---
+// Error: 2-57 unknown component: era
+#datetime(year: 2023, month: 1, day: 1).display("[era]")
+
+---
+// Error: 2-58 cannot display hour of a date without time
+#datetime(year: 2023, month: 1, day: 1).display("[hour]")
+
+---
+// Error: 16-18 offset must be between -24 and 24 hours
+#today(offset: 30)
+
+---
+// Error: 3-91 cannot apply '<' to datetime and datetime
+#(datetime(year: 2023, month: 1, day: 1) < datetime(year: 2023, month: 1, day: 1, hour: 0))
+
+---
+// Error: 3-64 duration overflow
+#(duration(seconds: 9223372036854775807) + duration(seconds: 1))
+
+---
+// Error: 3-65 duration overflow
+#(duration(seconds: -9223372036854775807) - duration(seconds: 2))
+
+---
+// Error: 3-68 duration overflow
+#(-(duration(seconds: -9223372036854775807) - duration(seconds: 1)))
@@ -0,0 +1,12 @@
+// Test the `sys` module.
+// Ref: false
+
+---
+// The test runner passes `key=value` as an input.
+#test(type(sys.inputs), "dictionary")
+#test(sys.inputs, (key: "value"))
+#test("missing" in sys.inputs, false)
+
+---
+// Error: 4-7 cannot mutate a constant
+#{ sys.inputs.key = "other" }
@@ -28,3 +28,27 @@ This is the start and it goes on.
 // All three lines go to the next page.
 #set text(olive)
 #lorem(10)
+
+---
+// Test configurable widow prevention.
+// Ref: false
+#set page(height: 60pt, margin: 0pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt, widows: 3)
+A \ B #locate(loc => test(loc.page(), 1)) \
+C #locate(loc => test(loc.page(), 2)) \ D \ E
+
+---
+// Test configurable orphan prevention.
+// Ref: false
+#set page(height: 60pt, margin: 0pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set block(spacing: 10pt)
+#set par(leading: 10pt, orphans: 3, widows: 1)
+A
+
+B #locate(loc => test(loc.page(), 2)) \ C \ D
+
+---
+// Error: 18-19 number must be positive
+#set par(widows: 0)
@@ -0,0 +1,83 @@
+// Test inside and outside margins, odd and even marginals and parity breaks.
+// Ref: false
+
+---
+// Inside and outside margins swap between odd and even pages.
+#set page(height: 60pt, margin: (inside: 30pt, outside: 10pt, y: 10pt))
+= Odd
+#pagebreak()
+= Even
+#locate(loc => {
+  let headings = query(heading, loc)
+  test(headings.first().location().position().x, 30pt)
+  test(headings.last().location().position().x, 10pt)
+})
+
+---
+// With a binding on the right, odd pages have the inside margin on the right.
+#set page(
+  height: 60pt,
+  margin: (inside: 30pt, outside: 10pt, y: 10pt),
+  binding: right,
+)
+= Odd
+#pagebreak()
+= Even
+#locate(loc => {
+  let headings = query(heading, loc)
+  test(headings.first().location().position().x, 10pt)
+  test(headings.last().location().position().x, 30pt)
+})
+
+---
+// Headers and footers can differ on odd, even and first pages.
+#set page(
+  height: 60pt,
+  margin: (y: 20pt),
+  header: (first: none, odd: align(right)[Odd], even: [Even]),
+  footer: (rest: align(center)[Footer], first: [First]),
+)
+#lorem(30)
+
+---
+// A parity break inserts an empty page if needed.
+#set page(height: 40pt)
+First
+#pagebreak(to: "odd")
+= Third
+#locate(loc => test(query(heading, loc).first().location().page(), 3))
+
+---
+// The inserted page is blank, but still counts.
+#set page(height: 60pt, numbering: "1", header: [#box[Header] <header>])
+First
+#pagebreak(to: "odd")
+Third
+#locate(loc => {
+  test(query(<header>, loc).map(it => it.location().page()), (1, 3))
+  test(counter(page).at(loc), (3,))
+})
+
+---
+// No empty page is inserted if the next page already has the right parity.
+#set page(height: 40pt)
+First
+#pagebreak(to: "even")
+= Second
+#locate(loc => test(query(heading, loc).first().location().page(), 2))
+
+---
+// Error: 19-44 inside and outside margins cannot be combined with left and right
+#set page(margin: (inside: 10pt, left: 5pt))
+
+---
+// Error: 20-23 binding must be left or right
+#set page(binding: top)
+
+---
+// Error: 19-31 unexpected key "middle", valid keys are "odd", "even", "first", and "rest"
+#set page(header: (middle: []))
+
+---
+// Error: 16-22 expected "even", "odd", or none
+#pagebreak(to: "left")
@@ -0,0 +1,54 @@
+// Test floating placement.
+// Ref: false
+
+---
+// Floats that don't fit move to the next page while text fills the gap.
+#set page(height: 140pt)
+#lorem(20)
+#figure(placement: auto, caption: [Float], rect(height: 80pt)) <big>
+#lorem(10)
+
+#locate(loc => {
+  let fig = query(<big>, loc).first()
+  test(fig.location().page(), 2)
+})
+
+---
+// Floats go to the top or bottom and keep their clearance.
+#set page(height: 200pt, margin: 10pt)
+#lorem(10)
+#place(bottom, float: true, clearance: 10pt, rect(height: 20pt)) <bottom>
+#place(top, float: true, rect(height: 20pt)) <top>
+#locate(loc => {
+  test(query(<top>, loc).first().location().position().y, 10pt)
+  test(query(<bottom>, loc).first().location().position().y, 170pt)
+})
+
+---
+// The metadata of a float moves with it and is only recorded once, while the
+// metadata of the elements around it stays in place.
+#set page(height: 200pt, margin: 10pt)
+#figure(placement: bottom, caption: [Float], rect(height: 20pt)) <float>
+#figure(caption: [Inline], rect(height: 20pt)) <inline>
+#locate(loc => {
+  test(query(figure, loc).len(), 2)
+  test(query(<float>, loc).len(), 1)
+  test(query(<inline>, loc).len(), 1)
+})
+
+---
+#figure(placement: top, caption: [Top], rect())
+#figure(placement: bottom, caption: [Bottom], rect())
+Between the figures.
+
+---
+// Error: 2-31 automatic positioning is only available for floats
+#place(auto, rect(width: 5pt))
+
+---
+// Error: 2-47 floating placement must be auto, top, or bottom
+#place(horizon, float: true, rect(width: 5pt))
+
+---
+// Error: 28-34 alignment must be vertical
+#figure(rect(), placement: center)
@@ -0,0 +1,77 @@
+// Test repeated table headers and footers.
+// Ref: false
+
+---
+// The header is repeated at the top of every page.
+#set page(height: 80pt)
+#table(
+  columns: 2,
+  table.header[#box[Key] <h>][Value],
+  ..range(8).map(n => ([#n], [#(n * n)])).flatten(),
+  [Last], [#box[End] <last>],
+)
+
+#locate(loc => {
+  let headers = query(<h>, loc)
+  let pages = query(<last>, loc).first().location().page()
+  test(pages > 1, true)
+  test(headers.len(), pages)
+  let top = headers.first().location().position().y
+  test(headers.all(h => h.location().position().y == top), true)
+})
+
+---
+// The footer is repeated at the bottom of every page and follows the body.
+#set page(height: 80pt)
+#table(
+  columns: 2,
+  gutter: 2pt,
+  table.header[Key][Value],
+  ..range(8).map(n => ([#box[#n] <body>], [#(n * n)])).flatten(),
+  table.footer[#box[Sum] <f>][140],
+)
+
+#locate(loc => {
+  let footers = query(<f>, loc)
+  let rows = query(<body>, loc)
+  test(footers.len(), rows.last().location().page())
+  test(footers.last().location().position().y > rows.last().location().position().y, true)
+})
+
+---
+// Header and footer cells can span columns and rows.
+#set page(height: 100pt)
+#table(
+  columns: 3,
+  table.header(
+    table.cell(rowspan: 2)[Item],
+    table.cell(colspan: 2)[Quarter],
+    [Q1], [Q2],
+  ),
+  ..range(6).map(n => ([#n], [#n], [#n])).flatten(),
+  table.footer(table.cell(colspan: 3)[Total]),
+)
+
+---
+// The body starts on a new row after a short header.
+#table(
+  columns: 3,
+  inset: 0pt,
+  table.header[#box[H] <head>],
+  [#box[A] <a>], [B], [C],
+)
+
+#locate(loc => {
+  let head = query(<head>, loc).first().location().position()
+  let a = query(<a>, loc).first().location().position()
+  test(head.x, a.x)
+  test(head.y < a.y, true)
+})
+
+---
+// Error: 13-28 table header must come first
+#table([A], table.header[B])
+
+---
+// Error: 8-23 table footer must come last
+#table(table.footer[A], [B])
@@ -0,0 +1,71 @@
+// Test table cells that span multiple columns and rows.
+// Ref: false
+
+---
+// Cells are placed around cells from earlier rows.
+#table(
+  columns: (20pt, 20pt, 20pt),
+  inset: 0pt,
+  table.cell(colspan: 2)[#box[A] <a>], [#box[B] <b>],
+  table.cell(rowspan: 2)[#box[C] <c>], [#box[D] <d>], [#box[E] <e>],
+  [#box[F] <f>], [#box[G] <g>],
+)
+
+#locate(loc => {
+  let pos(label) = query(label, loc).first().location().position()
+  test(pos(<a>).x, 10pt)
+  test(pos(<b>).x, 50pt)
+  test(pos(<d>).x, 30pt)
+  test(pos(<f>).x, 30pt)
+  test(pos(<g>).x, 50pt)
+  test(pos(<c>).y, pos(<d>).y)
+})
+
+---
+// Cells can override the table's fill, alignment and inset.
+#table(
+  columns: 4,
+  fill: (x, y) => if y == 0 { luma(220) },
+  align: center,
+  table.cell(colspan: 4, fill: aqua)[*Quarterly results*],
+  table.cell(rowspan: 2, align: horizon + left, inset: 10pt)[Revenue],
+  [Q1], [Q2], [Q3],
+  [1000 €], [2000 €], [3000 €],
+)
+
+---
+// Spanning cells make room for themselves in auto tracks.
+#table(
+  columns: 2,
+  gutter: 2pt,
+  table.cell(colspan: 2)[A fairly wide merged header],
+  [A], [B],
+  table.cell(rowspan: 2)[Very \ very \ tall], [C],
+  [D],
+)
+
+---
+// A cell spanning rows can break across pages.
+#set page(height: 80pt)
+#table(
+  columns: 2,
+  table.cell(rowspan: 4)[#lorem(10)],
+  [A], [B], [C], [D],
+)
+
+---
+// Spans in right-to-left tables.
+#set text(dir: rtl)
+#table(
+  columns: 3,
+  table.cell(colspan: 2)[A], [B],
+  [C], table.cell(colspan: 2)[D],
+)
+
+---
+// Error: 22-23 number must be positive
+#table.cell(colspan: 0)[A]
+
+---
+// Error: 8-12 function `table` does not contain `rows`
+#table.rows
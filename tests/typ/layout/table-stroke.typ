@@ -0,0 +1,101 @@
+// Test per-side and per-cell table strokes and rules.
+// Ref: false
+
+---
+// Per-side strokes.
+#table(
+  columns: 3,
+  stroke: (x: none, y: 0.5pt),
+  [A], [B], [C],
+  [D], [E], [F],
+)
+
+---
+// Strokes can depend on the cell's position.
+#table(
+  columns: 3,
+  stroke: (x, y) => (
+    top: if y == 0 { 1pt } else if y == 1 { 0.5pt },
+    bottom: if y == 2 { 1pt },
+  ),
+  [*Name*], [*Age*], [*City*],
+  [Ada], [36], [London],
+  [Grace], [85], [New York],
+)
+
+---
+// Set rules for the stroke fold per side.
+#set table(stroke: (x: 0.5pt + gray, y: 1pt))
+#set table(stroke: (top: blue))
+#set table.cell(stroke: (left: 2pt))
+#set table.cell(stroke: (left: red))
+#table(
+  columns: 3,
+  [A], [B], [C],
+  table.cell(stroke: auto)[D], [E], [F],
+)
+
+---
+// Cells can override the table's stroke.
+#table(
+  columns: 3,
+  stroke: 0.5pt + gray,
+  [A], table.cell(stroke: 2pt + red)[B], [C],
+  table.cell(colspan: 2, stroke: (bottom: 2pt))[D], [E],
+)
+
+---
+// Booktabs-style table with rules.
+#table(
+  columns: 3,
+  stroke: none,
+  table.hline(stroke: 1pt),
+  [*Name*], [*Age*], [*City*],
+  table.hline(stroke: 0.5pt),
+  [Ada], [36], [London],
+  [Grace], [85], [New York],
+  table.hline(stroke: 1pt),
+)
+
+---
+// Rules with explicit positions and extents.
+#table(
+  columns: 4,
+  stroke: none,
+  gutter: 3pt,
+  table.hline(y: 1, start: 1, end: 3, stroke: blue),
+  table.vline(x: 1, stroke: red),
+  table.vline(x: 3, start: 1),
+  [A], [B], [C], [D],
+  [E], [F], [G], [H],
+)
+
+---
+// Rules repeat with the header.
+#set page(height: 80pt)
+#table(
+  columns: 2,
+  stroke: none,
+  table.header(
+    table.hline(stroke: 1pt),
+    [*Key*], [*Value*],
+    table.hline(stroke: 0.5pt),
+  ),
+  ..range(8).map(n => ([#n], [#(n * n)])).flatten(),
+  table.hline(stroke: 1pt),
+)
+
+---
+// Rules in right-to-left tables.
+#set text(dir: rtl)
+#table(
+  columns: 3,
+  stroke: none,
+  [A], table.vline(), [B], [C],
+  table.hline(start: 1),
+  [D], [E], [F],
+)
+
+---
+// Error: 17-19 number must be at least zero
+#table.hline(y: -1)
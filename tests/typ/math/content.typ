@@ -16,4 +16,6 @@ $ x := #table(columns: 2)[x][y]/mat(1, 2, 3)
 ---
 // Test font switch.
 #let here = text.with(font: "Noto Sans")
+// Warning: 8-9 unknown font family: noto sans
+// Warning: 20-22 unknown font family: noto sans
 $#here[f] := #here[Hi there]$.
@@ -25,12 +25,16 @@ Hello
 ---
 #box[
   // Error: 4-32 document set rules are not allowed inside of containers
+  // Warning: 4-32 set rule has no effect
+  // Hint: 4-32 set rules only apply to content that follows them in the same block
   #set document(title: "Hello")
 ]
 
 ---
 #box[
   // Error: 4-18 page configuration is not allowed inside of containers
+  // Warning: 4-18 set rule has no effect
+  // Hint: 4-18 set rules only apply to content that follows them in the same block
   #set page("a4")
 ]
 
@@ -0,0 +1,48 @@
+// Test footnotes.
+// Ref: false
+
+---
+// Footnotes are numbered throughout the document.
+#set page(height: 120pt)
+A#footnote[First] B#footnote[Second]
+#pagebreak()
+C#footnote[Third]
+#locate(loc => {
+  let notes = query(footnote, loc)
+  test(notes.len(), 3)
+  test(notes.map(note => note.location().page()), (1, 1, 2))
+  test(counter(footnote).at(notes.last().location()), (3,))
+})
+
+---
+// Long footnotes break across pages and nested footnotes follow their parent.
+#set page(width: 120pt, height: 100pt)
+#set footnote(numbering: "*", separator: line(length: 100%, stroke: red))
+Hello#footnote[#lorem(40)#footnote[Nested]<nested>]<long> World#footnote[Last]
+#locate(loc => {
+  let page-of(label) = query(label, loc).first().location().page()
+  test(query(footnote, loc).len(), 3)
+  test(page-of(<long>), 1)
+  test(page-of(<nested>) >= page-of(<long>), true)
+  test(counter(page).final(loc).first() > 1, true)
+})
+
+---
+// A line whose footnote doesn't fit moves to the next page.
+#set page(height: 80pt, margin: 10pt)
+#block(height: 30pt)
+
+Here#footnote[#lorem(10)]
+#locate(loc => {
+  let notes = query(footnote, loc)
+  test(notes.len(), 1)
+  test(notes.first().location().page(), 2)
+})
+
+---
+#set footnote(clearance: 2em, gap: 1em, indent: 0pt)
+#footnote(numbering: "(a)")[Styled]
+
+---
+// Error: 10-12 missing argument: body
+#footnote()
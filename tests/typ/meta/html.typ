@@ -0,0 +1,56 @@
+// Test HTML export.
+// Ref: false
+
+---
+// Structural elements map to their HTML counterparts.
+#let doc = html[
+  = Introduction <intro>
+  Hello *world* and _you_!
+
+  - One
+  - Two
+]
+
+#test(doc.starts-with("<!DOCTYPE html>\n<html lang=\"en\">"), true)
+#test(doc.contains("<h1 id=\"intro\">Introduction</h1>"), true)
+#test(doc.contains("<p>Hello <strong>world</strong> and <em>you</em>!</p>"), true)
+#test(doc.contains("<ul>\n<li>One</li>\n<li>Two</li>\n</ul>"), true)
+
+---
+// The document's title becomes the page title.
+#let doc = html[
+  #set document(title: "Report")
+  Text
+]
+
+#test(doc.contains("<title>Report</title>"), true)
+
+---
+// The document's language comes from its text.
+#let doc = html[
+  #set text(lang: "de")
+  Hallo Welt
+]
+
+#test(doc.contains("<html lang=\"de\">"), true)
+
+---
+// Special characters are escaped.
+#let doc = html[a < b & "c"]
+#test(doc.contains("a &lt; b &amp;"), true)
+
+---
+// Tables are exported with their sections.
+#let doc = html(table(
+  columns: 2,
+  table.header[A][B],
+  [1], [2],
+))
+
+#test(doc.contains("<thead>\n<tr><th>A</th><th>B</th></tr>\n</thead>"), true)
+#test(doc.contains("<tbody>\n<tr><td>1</td><td>2</td></tr>\n</tbody>"), true)
+
+---
+// Content without an HTML equivalent is embedded as SVG.
+#let doc = html(rect(width: 10pt, height: 10pt))
+#test(doc.contains("<div><svg class=\"typst-doc\""), true)
@@ -0,0 +1,30 @@
+// Test numbering in other writing systems.
+// Ref: false
+
+---
+#test(numbering("一", 1), "一")
+#test(numbering("一", 12), "十二")
+#test(numbering("一", 105), "一百零五")
+#test(numbering("一", 10010), "一万零一十")
+#test(numbering("壹", 23), "贰拾叁")
+#test(numbering("١", 1024), "١٠٢٤")
+#test(numbering("१.", 42), "४२.")
+#test(numbering("α", 2), "β")
+#test(numbering("Α", 25), "ΑΑ")
+#test(numbering("①", 3), "③")
+#test(numbering("①", 42), "㊷")
+#test(numbering("가", 15), "가가")
+#test(numbering("ㄱ.", 3), "ㄷ.")
+#test(numbering("イ", 3), "ハ")
+#test(numbering("い", 3), "は")
+#test(numbering("ア", 3), "ウ")
+#test(numbering("あ", 46), "ん")
+#test(numbering("第一章", 3), "第三章")
+
+---
+// Headings and enums accept the patterns of other writing systems.
+#set heading(numbering: "一、")
+#set enum(numbering: "(α)")
+= 序論
++ Alpha
++ Beta
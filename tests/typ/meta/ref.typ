@@ -19,3 +19,12 @@ As seen in @intro, we proceed.
 
 // Error: 1-5 label occurs multiple times in the document
 @foo
+
+---
+// Test referencing a heading without numbering.
+// Ref: false
+= Introduction <intro>
+
+// Warning: 5-11 cannot reference heading without numbering
+// Hint: 5-11 enable numbering with `#set heading(numbering: "1.")`
+See @intro.
@@ -1,6 +1,7 @@
 // Test chinese text from Wikipedia.
 
 ---
+// Warning: 2-37 unknown font family: noto serif cjk sc
 #set text(font: "Noto Serif CJK SC")
 
 是美国广播公司电视剧《迷失》第3季的第22和23集，也是全剧的第71集和72集
@@ -49,3 +49,42 @@ It's a #emph[Tree]beard.
 #set page(width: 60pt)
 #set text(hyphenate: true)
 #h(6pt) networks, the rest.
+
+---
+// Test hyphenation exceptions and minimum fragment lengths.
+// Ref: false
+#set page(width: 60pt)
+#set par(justify: true)
+#set text(hyphenate: true, hyphenation-exceptions: ("data-base", "Typst"))
+A database for Typst. \
+#text(hyphenation-left-min: 4, hyphenation-right-min: 4)[Wonderful experiences]
+
+---
+// Test custom hyphenation patterns from a TeX pattern file.
+// Ref: false
+#set page(width: 40pt)
+#set text(
+  lang: "eu",
+  hyphenate: true,
+  hyphenation-patterns: read("/hyph-test.tex"),
+)
+abababababababab ababab
+
+---
+// Test limiting the number of consecutive hyphenated lines.
+// Ref: false
+#set page(width: 60pt)
+#set text(hyphenate: true)
+#set par(justify: true, consecutive-hyphens: 1)
+#lorem(30)
+
+#set par(linebreaks: "simple")
+#lorem(30)
+
+---
+// Error: 35-50 hyphenation exception must not have empty parts
+#set text(hyphenation-exceptions: ("data--base",))
+
+---
+// Error: 33-35 hyphenation patterns must not be empty
+#set text(hyphenation-patterns: "")
@@ -21,3 +21,33 @@
 #set align(end)
 #set text(dir: rtl)
 :
+
+---
+// Test custom overhang amounts at the start and end of lines.
+// Ref: false
+#set page(width: 130pt, margin: 15pt)
+#set par(justify: true)
+#set text(overhang: ("\"": (start: 50%, end: 50%), ",": 100%, "-": 0%))
+"This is a little bit of text," that builds up to hang-ing quotes, commas
+and "hyphens" in the margins.
+
+---
+// Test font expansion.
+// Ref: false
+#set page(width: 130pt, margin: 15pt)
+#set par(justify: true)
+#set text(expansion: 2%)
+This is a little bit of text that is justified with the help of slightly
+expanded or condensed glyphs.
+
+#set par(linebreaks: "simple")
+This is a little bit of text that is justified with the help of slightly
+expanded or condensed glyphs.
+
+---
+// Error: 21-32 overhang keys must be single characters
+#set text(overhang: ("ab": 10%))
+
+---
+// Error: 21-39 unexpected key "left", valid keys are "start" and "end"
+#set text(overhang: (".": (left: 10%)))
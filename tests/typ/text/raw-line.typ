@@ -0,0 +1,79 @@
+// Test line numbers, highlighted lines and tab size in raw blocks.
+// Ref: false
+
+---
+// Lines can be targeted by show rules.
+#show raw.line: it => {
+  test(it.count, 3)
+  if it.number == 2 {
+    test(it.text, "y = x + 1")
+    strong(it.body)
+  } else {
+    it
+  }
+}
+
+```py
+x = 1
+y = x + 1
+print(y)
+```
+
+---
+// Line numbers start at a configurable number.
+#set raw(numbering: "1", start: 9)
+#show raw.line: it => {
+  test(it.number in (9, 10, 11, 12), true)
+  it
+}
+
+```rust
+fn main() {
+
+    println!("Hello World!");
+}
+```
+
+---
+// Highlighted lines are filled.
+#set raw(
+  numbering: "i",
+  highlight: (1, ..range(3, 5)),
+  highlight-fill: yellow.lighten(60%),
+)
+
+```typ
+#let a = 1
+#let b = 2
+#let c = a + b
+#let d = c * 2
+```
+
+---
+// Tabs are expanded to the next multiple of the tab size.
+#show raw.line: it => {
+  test(it.text, "a       b       c")
+  it
+}
+#raw("a\tb\tc", block: true, tab-size: 8)
+
+---
+// Windows line endings are not part of the lines.
+#show raw.line: it => {
+  test(it.text, ("a", "b").at(it.number - 1))
+  it
+}
+#raw("a\r\nb", block: true)
+
+---
+// Inline raw text is not numbered.
+#set raw(numbering: "1")
+Use `x + 1` here.
+
+---
+// Error: 31-33 number must be at least zero
+#raw("a", block: true, start: -1)
+
+---
+// Error: 24-25 number must be positive
+#raw("a\tb", tab-size: 0)
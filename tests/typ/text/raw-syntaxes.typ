@@ -0,0 +1,60 @@
+// Test custom syntax definitions and themes for raw blocks.
+// Ref: false
+
+---
+// Custom syntaxes are selected through their file extensions.
+#let bodies = state("bodies", ())
+#show raw.line: it => bodies.update(b => b + (it.body,)) + it
+#let code = "let answer = 42"
+#raw(code, lang: "mylang", block: true)
+#raw(code, lang: "mylang", block: true, syntaxes: ("/mylang.sublime-syntax",))
+
+#set raw(syntaxes: ("/mylang.sublime-syntax",))
+```mylang
+# Compute the answer.
+let answer = 42
+```
+
+#locate(loc => {
+  let all = bodies.final(loc)
+  test(all.len(), 4)
+  test(all.at(0).text, "let answer = 42")
+  test(all.at(1).has("children"), true)
+  test(all.at(1).children.any(piece => piece.has("child")), true)
+  test(all.at(3), all.at(1))
+})
+
+---
+// Custom themes apply to built-in and custom syntaxes.
+#let bodies = state("bodies", ())
+#show raw.line: it => bodies.update(b => b + (it.body,)) + it
+#let code = "let answer = 42;"
+#raw(code, lang: "rust", block: true)
+#raw(code, lang: "rust", block: true)
+#raw(code, lang: "rust", block: true, theme: "/solarized.tmTheme")
+#raw("#let answer = *42*", lang: "typ", block: true)
+#raw("#let answer = *42*", lang: "typ", block: true, theme: "/solarized.tmTheme")
+
+#set raw(theme: "/solarized.tmTheme")
+```rust
+fn main() {
+    let answer = 42;
+}
+```
+
+#locate(loc => {
+  let all = bodies.final(loc)
+  test(all.len(), 8)
+  test(all.at(0), all.at(1))
+  test(all.at(0) == all.at(2), false)
+  test(all.at(3) == all.at(4), false)
+  test(all.at(2).has("children"), true)
+})
+
+---
+// Error: 21-45 file not found (searched at typ/text/nope.sublime-syntax)
+#raw("a", syntaxes: ("nope.sublime-syntax",))
+
+---
+// Error: 18-32 file not found (searched at typ/text/nope.tmTheme)
+#raw("a", theme: "nope.tmTheme")
@@ -0,0 +1,36 @@
+// Test vertical writing mode.
+// Ref: false
+
+---
+#set page(width: 120pt, height: 100pt)
+#set text(lang: "ja", dir: ttb)
+#locate(loc => none)<start>吾輩は猫である。名前はまだ無い。#locate(loc => {
+  let start = query(<start>, loc).first().location().position()
+  let end = loc.position()
+  test(end.page, start.page)
+  test(end.x < start.x, true)
+})
+
+---
+// Runs of other scripts are rotated along with the line.
+#set page(height: 100pt)
+#set text(dir: ttb)
+日本語とEnglishの混在。「括弧」もある。
+
+---
+// Columns continue on the next page once the page is full. Paragraphs are
+// stacked from right to left.
+#set page(width: 60pt, height: 80pt)
+#set text(lang: "ja", dir: ttb)
+#locate(loc => none)<first>吾輩は猫である。名前はまだ無い。
+
+どこで生れたかとんと見当がつかぬ。何でも薄暗いじめじめした所でニャーニャー泣いていた事だけは記憶している。#locate(loc => {
+  let first = query(<first>, loc).first().location().position()
+  test(first.page, 1)
+  test(loc.position().page > first.page, true)
+})
+
+---
+// Inline directions still work as before.
+#set text(dir: ttb)
+#text(dir: ltr)[Horizontal]
@@ -0,0 +1,65 @@
+// Test gradients.
+// Ref: false
+
+---
+#let g = gradient.linear(red, blue)
+#test(type(g), "gradient")
+#test(g, gradient.linear((red, 0%), (blue, 100%)))
+#test(repr(g), "gradient.linear((rgb(\"#ff4136\"), 0%), (rgb(\"#0074d9\"), 100%), angle: 0deg)")
+#test(
+  repr(gradient.conic(red, blue, green, relative: "parent")),
+  "gradient.conic((rgb(\"#ff4136\"), 0%), (rgb(\"#0074d9\"), 50%), (rgb(\"#2ecc40\"), 100%), center: (50%, 50%), angle: 0deg, relative: \"parent\")",
+)
+
+---
+// Gradients can be used wherever colors are used as paint.
+#set text(fill: gradient.linear(red, blue, angle: 45deg))
+#rect(
+  width: 40pt,
+  height: 20pt,
+  fill: gradient.radial((white, 0%), (eastern, 100%), center: (30%, 30%)),
+  stroke: 2pt + gradient.conic(red, blue, red),
+)
+#square(size: 20pt, stroke: gradient.linear(red, blue, relative: "parent"))
+Hello World
+
+---
+// Test hard stops.
+#let stops = ((red, 0%), (red, 50%), (blue, 50%), (blue, 100%))
+#stack(
+  dir: ltr,
+  spacing: 4pt,
+  rect(width: 30pt, height: 20pt, fill: gradient.linear(..stops)),
+  circle(radius: 10pt, fill: gradient.radial(..stops)),
+  circle(radius: 10pt, fill: gradient.conic(..stops)),
+)
+
+---
+// Test translucent stops.
+#let fade = gradient.linear(blue, rgb("#0074d900"))
+#rect(width: 60pt, height: 20pt, fill: fade, stroke: 2pt + red)
+#text(fill: gradient.conic(red, rgb("#0074d980"), red))[Faded]
+
+---
+// Error: 17-22 a gradient must have at least two stops
+#gradient.linear(red)
+
+---
+// Error: 23-29 expected color or array, found string
+#gradient.linear(red, "blue")
+
+---
+// Error: 29-33 either all or no stops must have an offset
+#gradient.linear((red, 0%), blue)
+
+---
+// Error: 30-41 offsets must be in ascending order
+#gradient.linear((red, 50%), (blue, 10%))
+
+---
+// Error: 29-41 offset must be between 0% and 100%
+#gradient.linear((red, 0%), (blue, 150%))
+
+---
+// Error: 39-43 expected "self", "parent", or auto
+#gradient.linear(red, blue, relative: "up")
@@ -0,0 +1,69 @@
+// Test stroke dictionaries, dash patterns, caps and joins.
+// Ref: false
+
+---
+#test(type(2pt + red), "stroke")
+#test(repr(2pt + red), "2pt + rgb(\"#ff4136\")")
+
+---
+// Stroke dictionaries keep the fields that are given.
+#test(
+  repr(line(stroke: (paint: blue, thickness: 4pt, cap: "round")).stroke),
+  "(paint: rgb(\"#0074d9\"), thickness: 4pt, cap: \"round\")",
+)
+#test(
+  repr(line(stroke: (thickness: 1pt, dash: "dashed")).stroke),
+  "(thickness: 1pt, dash: (array: (3pt, 3pt), phase: 0pt))",
+)
+#test(
+  repr(line(stroke: (dash: ("dot", 2pt, 4pt, 2pt), cap: "square")).stroke),
+  "(cap: \"square\", dash: (array: (\"dot\", 2pt, 4pt, 2pt), phase: 0pt))",
+)
+#test(
+  repr(line(stroke: (dash: (array: (3pt, 1pt), phase: 2pt))).stroke),
+  "(dash: (array: (3pt, 1pt), phase: 2pt))",
+)
+#test(
+  repr(line(stroke: (join: "bevel", dash: none)).stroke),
+  "(join: \"bevel\", dash: none)",
+)
+
+---
+// Strokes can be given as dictionaries everywhere.
+#line(length: 60pt, stroke: (paint: blue, thickness: 4pt, cap: "round"))
+#line(length: 60pt, stroke: (thickness: 1pt, dash: "dashed"))
+#line(length: 60pt, stroke: (dash: ("dot", 2pt, 4pt, 2pt), cap: "square"))
+#line(length: 60pt, stroke: (dash: (array: (3pt, 1pt), phase: 2pt)))
+#rect(width: 20pt, height: 20pt, stroke: (thickness: 3pt, join: "bevel"))
+#polygon(
+  stroke: (thickness: 2pt, join: "miter", miter-limit: 1.0),
+  (0pt, 0pt), (20pt, 5pt), (0pt, 10pt),
+)
+
+---
+// Side dictionaries still work and can contain stroke dictionaries.
+#rect(stroke: (left: (thickness: 2pt, dash: "loosely-dotted"), rest: 1pt))
+
+---
+// Error: 29-41 unexpected key "color", valid keys are "paint", "thickness", "cap", "join", "dash", and "miter-limit"
+#line(length: 10pt, stroke: (color: red))
+
+---
+// Error: 29-42 expected "butt", "round", or "square"
+#line(length: 10pt, stroke: (cap: "flat"))
+
+---
+// Error: 29-44 expected "solid", "dotted", "densely-dotted", "loosely-dotted", "dashed", "densely-dashed", "loosely-dashed", "dash-dotted", "densely-dash-dotted", "loosely-dash-dotted", array, dictionary, or none
+#line(length: 10pt, stroke: (dash: "dashy"))
+
+---
+// Error: 29-48 dash length must not be negative
+#line(length: 10pt, stroke: (dash: (-2pt, 1pt)))
+
+---
+// Error: 29-47 dash array must contain at least one non-zero length
+#line(length: 10pt, stroke: (dash: (0pt, 0pt)))
+
+---
+// Error: 29-47 miter limit must be at least 1.0
+#line(length: 10pt, stroke: (miter-limit: 0.5))